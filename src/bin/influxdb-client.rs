@@ -0,0 +1,19 @@
+use std::io;
+use std::process::ExitCode;
+use clap::Parser;
+use influxdb_client::cli::cli_arguments::CliArguments;
+use influxdb_client::cli::command_runner::run;
+
+#[actix_rt::main]
+async fn main() -> ExitCode {
+    match run(CliArguments::parse(), io::stdin()).await {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
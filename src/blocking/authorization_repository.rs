@@ -0,0 +1,84 @@
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_blocking_api_request;
+use crate::mapper::response_mapper::{map_blocking_json_response, map_blocking_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::authorization::{Authorization, Authorizations};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+
+pub fn list_authorizations(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig
+) -> Result<Vec<Authorization>, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "authorizations");
+    let result = get_blocking_api_request(influxdb_token.into(), Method::GET, url)
+        .query(&[("org", &influxdb_config.organisation)])
+        .send();
+    let authorizations: Authorizations = map_blocking_json_response(result)?;
+    Ok(authorizations.authorizations)
+}
+
+pub fn create_authorization(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    authorization: &Authorization
+) -> Result<Authorization, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "authorizations");
+    let result = get_blocking_api_request(influxdb_token.into(), Method::POST, url)
+        .json(authorization)
+        .send();
+    map_blocking_json_response(result)
+}
+
+pub fn delete_authorization(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    authorization_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("authorizations/{}", authorization_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send();
+    map_blocking_response(result).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::authorization::Permission;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    #[actix_rt::test]
+    async fn list_authorizations_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result = actix_rt::task::spawn_blocking(move || {
+            list_authorizations("token".to_string(), &test_config(url))
+        }).await.unwrap().unwrap();
+        assert_eq!(1, result.len());
+        assert_eq!(vec![Permission::bucket("write", "org-id", "bucket-id")], result[0].permissions);
+    }
+
+    #[actix_rt::test]
+    async fn create_and_delete_authorization_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result = actix_rt::task::spawn_blocking(move || {
+            let config = test_config(url);
+            let authorization = Authorization {
+                id: None,
+                org_id: "org-id".to_string(),
+                description: Some("reader".to_string()),
+                status: None,
+                token: None,
+                permissions: vec![Permission::bucket("read", "org-id", "bucket-id")],
+            };
+            let authorization = create_authorization("token".to_string(), &config, &authorization)?;
+            delete_authorization("token".to_string(), &config, authorization.id.as_deref().unwrap_or_default())?;
+            Ok::<_, InfluxDbError<Option<Error>>>(authorization)
+        }).await.unwrap().unwrap();
+        assert_eq!(Some("new-auth-id".to_string()), result.id);
+        assert_eq!(Some("new-token".to_string()), result.token);
+    }
+}
@@ -0,0 +1,91 @@
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_blocking_api_request;
+use crate::mapper::response_mapper::{map_blocking_json_response, map_blocking_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::bucket::{Bucket, Buckets};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+
+const PAGE_SIZE: usize = 100;
+
+pub fn list_buckets(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig
+) -> Result<Vec<Bucket>, InfluxDbError<Option<Error>>> {
+    let influxdb_token = influxdb_token.into();
+    let url = to_influxdb_api_url(influxdb_config, "buckets");
+    let mut buckets = vec![];
+    loop {
+        let result = get_blocking_api_request(influxdb_token.clone(), Method::GET, url.clone())
+            .query(&[("org", &influxdb_config.organisation)])
+            .query(&[("limit", PAGE_SIZE), ("offset", buckets.len())])
+            .send();
+        let page: Buckets = map_blocking_json_response(result)?;
+        let count = page.buckets.len();
+        buckets.extend(page.buckets);
+        if count < PAGE_SIZE {
+            return Ok(buckets);
+        }
+    }
+}
+
+pub fn create_bucket(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    bucket: &Bucket
+) -> Result<Bucket, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "buckets");
+    let result = get_blocking_api_request(influxdb_token.into(), Method::POST, url)
+        .json(bucket)
+        .send();
+    map_blocking_json_response(result)
+}
+
+pub fn delete_bucket(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    bucket_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("buckets/{}", bucket_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send();
+    map_blocking_response(result).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    #[actix_rt::test]
+    async fn list_buckets_reads_every_page() {
+        let harness = setup_test_harness();
+        let url = harness.url("paged");
+        let result = actix_rt::task::spawn_blocking(move || {
+            list_buckets("token".to_string(), &test_config(url))
+        }).await.unwrap();
+        assert_eq!(101, result.unwrap().len());
+    }
+
+    #[actix_rt::test]
+    async fn create_and_delete_bucket_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result = actix_rt::task::spawn_blocking(move || {
+            let config = test_config(url);
+            let bucket = Bucket {
+                id: None,
+                org_id: "org-id".to_string(),
+                name: "new".to_string(),
+                description: None,
+                retention_rules: vec![],
+            };
+            let bucket = create_bucket("token".to_string(), &config, &bucket)?;
+            delete_bucket("token".to_string(), &config, bucket.id.as_deref().unwrap_or_default())?;
+            Ok::<_, InfluxDbError<Option<Error>>>(bucket)
+        }).await.unwrap();
+        assert_eq!(Some("new-bucket-id".to_string()), result.unwrap().id);
+    }
+}
@@ -0,0 +1,111 @@
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_blocking_api_request;
+use crate::mapper::response_mapper::{map_blocking_json_response, map_blocking_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::check::{Check, Checks};
+use crate::model::secret::Secret;
+
+const PAGE_SIZE: usize = 100;
+
+pub fn list_checks(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str
+) -> Result<Vec<Check>, InfluxDbError<Option<Error>>> {
+    let influxdb_token = influxdb_token.into();
+    let url = to_influxdb_api_url(influxdb_config, "checks");
+    let mut checks = vec![];
+    loop {
+        let result = get_blocking_api_request(influxdb_token.clone(), Method::GET, url.clone())
+            .query(&[("orgID", org_id)])
+            .query(&[("limit", PAGE_SIZE), ("offset", checks.len())])
+            .send();
+        let page: Checks = map_blocking_json_response(result)?;
+        let count = page.checks.len();
+        checks.extend(page.checks);
+        if count < PAGE_SIZE {
+            return Ok(checks);
+        }
+    }
+}
+
+pub fn get_check(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    check_id: &str
+) -> Result<Check, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("checks/{}", check_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::GET, url)
+        .send();
+    map_blocking_json_response(result)
+}
+
+pub fn create_check(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    check: &Check
+) -> Result<Check, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "checks");
+    let result = get_blocking_api_request(influxdb_token.into(), Method::POST, url)
+        .json(check)
+        .send();
+    map_blocking_json_response(result)
+}
+
+pub fn update_check(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    check_id: &str,
+    check: &Check
+) -> Result<Check, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("checks/{}", check_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::PUT, url)
+        .json(check)
+        .send();
+    map_blocking_json_response(result)
+}
+
+pub fn delete_check(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    check_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("checks/{}", check_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send();
+    map_blocking_response(result).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::check::CheckStatusLevel;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    #[actix_rt::test]
+    async fn list_checks_reads_every_page() {
+        let harness = setup_test_harness();
+        let url = harness.url("paged");
+        let result = actix_rt::task::spawn_blocking(move || {
+            list_checks("token".to_string(), &test_config(url), "org-id")
+        }).await.unwrap();
+        assert_eq!(101, result.unwrap().len());
+    }
+
+    #[actix_rt::test]
+    async fn create_and_delete_check_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result = actix_rt::task::spawn_blocking(move || {
+            let config = test_config(url);
+            let deadman = Check::deadman("org-id", "heartbeat", "from(bucket: \"telegraf\")", "1m", "90s", CheckStatusLevel::Crit);
+            let check = create_check("token".to_string(), &config, &deadman)?;
+            delete_check("token".to_string(), &config, "new-id")?;
+            Ok::<_, InfluxDbError<Option<Error>>>(check)
+        }).await.unwrap();
+        assert_eq!(Some("new-id".to_string()), result.unwrap().id);
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+use reqwest::Error;
+use crate::credentials::credential_provider::CredentialProvider;
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::response_mapper::is_unauthorized;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+use crate::telemetry::client_stats::record_retry;
+
+pub fn with_credentials<T, F>(
+    credentials: &Arc<dyn CredentialProvider>,
+    influxdb_config: &InfluxdbConfig,
+    request: F
+) -> Result<T, InfluxDbError<Option<Error>>>
+where
+    F: Fn(Secret) -> Result<T, InfluxDbError<Option<Error>>>,
+{
+    match request(load(credentials)?) {
+        Err(error) if is_unauthorized(&error) => {
+            credentials.invalidate();
+            record_retry(&influxdb_config.address, 1);
+            request(load(credentials)?)
+        }
+        result => result,
+    }
+}
+
+fn load(credentials: &Arc<dyn CredentialProvider>) -> Result<Secret, InfluxDbError<Option<Error>>> {
+    credentials
+        .credentials()
+        .map_err(|error| InfluxDbError::Failed(None, error.to_string()))
+}
@@ -0,0 +1,160 @@
+use log::warn;
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_blocking_api_request;
+use crate::mapper::response_mapper::{map_blocking_json_response, map_blocking_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::bucket::Bucket;
+use crate::model::dbrp::{Dbrp, DbrpFilter, DbrpResponse, DbrpUpdate, Dbrps};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+use crate::blocking::bucket_repository::{create_bucket, delete_bucket};
+
+pub fn list_dbrps(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str,
+    filter: &DbrpFilter
+) -> Result<Vec<Dbrp>, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "dbrps");
+    let result = get_blocking_api_request(influxdb_token.into(), Method::GET, url)
+        .query(&[("orgID", org_id)])
+        .query(filter)
+        .send();
+    let dbrps: Dbrps = map_blocking_json_response(result)?;
+    Ok(dbrps.content)
+}
+
+pub fn get_dbrp(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str,
+    dbrp_id: &str
+) -> Result<Dbrp, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("dbrps/{}", dbrp_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::GET, url)
+        .query(&[("orgID", org_id)])
+        .send();
+    let response: DbrpResponse = map_blocking_json_response(result)?;
+    Ok(response.content)
+}
+
+pub fn create_dbrp(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    dbrp: &Dbrp
+) -> Result<Dbrp, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "dbrps");
+    let result = get_blocking_api_request(influxdb_token.into(), Method::POST, url)
+        .json(dbrp)
+        .send();
+    map_blocking_json_response(result)
+}
+
+pub fn update_dbrp(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str,
+    dbrp_id: &str,
+    update: &DbrpUpdate
+) -> Result<Dbrp, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("dbrps/{}", dbrp_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::PATCH, url)
+        .query(&[("orgID", org_id)])
+        .json(update)
+        .send();
+    let response: DbrpResponse = map_blocking_json_response(result)?;
+    Ok(response.content)
+}
+
+pub fn set_default_dbrp(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str,
+    dbrp_id: &str
+) -> Result<Dbrp, InfluxDbError<Option<Error>>> {
+    let update = DbrpUpdate {
+        retention_policy: None,
+        default: Some(true),
+    };
+    update_dbrp(influxdb_token, influxdb_config, org_id, dbrp_id, &update)
+}
+
+pub fn delete_dbrp(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str,
+    dbrp_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("dbrps/{}", dbrp_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::DELETE, url)
+        .query(&[("orgID", org_id)])
+        .send();
+    map_blocking_response(result).map(|_| ())
+}
+
+pub fn create_bucket_with_dbrp(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    bucket: &Bucket,
+    database: &str,
+    retention_policy: &str
+) -> Result<(Bucket, Dbrp), InfluxDbError<Option<Error>>> {
+    let influxdb_token = influxdb_token.into();
+    let bucket = create_bucket(&influxdb_token, influxdb_config, bucket)?;
+    let bucket_id = bucket
+        .id
+        .clone()
+        .ok_or_else(|| InfluxDbError::Failed(None, "created bucket has no id".to_string()))?;
+    let dbrp = Dbrp::new(&bucket.org_id, &bucket_id, database, retention_policy).as_default();
+    match create_dbrp(&influxdb_token, influxdb_config, &dbrp) {
+        Ok(dbrp) => Ok((bucket, dbrp)),
+        Err(error) => {
+            if let Err(cleanup) = delete_bucket(influxdb_token, influxdb_config, &bucket_id) {
+                warn!("Cannot delete bucket {} after failed dbrp creation: {}", bucket_id, cleanup);
+            }
+            Err(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    #[actix_rt::test]
+    async fn list_and_set_default_dbrp_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result = actix_rt::task::spawn_blocking(move || {
+            let config = test_config(url);
+            let dbrps = list_dbrps("token".to_string(), &config, "org-id", &DbrpFilter::default())?;
+            let dbrp = set_default_dbrp("token".to_string(), &config, "org-id", "dbrp-id")?;
+            Ok::<_, InfluxDbError<Option<Error>>>((dbrps, dbrp))
+        }).await.unwrap();
+        let (dbrps, dbrp) = result.unwrap();
+        assert_eq!(1, dbrps.len());
+        assert!(dbrp.default);
+    }
+
+    #[actix_rt::test]
+    async fn create_bucket_with_dbrp_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result = actix_rt::task::spawn_blocking(move || {
+            let bucket = Bucket {
+                id: None,
+                org_id: "org-id".to_string(),
+                name: "new".to_string(),
+                description: None,
+                retention_rules: vec![],
+            };
+            create_bucket_with_dbrp("token".to_string(), &test_config(url), &bucket, "telegraf", "autogen")
+        }).await.unwrap();
+        let (bucket, dbrp) = result.unwrap();
+        assert_eq!(Some("new-bucket-id".to_string()), bucket.id);
+        assert_eq!("new-bucket-id", dbrp.bucket_id);
+    }
+}
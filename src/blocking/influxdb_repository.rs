@@ -0,0 +1,150 @@
+use std::sync::Arc;
+use std::time::Instant;
+use log::debug;
+use reqwest::Error;
+use crate::blocking::credential_request::with_credentials;
+use crate::credentials::credential_provider::CredentialProvider;
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_blocking_request;
+use crate::mapper::response_mapper::{map_blocking_response, outcome, to_blocking_error_kind};
+use crate::mapper::url_mapper::{to_influxdb_read_url, to_influxdb_write_url};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+use crate::repository::request_limiter::{acquire_blocking, RequestKind};
+use crate::telemetry::client_stats::{record_query, record_write, WriteSize};
+
+pub fn write_to_influxdb(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    body: String
+) -> Result<String, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_write_url(influxdb_config);
+    debug!("Using body {:#?}", body);
+    let _permit = acquire_blocking(influxdb_config, RequestKind::Write, body.len());
+    let size = WriteSize::of(&body);
+    let started = Instant::now();
+    let result = get_blocking_request(influxdb_token.into(), url, body)
+        .send();
+    debug!("Result {:#?}", result);
+    let error_kind = to_blocking_error_kind(&result);
+    let result = map_blocking_response(result);
+    record_write(&influxdb_config.address, size, started, outcome(error_kind, &result));
+    result
+}
+
+pub fn read_from_influxdb(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    body: String
+) -> Result<String, InfluxDbError<Option<Error>>> {
+    debug!("Body: {}", body);
+    let url = to_influxdb_read_url(influxdb_config);
+    let _permit = acquire_blocking(influxdb_config, RequestKind::Query, body.len());
+    let started = Instant::now();
+    let result = get_blocking_request(influxdb_token.into(), url, body)
+        .header("Content-Type", "application/vnd.flux")
+        .send();
+    let error_kind = to_blocking_error_kind(&result);
+    let result = map_blocking_response(result);
+    let bytes = result.as_ref().map(String::len).unwrap_or_default();
+    record_query(&influxdb_config.address, bytes, started, outcome(error_kind, &result));
+    result
+}
+
+pub fn write_with_credentials(
+    credentials: &Arc<dyn CredentialProvider>,
+    influxdb_config: &InfluxdbConfig,
+    body: String
+) -> Result<String, InfluxDbError<Option<Error>>> {
+    with_credentials(credentials, influxdb_config, |influxdb_token| write_to_influxdb(influxdb_token, influxdb_config, body.clone()))
+}
+
+pub fn read_with_credentials(
+    credentials: &Arc<dyn CredentialProvider>,
+    influxdb_config: &InfluxdbConfig,
+    body: String
+) -> Result<String, InfluxDbError<Option<Error>>> {
+    with_credentials(credentials, influxdb_config, |influxdb_token| read_from_influxdb(influxdb_token, influxdb_config, body.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::static_credentials::StaticCredentials;
+    use crate::test_support::fake_influxdb::FakeInfluxDb;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    #[actix_rt::test]
+    async fn read_from_influxdb_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result = actix_rt::task::spawn_blocking(move || {
+            read_from_influxdb("token".to_string(), &test_config(url), "body".to_string())
+        }).await.unwrap();
+        assert!(result.is_ok());
+        assert_eq!("test", result.unwrap().to_string());
+    }
+
+    #[actix_rt::test]
+    async fn read_from_influxdb_error_with_body() {
+        let harness = setup_test_harness();
+        let url = harness.url("fails-body-response");
+        let result = actix_rt::task::spawn_blocking(move || {
+            read_from_influxdb("token".to_string(), &test_config(url), "body".to_string())
+        }).await.unwrap();
+        assert!(result.is_err());
+        assert_eq!("Rest call failed Some terrible error", result.unwrap_err().to_string());
+    }
+
+    #[actix_rt::test]
+    async fn write_to_influxdb_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result = actix_rt::task::spawn_blocking(move || {
+            write_to_influxdb("token".to_string(), &test_config(url), "body".to_string())
+        }).await.unwrap();
+        assert!(result.is_ok());
+        assert_eq!("test", result.unwrap().to_string());
+    }
+
+    #[actix_rt::test]
+    async fn write_to_influxdb_error_no_body() {
+        let harness = setup_test_harness();
+        let url = harness.url("fails");
+        let result = actix_rt::task::spawn_blocking(move || {
+            write_to_influxdb("token".to_string(), &test_config(url), "body".to_string())
+        }).await.unwrap();
+        assert!(result.is_err());
+        assert_eq!("Rest call failed 500 Internal Server Error", result.unwrap_err().to_string());
+    }
+
+    #[actix_rt::test]
+    async fn write_to_influxdb_failed_request() {
+        let harness = setup_test_harness();
+        let url = harness.url("some-bad-url");
+        let result = actix_rt::task::spawn_blocking(move || {
+            write_to_influxdb("token".to_string(), &test_config(url), "body".to_string())
+        }).await.unwrap();
+        assert!(result.is_err());
+        assert_eq!("Rest call failed 404 Not Found", result.unwrap_err().to_string());
+    }
+
+    #[actix_rt::test]
+    async fn write_and_read_with_credentials() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let config = fake.config("organisation", "bucket");
+        let result = actix_rt::task::spawn_blocking(move || {
+            let credentials: Arc<dyn CredentialProvider> = Arc::new(StaticCredentials::new("token"));
+            write_with_credentials(&credentials, &config, "cpu usage=1 1".to_string())?;
+            let flux = read_with_credentials(&credentials, &config, r#"from(bucket: "bucket") |> range(start: 0)"#.to_string())?;
+            let wrong: Arc<dyn CredentialProvider> = Arc::new(StaticCredentials::new("wrong"));
+            let result = write_with_credentials(&wrong, &config, "cpu usage=2 2".to_string());
+            Ok::<_, InfluxDbError<Option<Error>>>((flux, result))
+        }).await.unwrap();
+        let (flux, result) = result.unwrap();
+        assert!(flux.contains("usage"));
+        assert!(result.is_err());
+        assert_eq!(4, fake.request_count());
+    }
+}
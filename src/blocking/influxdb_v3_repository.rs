@@ -0,0 +1,160 @@
+use std::time::Instant;
+use log::debug;
+use reqwest::Error;
+use serde::de::DeserializeOwned;
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::influxdb_payload_mapper::InfluxDbPayloadMapper;
+use crate::mapper::influxdb_v3_mapper::{map_v3_rows, to_influxdb_v3_query_body};
+use crate::mapper::request_mapper::get_blocking_v3_request;
+use crate::mapper::response_mapper::{map_blocking_bytes_response, map_blocking_response, outcome, to_blocking_error_kind};
+use crate::mapper::url_mapper::{to_influxdb_v3_query_url, to_influxdb_v3_write_url};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::influxdb_v3_query::{InfluxDbV3Query, QueryFormat};
+use crate::model::influxdb_v3_write_options::InfluxDbV3WriteOptions;
+use crate::model::secret::Secret;
+use crate::repository::request_limiter::{acquire_blocking, RequestKind};
+use crate::telemetry::client_stats::{record_query, record_write, WriteSize};
+
+pub fn write_to_influxdb_v3(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    options: &InfluxDbV3WriteOptions,
+    body: String
+) -> Result<String, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_v3_write_url(influxdb_config, options);
+    debug!("Using body {:#?}", body);
+    let _permit = acquire_blocking(influxdb_config, RequestKind::Write, body.len());
+    let size = WriteSize::of(&body);
+    let started = Instant::now();
+    let result = get_blocking_v3_request(influxdb_token.into(), url, body)
+        .header("Content-Type", "text/plain; charset=utf-8")
+        .send();
+    debug!("Result {:#?}", result);
+    let error_kind = to_blocking_error_kind(&result);
+    let result = map_blocking_response(result);
+    record_write(&influxdb_config.address, size, started, outcome(error_kind, &result));
+    result
+}
+
+pub fn write_items_to_influxdb_v3<T>(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    options: &InfluxDbV3WriteOptions,
+    mapper: &dyn InfluxDbPayloadMapper<T>,
+    payloads: Vec<T>
+) -> Result<String, InfluxDbError<Option<Error>>> {
+    write_to_influxdb_v3(influxdb_token, influxdb_config, options, mapper.items(payloads))
+}
+
+pub fn query_influxdb_v3<R: DeserializeOwned>(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    query: &InfluxDbV3Query
+) -> Result<Vec<R>, InfluxDbError<Option<Error>>> {
+    let body = send_query(influxdb_token, influxdb_config, query)?;
+    map_v3_rows(query.format, &body)
+}
+
+pub fn query_influxdb_v3_parquet(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    query: &InfluxDbV3Query
+) -> Result<Vec<u8>, InfluxDbError<Option<Error>>> {
+    let query = query.clone().with_format(QueryFormat::Parquet);
+    send_query(influxdb_token, influxdb_config, &query)
+}
+
+fn send_query(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    query: &InfluxDbV3Query
+) -> Result<Vec<u8>, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_v3_query_url(influxdb_config, query.language);
+    let body = to_influxdb_v3_query_body(influxdb_config, query);
+    debug!("Body: {}", body);
+    let _permit = acquire_blocking(influxdb_config, RequestKind::Query, body.len());
+    let started = Instant::now();
+    let result = get_blocking_v3_request(influxdb_token.into(), url, body)
+        .header("Content-Type", "application/json")
+        .send();
+    let error_kind = to_blocking_error_kind(&result);
+    let result = map_blocking_bytes_response(result);
+    let bytes = result.as_ref().map(Vec::len).unwrap_or_default();
+    record_query(&influxdb_config.address, bytes, started, outcome(error_kind, &result));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Row {
+        host: String,
+        usage: f64,
+    }
+
+    #[actix_rt::test]
+    async fn write_to_influxdb_v3_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result = actix_rt::task::spawn_blocking(move || {
+            write_to_influxdb_v3(
+                "token".to_string(),
+                &test_config(url),
+                &InfluxDbV3WriteOptions::default(),
+                "cpu,host=a usage=1.5".to_string()
+            )
+        }).await.unwrap();
+        assert!(result.is_ok());
+        assert_eq!("test", result.unwrap().to_string());
+    }
+
+    #[actix_rt::test]
+    async fn query_influxdb_v3_sql_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result: Result<Vec<Row>, _> = actix_rt::task::spawn_blocking(move || {
+            query_influxdb_v3(
+                "token".to_string(),
+                &test_config(url),
+                &InfluxDbV3Query::sql("SELECT host, usage FROM cpu")
+            )
+        }).await.unwrap();
+        assert!(result.is_ok());
+        assert_eq!(vec![Row { host: "a".to_string(), usage: 1.5 }], result.unwrap());
+    }
+
+    #[actix_rt::test]
+    async fn query_influxdb_v3_error_no_body() {
+        let harness = setup_test_harness();
+        let url = harness.url("fails");
+        let result: Result<Vec<Row>, _> = actix_rt::task::spawn_blocking(move || {
+            query_influxdb_v3(
+                "token".to_string(),
+                &test_config(url),
+                &InfluxDbV3Query::sql("SELECT host, usage FROM cpu")
+            )
+        }).await.unwrap();
+        assert!(result.is_err());
+        assert_eq!("Rest call failed 500 Internal Server Error", result.unwrap_err().to_string());
+    }
+
+    #[actix_rt::test]
+    async fn query_influxdb_v3_parquet_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result = actix_rt::task::spawn_blocking(move || {
+            query_influxdb_v3_parquet(
+                "token".to_string(),
+                &test_config(url),
+                &InfluxDbV3Query::sql("SELECT host, usage FROM cpu")
+            )
+        }).await.unwrap();
+        assert!(result.is_ok());
+        assert_eq!("PAR1".as_bytes(), result.unwrap().as_slice());
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_blocking_api_request;
+use crate::mapper::response_mapper::{map_blocking_json_response, map_blocking_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::label::{Label, LabelMapping, LabelResource, LabelResponse, LabelledResource, Labels};
+use crate::model::secret::Secret;
+
+const PAGE_SIZE: usize = 100;
+
+pub fn list_labels(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig
+) -> Result<Vec<Label>, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "labels");
+    let result = get_blocking_api_request(influxdb_token.into(), Method::GET, url)
+        .send();
+    let labels: Labels = map_blocking_json_response(result)?;
+    Ok(labels.labels)
+}
+
+pub fn get_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    label_id: &str
+) -> Result<Label, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("labels/{}", label_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::GET, url)
+        .send();
+    let response: LabelResponse = map_blocking_json_response(result)?;
+    Ok(response.label)
+}
+
+pub fn create_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    label: &Label
+) -> Result<Label, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "labels");
+    let result = get_blocking_api_request(influxdb_token.into(), Method::POST, url)
+        .json(label)
+        .send();
+    let response: LabelResponse = map_blocking_json_response(result)?;
+    Ok(response.label)
+}
+
+pub fn update_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    label_id: &str,
+    label: &Label
+) -> Result<Label, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("labels/{}", label_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::PATCH, url)
+        .json(label)
+        .send();
+    let response: LabelResponse = map_blocking_json_response(result)?;
+    Ok(response.label)
+}
+
+pub fn delete_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    label_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("labels/{}", label_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send();
+    map_blocking_response(result).map(|_| ())
+}
+
+pub fn list_resource_labels(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    resource: LabelResource,
+    resource_id: &str
+) -> Result<Vec<Label>, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("{}/{}/labels", resource.path(), resource_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::GET, url)
+        .send();
+    let labels: Labels = map_blocking_json_response(result)?;
+    Ok(labels.labels)
+}
+
+pub fn attach_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    resource: LabelResource,
+    resource_id: &str,
+    label_id: &str
+) -> Result<Label, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("{}/{}/labels", resource.path(), resource_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::POST, url)
+        .json(&LabelMapping { label_id: label_id.to_string() })
+        .send();
+    let response: LabelResponse = map_blocking_json_response(result)?;
+    Ok(response.label)
+}
+
+pub fn detach_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    resource: LabelResource,
+    resource_id: &str,
+    label_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("{}/{}/labels/{}", resource.path(), resource_id, label_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send();
+    map_blocking_response(result).map(|_| ())
+}
+
+pub fn list_resources_by_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    resource: LabelResource,
+    label_id: &str
+) -> Result<Vec<LabelledResource>, InfluxDbError<Option<Error>>> {
+    let influxdb_token = influxdb_token.into();
+    let url = to_influxdb_api_url(influxdb_config, resource.path());
+    let mut labelled = vec![];
+    let mut offset = 0;
+    let mut after: Option<String> = None;
+    loop {
+        let request = get_blocking_api_request(influxdb_token.clone(), Method::GET, url.clone())
+            .query(&[("org", &influxdb_config.organisation)])
+            .query(&[("limit", PAGE_SIZE)]);
+        let request = match (resource, &after) {
+            (LabelResource::Tasks, Some(after)) => request.query(&[("after", after)]),
+            (LabelResource::Tasks, None) => request,
+            _ => request.query(&[("offset", offset)]),
+        };
+        let mut page: HashMap<String, serde_json::Value> = map_blocking_json_response(request.send())?;
+        let page: Vec<LabelledResource> = page
+            .remove(resource.path())
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|error| InfluxDbError::Failed(None, format!("invalid response {}", error)))?
+            .unwrap_or_default();
+        let count = page.len();
+        offset += count;
+        after = page.last().map(|resource| resource.id.clone());
+        labelled.extend(
+            page.into_iter()
+                .filter(|resource| resource.labels.iter().any(|label| label.id.as_deref() == Some(label_id)))
+        );
+        if count < PAGE_SIZE {
+            return Ok(labelled);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    #[actix_rt::test]
+    async fn list_and_get_labels_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result = actix_rt::task::spawn_blocking(move || {
+            let config = test_config(url);
+            let labels = list_labels("token".to_string(), &config)?;
+            let label = get_label("token".to_string(), &config, "label-id")?;
+            Ok::<_, InfluxDbError<Option<Error>>>((labels, label))
+        }).await.unwrap();
+        let (labels, label) = result.unwrap();
+        assert_eq!(vec![label.clone()], labels);
+        assert_eq!(Some("label-id".to_string()), label.id);
+    }
+
+    #[actix_rt::test]
+    async fn attach_and_detach_labels_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result = actix_rt::task::spawn_blocking(move || {
+            let config = test_config(url);
+            let label = attach_label("token".to_string(), &config, LabelResource::Buckets, "bucket-id", "label-id")?;
+            detach_label("token".to_string(), &config, LabelResource::Buckets, "bucket-id", "label-id")?;
+            Ok::<_, InfluxDbError<Option<Error>>>(label)
+        }).await.unwrap();
+        assert_eq!(Some("label-id".to_string()), result.unwrap().id);
+    }
+}
@@ -0,0 +1,10 @@
+pub mod authorization_repository;
+pub mod bucket_repository;
+pub mod check_repository;
+pub mod credential_request;
+pub mod dbrp_repository;
+pub mod influxdb_repository;
+pub mod influxdb_v3_repository;
+pub mod label_repository;
+pub mod organisation_repository;
+pub mod user_repository;
\ No newline at end of file
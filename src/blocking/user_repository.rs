@@ -0,0 +1,158 @@
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::{get_blocking_api_request, get_blocking_basic_request};
+use crate::mapper::response_mapper::{map_blocking_json_response, map_blocking_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+use crate::model::user::{PasswordRequest, User, Users};
+
+const PAGE_SIZE: usize = 100;
+
+pub fn list_users(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig
+) -> Result<Vec<User>, InfluxDbError<Option<Error>>> {
+    let influxdb_token = influxdb_token.into();
+    let url = to_influxdb_api_url(influxdb_config, "users");
+    let mut users = vec![];
+    loop {
+        let result = get_blocking_api_request(influxdb_token.clone(), Method::GET, url.clone())
+            .query(&[("limit", PAGE_SIZE), ("offset", users.len())])
+            .send();
+        let page: Users = map_blocking_json_response(result)?;
+        let count = page.users.len();
+        users.extend(page.users);
+        if count < PAGE_SIZE {
+            return Ok(users);
+        }
+    }
+}
+
+pub fn find_user(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    name: &str
+) -> Result<Option<User>, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "users");
+    let result = get_blocking_api_request(influxdb_token.into(), Method::GET, url)
+        .query(&[("name", name)])
+        .send();
+    let users: Users = map_blocking_json_response(result)?;
+    Ok(users.users.into_iter().find(|user| user.name == name))
+}
+
+pub fn get_user(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    user_id: &str
+) -> Result<User, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("users/{}", user_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::GET, url)
+        .send();
+    map_blocking_json_response(result)
+}
+
+pub fn current_user(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig
+) -> Result<User, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "me");
+    let result = get_blocking_api_request(influxdb_token.into(), Method::GET, url)
+        .send();
+    map_blocking_json_response(result)
+}
+
+pub fn create_user(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    user: &User
+) -> Result<User, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "users");
+    let result = get_blocking_api_request(influxdb_token.into(), Method::POST, url)
+        .json(user)
+        .send();
+    map_blocking_json_response(result)
+}
+
+pub fn update_user(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    user_id: &str,
+    user: &User
+) -> Result<User, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("users/{}", user_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::PATCH, url)
+        .json(user)
+        .send();
+    map_blocking_json_response(result)
+}
+
+pub fn delete_user(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    user_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("users/{}", user_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send();
+    map_blocking_response(result).map(|_| ())
+}
+
+pub fn set_password(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    user_id: &str,
+    password: impl Into<Secret>
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("users/{}/password", user_id));
+    let result = get_blocking_api_request(influxdb_token.into(), Method::POST, url)
+        .json(&PasswordRequest { password: password.into() })
+        .send();
+    map_blocking_response(result).map(|_| ())
+}
+
+pub fn change_password(
+    influxdb_config: &InfluxdbConfig,
+    username: &str,
+    old_password: impl Into<Secret>,
+    new_password: impl Into<Secret>
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "me/password");
+    let result = get_blocking_basic_request(username, &old_password.into(), Method::PUT, url)
+        .json(&PasswordRequest { password: new_password.into() })
+        .send();
+    map_blocking_response(result).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    #[actix_rt::test]
+    async fn list_users_reads_every_page() {
+        let harness = setup_test_harness();
+        let url = harness.url("paged");
+        let result = actix_rt::task::spawn_blocking(move || {
+            list_users("token".to_string(), &test_config(url))
+        }).await.unwrap();
+        assert_eq!(101, result.unwrap().len());
+    }
+
+    #[actix_rt::test]
+    async fn create_update_and_delete_user_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let result = actix_rt::task::spawn_blocking(move || {
+            let config = test_config(url);
+            let user = create_user("token".to_string(), &config, &User::new("bob"))?;
+            let user = update_user("token".to_string(), &config, "new-user-id", &user)?;
+            set_password("token".to_string(), &config, "new-user-id", "secret")?;
+            delete_user("token".to_string(), &config, "new-user-id")?;
+            Ok::<_, InfluxDbError<Option<Error>>>(user)
+        }).await.unwrap();
+        assert_eq!("bob", result.unwrap().name);
+    }
+}
@@ -0,0 +1 @@
+pub mod write_ahead_buffer;
\ No newline at end of file
@@ -0,0 +1,614 @@
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use log::{debug, error, warn};
+use crate::error::write_ahead_error::WriteAheadError;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::precision::Precision;
+use crate::model::secret::Secret;
+use crate::model::write_ahead_config::{OverflowPolicy, WriteAheadConfig};
+use crate::model::write_ahead_stats::WriteAheadStats;
+use crate::repository::influxdb_repository::send_write;
+use crate::mapper::response_mapper::WriteFailure;
+
+const HEADER_BYTES: u64 = 16;
+const SEGMENT_EXTENSION: &str = "wal";
+const ACK_FILE: &str = "ack";
+
+pub struct WriteAheadBuffer {
+    config: Arc<WriteAheadConfig>,
+    directory: PathBuf,
+    state: Arc<Mutex<BufferState>>,
+    replaying: AtomicBool,
+}
+
+struct BufferState {
+    segments: VecDeque<Segment>,
+    pending: VecDeque<PendingRecord>,
+    writer: Option<File>,
+    next_segment_id: u64,
+    dropped_records: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Segment {
+    id: u64,
+    size: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct PendingRecord {
+    segment: u64,
+    offset: u64,
+    length: u32,
+    written_at: u64,
+}
+
+impl PendingRecord {
+    fn end(&self) -> u64 {
+        self.offset + HEADER_BYTES + self.length as u64
+    }
+}
+
+impl WriteAheadBuffer {
+    pub fn open(config: WriteAheadConfig) -> Result<Self, WriteAheadError> {
+        let directory = PathBuf::from(&config.directory);
+        fs::create_dir_all(&directory)?;
+        let ack = read_ack(&directory)?;
+        let mut ids = vec![];
+        for entry in fs::read_dir(&directory)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some(SEGMENT_EXTENSION) {
+                continue;
+            }
+            if let Some(id) = path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.parse::<u64>().ok()) {
+                ids.push(id);
+            }
+        }
+        ids.sort_unstable();
+        let mut state = BufferState {
+            segments: VecDeque::new(),
+            pending: VecDeque::new(),
+            writer: None,
+            next_segment_id: ids.last().map(|id| id + 1).unwrap_or(0).max(ack.map(|(id, _)| id + 1).unwrap_or(0)),
+            dropped_records: 0,
+        };
+        for id in ids {
+            let (records, size) = recover_segment(&segment_path(&directory, id), id)?;
+            state.segments.push_back(Segment { id, size });
+            state.pending.extend(
+                records
+                    .into_iter()
+                    .filter(|record| ack.map(|ack| (record.segment, record.offset) >= ack).unwrap_or(true))
+            );
+        }
+        state.remove_acknowledged_segments(&directory)?;
+        debug!("Recovered {} pending records from {:?}", state.pending.len(), directory);
+        Ok(WriteAheadBuffer {
+            config: Arc::new(config),
+            directory,
+            state: Arc::new(Mutex::new(state)),
+            replaying: AtomicBool::new(false),
+        })
+    }
+
+    pub fn append(&self, body: &str) -> Result<(), WriteAheadError> {
+        append_record(&self.config, &self.directory, &self.state, body)
+    }
+
+    pub async fn replay(
+        &self,
+        influxdb_token: impl Into<Secret>,
+        influxdb_config: &InfluxdbConfig
+    ) -> Result<usize, WriteAheadError> {
+        if self.replaying.swap(true, Ordering::SeqCst) {
+            return Ok(0);
+        }
+        let _replaying = ReplayGuard(&self.replaying);
+        self.replay_pending(influxdb_token.into(), influxdb_config).await
+    }
+
+    pub async fn write_to_influxdb(
+        &self,
+        influxdb_token: impl Into<Secret>,
+        influxdb_config: &InfluxdbConfig,
+        body: String
+    ) -> Result<usize, WriteAheadError> {
+        let config = self.config.clone();
+        let directory = self.directory.clone();
+        let state = self.state.clone();
+        run_blocking(move || append_record(&config, &directory, &state, &body)).await?;
+        match self.replay(influxdb_token, influxdb_config).await {
+            Err(WriteAheadError::Write(error)) => {
+                warn!("InfluxDB unavailable, {} records queued: {}", self.stats().pending_records, error);
+                Ok(0)
+            }
+            result => result,
+        }
+    }
+
+    pub fn stats(&self) -> WriteAheadStats {
+        let state = self.state.lock().unwrap();
+        WriteAheadStats {
+            pending_records: state.pending.len(),
+            pending_bytes: state.pending.iter().map(|record| record.length as u64).sum(),
+            disk_bytes: state.disk_bytes(),
+            segments: state.segments.len(),
+            oldest_age: state
+                .pending
+                .front()
+                .map(|record| Duration::from_millis(now_millis().saturating_sub(record.written_at))),
+            dropped_records: state.dropped_records,
+        }
+    }
+
+    async fn replay_pending(
+        &self,
+        influxdb_token: Secret,
+        influxdb_config: &InfluxdbConfig
+    ) -> Result<usize, WriteAheadError> {
+        let mut delivered = 0;
+        while let Some((record, body)) = self.next_record().await? {
+            let (result, failure, _) = send_write(influxdb_token.clone(), influxdb_config, Precision::Second, body).await;
+            match (result, failure) {
+                (Ok(_), _) => delivered += 1,
+                (Err(error), Some(WriteFailure::InvalidData)) => {
+                    error!("Dropping record rejected by InfluxDB: {}", error);
+                    self.state.lock().unwrap().dropped_records += 1;
+                }
+                (Err(error), _) => return Err(WriteAheadError::Write(error)),
+            }
+            self.acknowledge(record).await?;
+        }
+        Ok(delivered)
+    }
+
+    async fn next_record(&self) -> Result<Option<(PendingRecord, String)>, WriteAheadError> {
+        let record = match self.state.lock().unwrap().pending.front() {
+            Some(record) => *record,
+            None => return Ok(None),
+        };
+        let path = segment_path(&self.directory, record.segment);
+        let body = run_blocking(move || read_record(&path, record)).await?;
+        Ok(Some((record, body)))
+    }
+
+    async fn acknowledge(&self, record: PendingRecord) -> Result<(), WriteAheadError> {
+        let state = self.state.clone();
+        let directory = self.directory.clone();
+        run_blocking(move || {
+            let mut state = state.lock().unwrap();
+            if state.pending.front() != Some(&record) {
+                return Ok(());
+            }
+            state.pending.pop_front();
+            write_ack(&directory, record.segment, record.end())?;
+            state.remove_acknowledged_segments(&directory)
+        }).await
+    }
+}
+
+struct ReplayGuard<'a>(&'a AtomicBool);
+
+impl Drop for ReplayGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+impl BufferState {
+    fn disk_bytes(&self) -> u64 {
+        self.segments.iter().map(|segment| segment.size).sum()
+    }
+
+    fn drop_oldest_segment(&mut self, directory: &Path) -> Result<(), WriteAheadError> {
+        let segment = match self.segments.pop_front() {
+            Some(segment) => segment,
+            None => return Ok(()),
+        };
+        let before = self.pending.len();
+        self.pending.retain(|record| record.segment != segment.id);
+        let dropped = before - self.pending.len();
+        self.dropped_records += dropped as u64;
+        warn!("Write ahead buffer full, dropped {} records from segment {}", dropped, segment.id);
+        if self.segments.is_empty() {
+            self.writer = None;
+        }
+        write_ack(directory, segment.id + 1, 0)?;
+        fs::remove_file(segment_path(directory, segment.id))?;
+        Ok(())
+    }
+
+    fn remove_acknowledged_segments(&mut self, directory: &Path) -> Result<(), WriteAheadError> {
+        while let Some(segment) = self.segments.front().copied() {
+            let is_active = self.segments.len() == 1 && self.writer.is_some();
+            let has_pending = self.pending.front().map(|record| record.segment == segment.id).unwrap_or(false);
+            if is_active || has_pending {
+                break;
+            }
+            if self.segments.len() == 1 {
+                self.writer = None;
+            }
+            fs::remove_file(segment_path(directory, segment.id))?;
+            self.segments.pop_front();
+        }
+        Ok(())
+    }
+}
+
+fn append_record(
+    config: &WriteAheadConfig,
+    directory: &Path,
+    state: &Mutex<BufferState>,
+    body: &str
+) -> Result<(), WriteAheadError> {
+    let payload = body.as_bytes();
+    let record_bytes = HEADER_BYTES + payload.len() as u64;
+    if record_bytes > config.max_total_bytes || payload.len() > u32::MAX as usize {
+        return Err(WriteAheadError::RecordTooLarge(record_bytes));
+    }
+    let mut state = state.lock().unwrap();
+    while state.disk_bytes() + record_bytes > config.max_total_bytes {
+        match config.overflow_policy {
+            OverflowPolicy::RejectNew => return Err(WriteAheadError::BufferFull),
+            OverflowPolicy::DropOldest => state.drop_oldest_segment(directory)?,
+        }
+    }
+    let rotate = match state.segments.back() {
+        Some(segment) => state.writer.is_none() || (segment.size > 0 && segment.size + record_bytes > config.segment_max_bytes),
+        None => true,
+    };
+    if rotate {
+        let id = state.next_segment_id;
+        state.next_segment_id += 1;
+        state.writer = Some(OpenOptions::new().create(true).append(true).open(segment_path(directory, id))?);
+        state.segments.push_back(Segment { id, size: 0 });
+    }
+    let written_at = now_millis();
+    let mut record = Vec::with_capacity(record_bytes as usize);
+    record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    record.extend_from_slice(&checksum(written_at, payload).to_le_bytes());
+    record.extend_from_slice(&written_at.to_le_bytes());
+    record.extend_from_slice(payload);
+    let writer = state.writer.as_mut().expect("segment writer");
+    writer.write_all(&record)?;
+    if config.sync_on_append {
+        writer.sync_data()?;
+    }
+    let segment = state.segments.back_mut().expect("active segment");
+    let offset = segment.size;
+    segment.size += record_bytes;
+    let id = segment.id;
+    state.pending.push_back(PendingRecord {
+        segment: id,
+        offset,
+        length: payload.len() as u32,
+        written_at,
+    });
+    Ok(())
+}
+
+fn recover_segment(path: &Path, id: u64) -> Result<(Vec<PendingRecord>, u64), WriteAheadError> {
+    let mut contents = vec![];
+    File::open(path)?.read_to_end(&mut contents)?;
+    let mut records = vec![];
+    let mut offset = 0_u64;
+    while offset + HEADER_BYTES <= contents.len() as u64 {
+        let start = offset as usize;
+        let length = u32::from_le_bytes(contents[start..start + 4].try_into().unwrap());
+        let expected = u32::from_le_bytes(contents[start + 4..start + 8].try_into().unwrap());
+        let written_at = u64::from_le_bytes(contents[start + 8..start + 16].try_into().unwrap());
+        let end = start + HEADER_BYTES as usize + length as usize;
+        if end > contents.len() || checksum(written_at, &contents[start + HEADER_BYTES as usize..end]) != expected {
+            break;
+        }
+        records.push(PendingRecord { segment: id, offset, length, written_at });
+        offset = end as u64;
+    }
+    if offset < contents.len() as u64 {
+        warn!("Truncating {} corrupt bytes from {:?}", contents.len() as u64 - offset, path);
+        let file = OpenOptions::new().write(true).open(path)?;
+        file.set_len(offset)?;
+        file.sync_all()?;
+    }
+    Ok((records, offset))
+}
+
+fn read_record(path: &Path, record: PendingRecord) -> Result<String, WriteAheadError> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(record.offset + HEADER_BYTES))?;
+    let mut payload = vec![0; record.length as usize];
+    file.read_exact(&mut payload)?;
+    Ok(String::from_utf8(payload).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?)
+}
+
+async fn run_blocking<T, F>(task: F) -> Result<T, WriteAheadError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, WriteAheadError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(io::Error::other)?
+}
+
+fn read_ack(directory: &Path) -> Result<Option<(u64, u64)>, WriteAheadError> {
+    let contents = match fs::read_to_string(directory.join(ACK_FILE)) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    let mut parts = contents.split_whitespace().map(|part| part.parse::<u64>());
+    match (parts.next(), parts.next()) {
+        (Some(Ok(segment)), Some(Ok(offset))) => Ok(Some((segment, offset))),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidData, "invalid write ahead ack file").into()),
+    }
+}
+
+fn write_ack(directory: &Path, segment: u64, offset: u64) -> Result<(), WriteAheadError> {
+    let temporary = directory.join(format!("{}.tmp", ACK_FILE));
+    let mut file = File::create(&temporary)?;
+    file.write_all(format!("{} {}\n", segment, offset).as_bytes())?;
+    file.sync_all()?;
+    fs::rename(temporary, directory.join(ACK_FILE))?;
+    Ok(())
+}
+
+fn segment_path(directory: &Path, id: u64) -> PathBuf {
+    directory.join(format!("{:020}.{}", id, SEGMENT_EXTENSION))
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_millis() as u64).unwrap_or(0)
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0_u32; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut value = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            value = if value & 1 == 1 { 0xEDB8_8320 ^ (value >> 1) } else { value >> 1 };
+            bit += 1;
+        }
+        table[index] = value;
+        index += 1;
+    }
+    table
+}
+
+fn checksum(written_at: u64, payload: &[u8]) -> u32 {
+    let mut crc = !0_u32;
+    for byte in written_at.to_le_bytes().iter().chain(payload) {
+        crc = CRC_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::fake_influxdb::{FakeFailure, FakeInfluxDb};
+
+    fn directory(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("influxdb-client-wal-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        path.to_string_lossy().to_string()
+    }
+
+    fn config(directory: &str) -> WriteAheadConfig {
+        WriteAheadConfig {
+            sync_on_append: false,
+            ..WriteAheadConfig::new(directory)
+        }
+    }
+
+    #[test]
+    fn checksum_matches_crc32() {
+        let mut crc = !0_u32;
+        for byte in "123456789".as_bytes() {
+            crc = CRC_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+        }
+        assert_eq!(0xCBF4_3926, !crc);
+    }
+
+    #[actix_rt::test]
+    async fn replays_in_order() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let directory = directory("replays_in_order");
+        let buffer = WriteAheadBuffer::open(config(&directory)).unwrap();
+        buffer.append("cpu usage=1 1").unwrap();
+        buffer.append("cpu usage=2 2").unwrap();
+        assert_eq!(2, buffer.stats().pending_records);
+        let result = buffer.replay("token".to_string(), &fake.config("organisation", "bucket")).await;
+        assert_eq!(2, result.unwrap());
+        assert_eq!(vec!["cpu usage=1 1".to_string(), "cpu usage=2 2".to_string()], fake.lines("bucket"));
+        assert_eq!(0, buffer.stats().pending_records);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn keeps_records_while_unavailable() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let directory = directory("keeps_records_while_unavailable");
+        let buffer = WriteAheadBuffer::open(config(&directory)).unwrap();
+        fake.inject_failure(FakeFailure::ServiceUnavailable);
+        let result = buffer
+            .write_to_influxdb("token".to_string(), &fake.config("organisation", "bucket"), "cpu usage=1 1".to_string())
+            .await;
+        assert_eq!(0, result.unwrap());
+        assert_eq!(1, buffer.stats().pending_records);
+        assert_eq!(0, fake.point_count("bucket"));
+        let result = buffer
+            .write_to_influxdb("token".to_string(), &fake.config("organisation", "bucket"), "cpu usage=2 2".to_string())
+            .await;
+        assert_eq!(2, result.unwrap());
+        assert_eq!(vec!["cpu usage=1 1".to_string(), "cpu usage=2 2".to_string()], fake.lines("bucket"));
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn drops_rejected_records() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let directory = directory("drops_rejected_records");
+        let buffer = WriteAheadBuffer::open(config(&directory)).unwrap();
+        buffer.append("cpu usage=").unwrap();
+        buffer.append("cpu usage=2 2").unwrap();
+        let result = buffer.replay("token".to_string(), &fake.config("organisation", "bucket")).await;
+        assert_eq!(1, result.unwrap());
+        assert_eq!(1, buffer.stats().dropped_records);
+        assert_eq!(vec!["cpu usage=2 2".to_string()], fake.lines("bucket"));
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn keeps_records_rejected_as_unauthorized() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let directory = directory("keeps_records_rejected_as_unauthorized");
+        let buffer = WriteAheadBuffer::open(config(&directory)).unwrap();
+        buffer.append("cpu usage=1 1").unwrap();
+        buffer.append("cpu usage=2 2").unwrap();
+        let result = buffer.replay("wrong".to_string(), &fake.config("organisation", "bucket")).await;
+        assert!(matches!(result, Err(WriteAheadError::Write(_))));
+        assert_eq!(2, buffer.stats().pending_records);
+        assert_eq!(0, buffer.stats().dropped_records);
+        let result = buffer.replay("token".to_string(), &fake.config("organisation", "bucket")).await;
+        assert_eq!(2, result.unwrap());
+        assert_eq!(vec!["cpu usage=1 1".to_string(), "cpu usage=2 2".to_string()], fake.lines("bucket"));
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn replays_after_cancelled_replay() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let directory = directory("replays_after_cancelled_replay");
+        let buffer = WriteAheadBuffer::open(config(&directory)).unwrap();
+        buffer.append("cpu usage=1 1").unwrap();
+        fake.inject_failure(FakeFailure::SlowResponse(Duration::from_millis(500)));
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(50),
+            buffer.replay("token".to_string(), &fake.config("organisation", "bucket"))
+        ).await;
+        assert!(cancelled.is_err());
+        let result = buffer.replay("token".to_string(), &fake.config("organisation", "bucket")).await;
+        assert_eq!(1, result.unwrap());
+        assert_eq!(0, buffer.stats().pending_records);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn recovers_after_restart() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let directory = directory("recovers_after_restart");
+        {
+            let buffer = WriteAheadBuffer::open(config(&directory)).unwrap();
+            buffer.append("cpu usage=1 1").unwrap();
+            buffer.append("cpu usage=2 2").unwrap();
+            buffer.append("cpu usage=3 3").unwrap();
+            fake.inject_failure(FakeFailure::SlowResponse(Duration::ZERO));
+            fake.inject_failure(FakeFailure::ServiceUnavailable);
+            let _ = buffer.replay("token".to_string(), &fake.config("organisation", "bucket")).await;
+        }
+        let buffer = WriteAheadBuffer::open(config(&directory)).unwrap();
+        assert_eq!(2, buffer.stats().pending_records);
+        let result = buffer.replay("token".to_string(), &fake.config("organisation", "bucket")).await;
+        assert_eq!(2, result.unwrap());
+        assert_eq!(
+            vec!["cpu usage=1 1".to_string(), "cpu usage=2 2".to_string(), "cpu usage=3 3".to_string()],
+            fake.lines("bucket")
+        );
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn truncates_torn_writes() {
+        let directory = directory("truncates_torn_writes");
+        {
+            let buffer = WriteAheadBuffer::open(config(&directory)).unwrap();
+            buffer.append("cpu usage=1 1").unwrap();
+            buffer.append("cpu usage=2 2").unwrap();
+        }
+        let path = segment_path(Path::new(&directory), 0);
+        let size = fs::metadata(&path).unwrap().len();
+        OpenOptions::new().append(true).open(&path).unwrap().write_all(&[42, 0, 0, 0, 1, 2]).unwrap();
+        let buffer = WriteAheadBuffer::open(config(&directory)).unwrap();
+        assert_eq!(2, buffer.stats().pending_records);
+        assert_eq!(size, fs::metadata(&path).unwrap().len());
+        buffer.append("cpu usage=3 3").unwrap();
+        assert_eq!(3, buffer.stats().pending_records);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn rotates_segments() {
+        let directory = directory("rotates_segments");
+        let buffer = WriteAheadBuffer::open(WriteAheadConfig {
+            segment_max_bytes: 40,
+            ..config(&directory)
+        }).unwrap();
+        buffer.append("cpu usage=1 1").unwrap();
+        buffer.append("cpu usage=2 2").unwrap();
+        buffer.append("cpu usage=3 3").unwrap();
+        let result = buffer.stats();
+        assert_eq!(3, result.segments);
+        assert_eq!(3 * (HEADER_BYTES + 13), result.disk_bytes);
+        assert_eq!(39, result.pending_bytes);
+        assert!(result.oldest_age.is_some());
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn drop_oldest_policy() {
+        let directory = directory("drop_oldest_policy");
+        let buffer = WriteAheadBuffer::open(WriteAheadConfig {
+            segment_max_bytes: 40,
+            max_total_bytes: 60,
+            ..config(&directory)
+        }).unwrap();
+        buffer.append("cpu usage=1 1").unwrap();
+        buffer.append("cpu usage=2 2").unwrap();
+        buffer.append("cpu usage=3 3").unwrap();
+        let result = buffer.stats();
+        assert_eq!(2, result.pending_records);
+        assert_eq!(1, result.dropped_records);
+        drop(buffer);
+        let buffer = WriteAheadBuffer::open(config(&directory)).unwrap();
+        assert_eq!(2, buffer.stats().pending_records);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn reject_new_policy() {
+        let directory = directory("reject_new_policy");
+        let buffer = WriteAheadBuffer::open(WriteAheadConfig {
+            max_total_bytes: 60,
+            overflow_policy: OverflowPolicy::RejectNew,
+            ..config(&directory)
+        }).unwrap();
+        buffer.append("cpu usage=1 1").unwrap();
+        buffer.append("cpu usage=2 2").unwrap();
+        let result = buffer.append("cpu usage=3 3");
+        assert_eq!("Write ahead buffer is full", result.unwrap_err().to_string());
+        assert_eq!(2, buffer.stats().pending_records);
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn rejects_oversized_record() {
+        let directory = directory("rejects_oversized_record");
+        let buffer = WriteAheadBuffer::open(WriteAheadConfig {
+            max_total_bytes: 20,
+            ..config(&directory)
+        }).unwrap();
+        let result = buffer.append("cpu usage=1 1");
+        assert_eq!("Record of 29 bytes exceeds the write ahead buffer capacity", result.unwrap_err().to_string());
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,161 @@
+use std::path::PathBuf;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use crate::model::precision::Precision;
+
+#[derive(Parser, Clone, PartialEq, Debug)]
+#[command(name = "influxdb-client", version, about = "Write, query and administer InfluxDB")]
+pub struct CliArguments {
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    #[arg(long, global = true)]
+    pub address: Option<String>,
+    #[arg(long, global = true)]
+    pub org: Option<String>,
+    #[arg(long, global = true)]
+    pub bucket: Option<String>,
+    #[arg(long, global = true)]
+    pub token: Option<String>,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Clone, PartialEq, Debug)]
+pub enum Command {
+    Write(WriteArguments),
+    Query(QueryArguments),
+    Ping,
+    #[command(subcommand)]
+    Bucket(BucketCommand),
+    #[command(subcommand)]
+    Auth(AuthCommand),
+}
+
+#[derive(Args, Clone, PartialEq, Debug)]
+pub struct WriteArguments {
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+    #[arg(long, value_enum, default_value_t = WriteFormat::Lp)]
+    pub format: WriteFormat,
+    #[arg(long, value_enum, default_value_t = PrecisionArgument::Ns)]
+    pub precision: PrecisionArgument,
+    #[arg(long, default_value_t = 5000)]
+    pub batch_size: usize,
+    #[arg(long)]
+    pub csv_config: Option<PathBuf>,
+}
+
+#[derive(Args, Clone, PartialEq, Debug)]
+pub struct QueryArguments {
+    pub query: Option<String>,
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub output: OutputFormat,
+}
+
+#[derive(Subcommand, Clone, PartialEq, Debug)]
+pub enum BucketCommand {
+    List,
+    Create {
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        retention_seconds: Option<u64>,
+        #[arg(long)]
+        description: Option<String>,
+    },
+    Delete {
+        #[arg(long)]
+        id: String,
+    },
+}
+
+#[derive(Subcommand, Clone, PartialEq, Debug)]
+pub enum AuthCommand {
+    List,
+    Create {
+        #[arg(long)]
+        description: Option<String>,
+        #[arg(long = "read-bucket")]
+        read_buckets: Vec<String>,
+        #[arg(long = "write-bucket")]
+        write_buckets: Vec<String>,
+    },
+    Delete {
+        #[arg(long)]
+        id: String,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum WriteFormat {
+    Lp,
+    Csv,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum OutputFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum PrecisionArgument {
+    S,
+    Ms,
+    Us,
+    Ns,
+}
+
+impl PrecisionArgument {
+    pub fn precision(&self) -> Precision {
+        match self {
+            PrecisionArgument::S => Precision::Second,
+            PrecisionArgument::Ms => Precision::Millisecond,
+            PrecisionArgument::Us => Precision::Microsecond,
+            PrecisionArgument::Ns => Precision::Nanosecond,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_write() {
+        let result = CliArguments::parse_from(["influxdb-client", "write", "--file", "data.lp", "--precision", "s", "--bucket", "other"]);
+        assert_eq!(Some("other".to_string()), result.bucket);
+        assert_eq!(
+            Command::Write(WriteArguments {
+                file: Some(PathBuf::from("data.lp")),
+                format: WriteFormat::Lp,
+                precision: PrecisionArgument::S,
+                batch_size: 5000,
+                csv_config: None,
+            }),
+            result.command
+        );
+    }
+
+    #[test]
+    fn parses_auth_create() {
+        let result = CliArguments::parse_from([
+            "influxdb-client", "auth", "create", "--read-bucket", "a", "--read-bucket", "b", "--write-bucket", "c",
+        ]);
+        assert_eq!(
+            Command::Auth(AuthCommand::Create {
+                description: None,
+                read_buckets: vec!["a".to_string(), "b".to_string()],
+                write_buckets: vec!["c".to_string()],
+            }),
+            result.command
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_output() {
+        assert!(CliArguments::try_parse_from(["influxdb-client", "query", "x", "--output", "xml"]).is_err());
+    }
+}
@@ -0,0 +1,282 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use crate::cli::cli_arguments::{AuthCommand, BucketCommand, CliArguments, Command, OutputFormat, QueryArguments, WriteArguments, WriteFormat};
+use crate::cli::config_loader::{load_config, load_csv_import_config, load_token};
+use crate::cli::output_mapper::{authorizations_to_text, buckets_to_text, tables_to_json, tables_to_text};
+use crate::error::cli_error::CliError;
+use crate::mapper::flux_csv_mapper::parse_flux_csv;
+use crate::mapper::line_protocol_parser::parse_line_protocol;
+use crate::model::authorization::{Authorization, Permission};
+use crate::model::bucket::{Bucket, RetentionRule};
+use crate::model::csv_import_config::CsvImportConfig;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+use crate::repository::authorization_repository::{create_authorization, delete_authorization, list_authorizations};
+use crate::repository::bucket_repository::{create_bucket, delete_bucket, list_buckets};
+use crate::repository::health_repository::ping;
+use crate::repository::influxdb_repository::{read_from_influxdb, send_write};
+use crate::repository::organisation_repository::find_organisation_id;
+use crate::writer::csv_importer::import_csv;
+
+pub async fn run(arguments: CliArguments, input: impl Read) -> Result<String, CliError> {
+    let influxdb_config = load_config(&arguments)?;
+    if arguments.command == Command::Ping {
+        ping(&influxdb_config).await?;
+        return Ok("OK".to_string());
+    }
+    let influxdb_token = load_token(&arguments, &influxdb_config)?;
+    run_command(arguments.command, influxdb_token, &influxdb_config, input).await
+}
+
+pub (crate) async fn run_command(
+    command: Command,
+    influxdb_token: Secret,
+    influxdb_config: &InfluxdbConfig,
+    input: impl Read
+) -> Result<String, CliError> {
+    match command {
+        Command::Write(arguments) => write(arguments, influxdb_token, influxdb_config, input).await,
+        Command::Query(arguments) => query(arguments, influxdb_token, influxdb_config, input).await,
+        Command::Ping => ping(influxdb_config).await.map(|_| "OK".to_string()).map_err(CliError::from),
+        Command::Bucket(command) => bucket(command, influxdb_token, influxdb_config).await,
+        Command::Auth(command) => auth(command, influxdb_token, influxdb_config).await,
+    }
+}
+
+async fn write(
+    arguments: WriteArguments,
+    influxdb_token: Secret,
+    influxdb_config: &InfluxdbConfig,
+    input: impl Read
+) -> Result<String, CliError> {
+    if arguments.format == WriteFormat::Csv {
+        return write_csv(arguments, influxdb_token, influxdb_config, input).await;
+    }
+    let contents = read_input(arguments.file.as_deref(), input)?;
+    let lines: Vec<String> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect();
+    parse_line_protocol(&lines.join("\n"))?;
+    for chunk in lines.chunks(arguments.batch_size.max(1)) {
+        let (result, _, _) = send_write(influxdb_token.clone(), influxdb_config, arguments.precision.precision(), chunk.join("\n")).await;
+        result?;
+    }
+    Ok(format!("Wrote {} points", lines.len()))
+}
+
+async fn write_csv(
+    arguments: WriteArguments,
+    influxdb_token: Secret,
+    influxdb_config: &InfluxdbConfig,
+    input: impl Read
+) -> Result<String, CliError> {
+    let import_config = CsvImportConfig {
+        batch_size: arguments.batch_size,
+        ..load_csv_import_config(arguments.csv_config.as_deref())?
+    };
+    let stats = match arguments.file.as_deref() {
+        Some(path) => import_csv(influxdb_token, influxdb_config, &import_config, fs::File::open(path)?).await?,
+        None => {
+            let contents = read_input(None, input)?;
+            import_csv(influxdb_token, influxdb_config, &import_config, io::Cursor::new(contents)).await?
+        }
+    };
+    match stats.skipped_rows {
+        0 => Ok(format!("Wrote {} points", stats.written_points)),
+        skipped => Ok(format!("Wrote {} points, skipped {} rows", stats.written_points, skipped)),
+    }
+}
+
+async fn query(
+    arguments: QueryArguments,
+    influxdb_token: Secret,
+    influxdb_config: &InfluxdbConfig,
+    input: impl Read
+) -> Result<String, CliError> {
+    let flux = match arguments.query {
+        Some(query) => query,
+        None => read_input(arguments.file.as_deref(), input)?,
+    };
+    let body = read_from_influxdb(influxdb_token, influxdb_config, flux).await?;
+    match arguments.output {
+        OutputFormat::Csv => Ok(body),
+        OutputFormat::Table => Ok(tables_to_text(&parse_flux_csv(&body)?)),
+        OutputFormat::Json => Ok(tables_to_json(&parse_flux_csv(&body)?)),
+    }
+}
+
+async fn bucket(
+    command: BucketCommand,
+    influxdb_token: Secret,
+    influxdb_config: &InfluxdbConfig
+) -> Result<String, CliError> {
+    match command {
+        BucketCommand::List => Ok(buckets_to_text(&list_buckets(influxdb_token, influxdb_config).await?)),
+        BucketCommand::Create { name, retention_seconds, description } => {
+            let org_id = find_organisation_id(influxdb_token.clone(), influxdb_config).await?;
+            let bucket = Bucket {
+                id: None,
+                org_id,
+                name,
+                description,
+                retention_rules: retention_seconds
+                    .map(|every_seconds| vec![RetentionRule { rule_type: "expire".to_string(), every_seconds }])
+                    .unwrap_or_default(),
+            };
+            let created = create_bucket(influxdb_token, influxdb_config, &bucket).await?;
+            Ok(buckets_to_text(&[created]))
+        }
+        BucketCommand::Delete { id } => {
+            delete_bucket(influxdb_token, influxdb_config, &id).await?;
+            Ok(format!("Deleted bucket {}", id))
+        }
+    }
+}
+
+async fn auth(
+    command: AuthCommand,
+    influxdb_token: Secret,
+    influxdb_config: &InfluxdbConfig
+) -> Result<String, CliError> {
+    match command {
+        AuthCommand::List => Ok(authorizations_to_text(&list_authorizations(influxdb_token, influxdb_config).await?)),
+        AuthCommand::Create { description, read_buckets, write_buckets } => {
+            if read_buckets.is_empty() && write_buckets.is_empty() {
+                return Err(CliError::Config("at least one --read-bucket or --write-bucket is required".to_string()));
+            }
+            let org_id = find_organisation_id(influxdb_token.clone(), influxdb_config).await?;
+            let permissions = read_buckets
+                .iter()
+                .map(|bucket_id| Permission::bucket("read", &org_id, bucket_id))
+                .chain(write_buckets.iter().map(|bucket_id| Permission::bucket("write", &org_id, bucket_id)))
+                .collect();
+            let authorization = Authorization {
+                id: None,
+                org_id,
+                description,
+                status: None,
+                token: None,
+                permissions,
+            };
+            let created = create_authorization(influxdb_token, influxdb_config, &authorization).await?;
+            let token = created.token.clone().unwrap_or_default();
+            Ok(format!("{}\n\nToken: {}", authorizations_to_text(&[created]), token))
+        }
+        AuthCommand::Delete { id } => {
+            delete_authorization(influxdb_token, influxdb_config, &id).await?;
+            Ok(format!("Deleted authorization {}", id))
+        }
+    }
+}
+
+fn read_input(file: Option<&Path>, mut input: impl Read) -> Result<String, CliError> {
+    match file {
+        Some(path) => Ok(fs::read_to_string(path)?),
+        None => {
+            let mut contents = String::new();
+            input.read_to_string(&mut contents)?;
+            Ok(contents)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use super::*;
+    use crate::test_support::fake_influxdb::FakeInfluxDb;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    fn command(arguments: &[&str]) -> Command {
+        CliArguments::parse_from(["influxdb-client"].iter().chain(arguments.iter())).command
+    }
+
+    #[actix_rt::test]
+    async fn writes_line_protocol_in_batches() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let input = "# comment\ncpu value=1 1\n\ncpu value=2 2\ncpu value=3 3\n";
+        let result = run_command(
+            command(&["write", "--batch-size", "2", "--precision", "s"]),
+            Secret::from("token"),
+            &fake.config("organisation", "bucket"),
+            input.as_bytes()
+        ).await;
+        assert_eq!("Wrote 3 points", result.unwrap());
+        assert_eq!(3, fake.point_count("bucket"));
+        assert_eq!(2, fake.request_count());
+    }
+
+    #[actix_rt::test]
+    async fn rejects_invalid_line_protocol() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let result = run_command(command(&["write"]), Secret::from("token"), &fake.config("organisation", "bucket"), "cpu".as_bytes()).await;
+        assert!(result.is_err());
+        assert_eq!(0, fake.request_count());
+    }
+
+    #[actix_rt::test]
+    async fn writes_csv_and_queries() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let influxdb_config = fake.config("organisation", "bucket");
+        let input = "#datatype measurement,double,dateTime:RFC3339,tag\n_measurement,usage,_time,host\ncpu,1.5,2024-05-01T00:10:00Z,a\n";
+        let result = run_command(command(&["write", "--format", "csv"]), Secret::from("token"), &influxdb_config, input.as_bytes()).await;
+        assert_eq!("Wrote 1 points", result.unwrap());
+        let flux = r#"from(bucket: "bucket") |> range(start: 2024-05-01T00:00:00Z, stop: 2024-05-02T00:00:00Z)"#;
+        let result = run_command(command(&["query", flux, "--output", "json"]), Secret::from("token"), &influxdb_config, "".as_bytes()).await;
+        let rows: serde_json::Value = serde_json::from_str(&result.unwrap()).unwrap();
+        assert_eq!(1.5, rows[0]["_value"]);
+        assert_eq!("a", rows[0]["host"]);
+    }
+
+    #[actix_rt::test]
+    async fn writes_csv_with_import_config() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let path = std::env::temp_dir().join(format!("influxdb-client-cli-{}-import.json", std::process::id()));
+        fs::write(&path, r#"{"measurement": "weather", "columns": {"city": "tag", "temperature": {"field": "float"}, "at": {"time": "epoch_seconds"}}, "row_error_policy": "skip"}"#).unwrap();
+        let input = "city,temperature,at\nparis,21.5,1714521600\nrome,warm,1714521600\n";
+        let result = run_command(
+            command(&["write", "--format", "csv", "--csv-config", &path.to_string_lossy()]),
+            Secret::from("token"),
+            &fake.config("organisation", "bucket"),
+            input.as_bytes()
+        ).await;
+        assert_eq!("Wrote 1 points, skipped 1 rows", result.unwrap());
+        assert_eq!(vec!["weather,city=paris temperature=21.5 1714521600000000000".to_string()], fake.lines("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn creates_bucket() {
+        let harness = setup_test_harness();
+        let result = run_command(
+            command(&["bucket", "create", "--name", "metrics", "--retention-seconds", "60"]),
+            Secret::from("token"),
+            &test_config(harness.url("success")),
+            "".as_bytes()
+        ).await;
+        assert_eq!("ID             Name     Retention\nnew-bucket-id  metrics  60s", result.unwrap());
+    }
+
+    #[actix_rt::test]
+    async fn creates_authorization() {
+        let harness = setup_test_harness();
+        let result = run_command(
+            command(&["auth", "create", "--write-bucket", "bucket-id"]),
+            Secret::from("token"),
+            &test_config(harness.url("success")),
+            "".as_bytes()
+        ).await;
+        assert!(result.unwrap().ends_with("Token: new-token"));
+    }
+
+    #[actix_rt::test]
+    async fn pings() {
+        let harness = setup_test_harness();
+        let result = run_command(command(&["ping"]), Secret::from("token"), &test_config(harness.url("success")), "".as_bytes()).await;
+        assert_eq!("OK", result.unwrap());
+    }
+}
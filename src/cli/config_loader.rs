@@ -0,0 +1,156 @@
+use std::fs;
+use std::path::Path;
+use serde::de::DeserializeOwned;
+use crate::cli::cli_arguments::CliArguments;
+use crate::credentials::credential_provider::CredentialProvider;
+use crate::credentials::file_credentials::FileCredentials;
+use crate::error::cli_error::CliError;
+use crate::model::csv_import_config::CsvImportConfig;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+
+pub fn load_config(arguments: &CliArguments) -> Result<InfluxdbConfig, CliError> {
+    load_config_with(arguments, |name| std::env::var(name).ok())
+}
+
+pub fn load_token(arguments: &CliArguments, influxdb_config: &InfluxdbConfig) -> Result<Secret, CliError> {
+    load_token_with(arguments, influxdb_config, |name| std::env::var(name).ok())
+}
+
+pub (crate) fn load_config_with(
+    arguments: &CliArguments,
+    environment: impl Fn(&str) -> Option<String>
+) -> Result<InfluxdbConfig, CliError> {
+    let mut config = match &arguments.config {
+        Some(path) => read_config_file(path)?,
+        None => InfluxdbConfig::from_env_unchecked(&environment),
+    };
+    if let Some(address) = &arguments.address {
+        config.address = address.clone();
+    }
+    if let Some(organisation) = &arguments.org {
+        config.organisation = organisation.clone();
+    }
+    if let Some(bucket) = &arguments.bucket {
+        config.bucket = bucket.clone();
+    }
+    config.address = config.address.trim().trim_end_matches('/').to_string();
+    config.validate()?;
+    Ok(config)
+}
+
+pub (crate) fn load_token_with(
+    arguments: &CliArguments,
+    influxdb_config: &InfluxdbConfig,
+    environment: impl Fn(&str) -> Option<String>
+) -> Result<Secret, CliError> {
+    if let Some(token) = arguments.token.clone().or_else(|| environment("INFLUX_TOKEN")) {
+        return Ok(Secret::from(token));
+    }
+    if influxdb_config.influxdb_token_path.is_empty() {
+        return Err(CliError::Config("token is required".to_string()));
+    }
+    Ok(FileCredentials::new(&influxdb_config.influxdb_token_path).credentials()?)
+}
+
+pub (crate) fn load_csv_import_config(path: Option<&Path>) -> Result<CsvImportConfig, CliError> {
+    match path {
+        Some(path) => read_config_file(path),
+        None => Ok(CsvImportConfig::default()),
+    }
+}
+
+fn read_config_file<T: DeserializeOwned>(path: &Path) -> Result<T, CliError> {
+    let contents = fs::read_to_string(path)?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("toml") => toml::from_str(&contents).map_err(|error| CliError::Config(error.to_string())),
+        _ => serde_json::from_str(&contents).map_err(|error| CliError::Config(error.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use clap::Parser;
+    use super::*;
+
+    fn arguments(extra: &[&str]) -> CliArguments {
+        CliArguments::parse_from(["influxdb-client"].iter().chain(extra.iter()).chain(["ping"].iter()))
+    }
+
+    fn environment(values: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let values: HashMap<String, String> = values.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect();
+        move |name| values.get(name).cloned()
+    }
+
+    fn file(name: &str, contents: &str) -> String {
+        let path = std::env::temp_dir().join(format!("influxdb-client-cli-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn loads_from_environment() {
+        let result = load_config_with(
+            &arguments(&["--bucket", "override"]),
+            environment(&[("INFLUX_HOST", "http://localhost:8086/"), ("INFLUX_ORG", "org"), ("INFLUX_BUCKET", "bucket")])
+        ).unwrap();
+        assert_eq!(
+            InfluxdbConfig {
+                address: "http://localhost:8086".to_string(),
+                organisation: "org".to_string(),
+                bucket: "override".to_string(),
+                influxdb_token_path: "".to_string(),
+                limits: None,
+            },
+            result
+        );
+    }
+
+    #[test]
+    fn loads_toml_file() {
+        let path = file("config.toml", "address = \"http://toml:8086\"\norganisation = \"org\"\nbucket = \"bucket\"\ninfluxdb_token_path = \"\"\n");
+        let result = load_config_with(&arguments(&["--config", &path]), environment(&[])).unwrap();
+        assert_eq!("http://toml:8086", result.address);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn loads_json_file() {
+        let path = file("config.json", r#"{"address":"http://json:8086","organisation":"org","bucket":"bucket","influxdb_token_path":""}"#);
+        let result = load_config_with(&arguments(&["--config", &path]), environment(&[])).unwrap();
+        assert_eq!("http://json:8086", result.address);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reports_every_problem() {
+        let result = load_config_with(&arguments(&[]), environment(&[]));
+        assert_eq!(
+            "Invalid configuration: address is required; organisation is required; bucket is required",
+            result.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_address() {
+        let result = load_config_with(&arguments(&["--address", "localhost:8086", "--org", "org", "--bucket", "bucket"]), environment(&[]));
+        assert_eq!("Invalid configuration: address 'localhost:8086' is not a valid http or https url", result.unwrap_err().to_string());
+    }
+
+    #[test]
+    fn loads_token_from_file() {
+        let path = file("token", "secret\n");
+        let config = InfluxdbConfig {
+            address: "address".to_string(),
+            organisation: "org".to_string(),
+            bucket: "bucket".to_string(),
+            influxdb_token_path: path.clone(),
+            limits: None,
+        };
+        assert_eq!("secret", load_token_with(&arguments(&[]), &config, environment(&[])).unwrap().expose());
+        assert_eq!("env", load_token_with(&arguments(&[]), &config, environment(&[("INFLUX_TOKEN", "env")])).unwrap().expose());
+        assert_eq!("flag", load_token_with(&arguments(&["--token", "flag"]), &config, environment(&[("INFLUX_TOKEN", "env")])).unwrap().expose());
+        fs::remove_file(path).unwrap();
+    }
+}
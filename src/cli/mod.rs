@@ -0,0 +1,4 @@
+pub mod cli_arguments;
+pub mod command_runner;
+pub mod config_loader;
+pub mod output_mapper;
\ No newline at end of file
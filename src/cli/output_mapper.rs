@@ -0,0 +1,148 @@
+use serde_json::{Map, Value};
+use crate::model::authorization::Authorization;
+use crate::model::bucket::Bucket;
+use crate::model::flux_table::{FluxColumn, FluxTable};
+
+pub fn tables_to_text(tables: &[FluxTable]) -> String {
+    tables
+        .iter()
+        .map(|table| {
+            let header = table.columns.iter().map(|column| column.name.clone()).collect();
+            to_aligned(header, table.rows.clone())
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+pub fn tables_to_json(tables: &[FluxTable]) -> String {
+    let rows: Vec<Value> = tables
+        .iter()
+        .flat_map(|table| {
+            table.rows.iter().map(|row| {
+                let mut object = Map::new();
+                for (column, value) in table.columns.iter().zip(row) {
+                    object.insert(column.name.clone(), to_json_value(column, value));
+                }
+                Value::Object(object)
+            })
+        })
+        .collect();
+    Value::Array(rows).to_string()
+}
+
+pub fn buckets_to_text(buckets: &[Bucket]) -> String {
+    let rows = buckets
+        .iter()
+        .map(|bucket| {
+            let retention = bucket
+                .retention_rules
+                .first()
+                .filter(|rule| rule.every_seconds > 0)
+                .map(|rule| format!("{}s", rule.every_seconds))
+                .unwrap_or_else(|| "infinite".to_string());
+            vec![bucket.id.clone().unwrap_or_default(), bucket.name.clone(), retention]
+        })
+        .collect();
+    to_aligned(vec!["ID".to_string(), "Name".to_string(), "Retention".to_string()], rows)
+}
+
+pub fn authorizations_to_text(authorizations: &[Authorization]) -> String {
+    let rows = authorizations
+        .iter()
+        .map(|authorization| {
+            let permissions = authorization
+                .permissions
+                .iter()
+                .map(|permission| match &permission.resource.id {
+                    Some(id) => format!("{}:{}/{}", permission.action, permission.resource.resource_type, id),
+                    None => format!("{}:{}", permission.action, permission.resource.resource_type),
+                })
+                .collect::<Vec<String>>()
+                .join(",");
+            vec![
+                authorization.id.clone().unwrap_or_default(),
+                authorization.description.clone().unwrap_or_default(),
+                authorization.status.clone().unwrap_or_default(),
+                permissions,
+            ]
+        })
+        .collect();
+    to_aligned(
+        vec!["ID".to_string(), "Description".to_string(), "Status".to_string(), "Permissions".to_string()],
+        rows
+    )
+}
+
+fn to_json_value(column: &FluxColumn, value: &str) -> Value {
+    if value.is_empty() {
+        return Value::Null;
+    }
+    let parsed = match column.data_type.as_str() {
+        "long" => value.parse::<i64>().ok().map(Value::from),
+        "unsignedLong" => value.parse::<u64>().ok().map(Value::from),
+        "double" => value.parse::<f64>().ok().map(Value::from),
+        "boolean" => value.parse::<bool>().ok().map(Value::from),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| Value::String(value.to_string()))
+}
+
+fn to_aligned(header: Vec<String>, rows: Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|name| name.chars().count()).collect();
+    for row in &rows {
+        for (index, value) in row.iter().enumerate() {
+            if let Some(width) = widths.get_mut(index) {
+                *width = (*width).max(value.chars().count());
+            }
+        }
+    }
+    std::iter::once(&header)
+        .chain(rows.iter())
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(value, width)| format!("{:width$}", value, width = width))
+                .collect::<Vec<String>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapper::flux_csv_mapper::parse_flux_csv;
+    use crate::model::bucket::RetentionRule;
+
+    const BODY: &str = "#datatype,string,long,double,string\n#group,false,false,false,true\n#default,_result,,,\n,result,table,_value,host\n,,0,1.5,a\n,,0,,b\n";
+
+    #[test]
+    fn maps_tables_to_text() {
+        let result = tables_to_text(&parse_flux_csv(BODY).unwrap());
+        assert_eq!("result   table  _value  host\n_result  0      1.5     a\n_result  0              b", result);
+    }
+
+    #[test]
+    fn maps_tables_to_json() {
+        let result = tables_to_json(&parse_flux_csv(BODY).unwrap());
+        assert_eq!(
+            r#"[{"_value":1.5,"host":"a","result":"_result","table":0},{"_value":null,"host":"b","result":"_result","table":0}]"#,
+            result
+        );
+    }
+
+    #[test]
+    fn maps_buckets_to_text() {
+        let result = buckets_to_text(&[Bucket {
+            id: Some("1".to_string()),
+            org_id: "org".to_string(),
+            name: "metrics".to_string(),
+            description: None,
+            retention_rules: vec![RetentionRule { rule_type: "expire".to_string(), every_seconds: 60 }],
+        }]);
+        assert_eq!("ID  Name     Retention\n1   metrics  60s", result);
+    }
+}
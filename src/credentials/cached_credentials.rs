@@ -0,0 +1,111 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::credentials::credential_provider::CredentialProvider;
+use crate::error::credential_error::CredentialError;
+use crate::model::secret::Secret;
+
+pub struct CachedCredentials<P: CredentialProvider> {
+    provider: P,
+    ttl: Duration,
+    cached: Mutex<Option<(Secret, Instant)>>,
+}
+
+impl<P: CredentialProvider> CachedCredentials<P> {
+    pub fn new(provider: P, ttl: Duration) -> Self {
+        CachedCredentials {
+            provider,
+            ttl,
+            cached: Mutex::new(None),
+        }
+    }
+}
+
+impl<P: CredentialProvider> CredentialProvider for CachedCredentials<P> {
+    fn credentials(&self) -> Result<Secret, CredentialError> {
+        if let Some((token, fetched)) = self.cached.lock().unwrap().as_ref() {
+            if fetched.elapsed() < self.ttl {
+                return Ok(token.clone());
+            }
+        }
+        let token = self.provider.credentials()?;
+        *self.cached.lock().unwrap() = Some((token.clone(), Instant::now()));
+        Ok(token)
+    }
+
+    fn invalidate(&self) {
+        *self.cached.lock().unwrap() = None;
+        self.provider.invalidate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use super::*;
+
+    struct CountingCredentials {
+        calls: AtomicUsize,
+    }
+
+    impl CredentialProvider for CountingCredentials {
+        fn credentials(&self) -> Result<Secret, CredentialError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Secret::from(format!("token-{}", call)))
+        }
+    }
+
+    fn counting() -> CountingCredentials {
+        CountingCredentials {
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    #[test]
+    fn caches_until_expired() {
+        let provider = CachedCredentials::new(counting(), Duration::from_millis(50));
+        assert_eq!("token-0", provider.credentials().unwrap().expose());
+        assert_eq!("token-0", provider.credentials().unwrap().expose());
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!("token-1", provider.credentials().unwrap().expose());
+    }
+
+    struct SlowCredentials {
+        started: std::sync::mpsc::Sender<()>,
+        release: Mutex<std::sync::mpsc::Receiver<()>>,
+    }
+
+    impl CredentialProvider for SlowCredentials {
+        fn credentials(&self) -> Result<Secret, CredentialError> {
+            self.started.send(()).unwrap();
+            self.release.lock().unwrap().recv().unwrap();
+            Ok(Secret::from("token"))
+        }
+    }
+
+    #[test]
+    fn invalidates_while_refreshing() {
+        let (started, started_receiver) = std::sync::mpsc::channel();
+        let (release, release_receiver) = std::sync::mpsc::channel();
+        let provider = std::sync::Arc::new(CachedCredentials::new(
+            SlowCredentials {
+                started,
+                release: Mutex::new(release_receiver),
+            },
+            Duration::from_secs(60),
+        ));
+        let refreshing = provider.clone();
+        let handle = std::thread::spawn(move || refreshing.credentials().unwrap());
+        started_receiver.recv().unwrap();
+        provider.invalidate();
+        release.send(()).unwrap();
+        assert_eq!("token", handle.join().unwrap().expose());
+    }
+
+    #[test]
+    fn refetches_after_invalidate() {
+        let provider = CachedCredentials::new(counting(), Duration::from_secs(60));
+        assert_eq!("token-0", provider.credentials().unwrap().expose());
+        provider.invalidate();
+        assert_eq!("token-1", provider.credentials().unwrap().expose());
+    }
+}
@@ -0,0 +1,69 @@
+use std::process::Command;
+use crate::credentials::credential_provider::CredentialProvider;
+use crate::error::credential_error::CredentialError;
+use crate::model::secret::Secret;
+
+pub struct CommandCredentials {
+    program: String,
+    arguments: Vec<String>,
+}
+
+impl CommandCredentials {
+    pub fn new(program: &str, arguments: &[&str]) -> Self {
+        CommandCredentials {
+            program: program.to_string(),
+            arguments: arguments.iter().map(|argument| argument.to_string()).collect(),
+        }
+    }
+}
+
+impl CredentialProvider for CommandCredentials {
+    fn credentials(&self) -> Result<Secret, CredentialError> {
+        let output = Command::new(&self.program)
+            .args(&self.arguments)
+            .output()
+            .map_err(|error| CredentialError::Command(format!("{} could not be started: {}", self.program, error)))?;
+        if !output.status.success() {
+            return Err(CredentialError::Command(format!(
+                "{} exited with {}: {}",
+                self.program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let token = String::from_utf8(output.stdout)
+            .map_err(|_| CredentialError::Command(format!("{} printed invalid utf-8", self.program)))?
+            .trim()
+            .to_string();
+        if token.is_empty() {
+            return Err(CredentialError::Missing(format!("output of {}", self.program)));
+        }
+        Ok(Secret::from(token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_command_output() {
+        let result = CommandCredentials::new("sh", &["-c", "echo token"]).credentials().unwrap();
+        assert_eq!("token", result.expose());
+    }
+
+    #[test]
+    fn rejects_failed_command() {
+        let result = CommandCredentials::new("sh", &["-c", "echo denied >&2; exit 3"]).credentials();
+        assert_eq!(
+            "Credential command failed sh exited with exit status: 3: denied",
+            result.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn rejects_empty_output() {
+        let result = CommandCredentials::new("true", &[]).credentials();
+        assert_eq!("No credentials found in output of true", result.unwrap_err().to_string());
+    }
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+use crate::error::credential_error::CredentialError;
+use crate::model::secret::Secret;
+
+pub trait CredentialProvider: Send + Sync {
+    fn credentials(&self) -> Result<Secret, CredentialError>;
+
+    fn invalidate(&self) {}
+}
+
+impl<T: CredentialProvider + ?Sized> CredentialProvider for Arc<T> {
+    fn credentials(&self) -> Result<Secret, CredentialError> {
+        (**self).credentials()
+    }
+
+    fn invalidate(&self) {
+        (**self).invalidate()
+    }
+}
+
+impl<T: CredentialProvider + ?Sized> CredentialProvider for Box<T> {
+    fn credentials(&self) -> Result<Secret, CredentialError> {
+        (**self).credentials()
+    }
+
+    fn invalidate(&self) {
+        (**self).invalidate()
+    }
+}
@@ -0,0 +1,121 @@
+use std::future::Future;
+use std::io;
+use std::sync::Arc;
+use reqwest::Error;
+use crate::credentials::credential_provider::CredentialProvider;
+use crate::error::credential_error::CredentialError;
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::response_mapper::is_unauthorized;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+use crate::telemetry::client_stats::record_retry;
+
+pub async fn with_credentials<T, F, R>(
+    credentials: &Arc<dyn CredentialProvider>,
+    influxdb_config: &InfluxdbConfig,
+    request: F
+) -> Result<T, InfluxDbError<Option<Error>>>
+where
+    F: Fn(Secret) -> R,
+    R: Future<Output = Result<T, InfluxDbError<Option<Error>>>>,
+{
+    match request(load(credentials).await?).await {
+        Err(error) if is_unauthorized(&error) => {
+            credentials.invalidate();
+            record_retry(&influxdb_config.address, 1);
+            request(load(credentials).await?).await
+        }
+        result => result,
+    }
+}
+
+pub (crate) async fn load_credentials(credentials: &Arc<dyn CredentialProvider>) -> Result<Secret, CredentialError> {
+    let credentials = credentials.clone();
+    tokio::task::spawn_blocking(move || credentials.credentials())
+        .await
+        .map_err(io::Error::other)?
+}
+
+async fn load(credentials: &Arc<dyn CredentialProvider>) -> Result<Secret, InfluxDbError<Option<Error>>> {
+    load_credentials(credentials)
+        .await
+        .map_err(|error| InfluxDbError::Failed(None, error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use super::*;
+    use crate::repository::bucket_repository::list_buckets;
+    use crate::repository::influxdb_repository::write_to_influxdb;
+    use crate::telemetry::client_stats::address_stats;
+    use crate::test_support::fake_influxdb::FakeInfluxDb;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    struct RotatingCredentials {
+        tokens: Mutex<Vec<Secret>>,
+    }
+
+    impl CredentialProvider for RotatingCredentials {
+        fn credentials(&self) -> Result<Secret, CredentialError> {
+            self.tokens
+                .lock()
+                .unwrap()
+                .last()
+                .cloned()
+                .ok_or_else(|| CredentialError::Missing("test provider".to_string()))
+        }
+
+        fn invalidate(&self) {
+            self.tokens.lock().unwrap().pop();
+        }
+    }
+
+    #[actix_rt::test]
+    async fn retries_once_with_fresh_credentials() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let credentials: Arc<dyn CredentialProvider> = Arc::new(RotatingCredentials {
+            tokens: Mutex::new(vec!["token".into(), "expired".into()]),
+        });
+        let config = fake.config("organisation", "bucket");
+        let before = address_stats(&fake.address()).unwrap_or_default();
+        let result = with_credentials(&credentials, &config, |influxdb_token| {
+            write_to_influxdb(influxdb_token, &config, "cpu usage=1 1".to_string())
+        }).await;
+        assert!(result.is_ok());
+        assert_eq!(2, fake.request_count());
+        assert_eq!(1, fake.point_count("bucket"));
+        assert_eq!(1, address_stats(&fake.address()).unwrap().retries - before.retries);
+    }
+
+    #[actix_rt::test]
+    async fn gives_up_after_one_retry() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let credentials: Arc<dyn CredentialProvider> = Arc::new(RotatingCredentials {
+            tokens: Mutex::new(vec!["revoked".into(), "expired".into()]),
+        });
+        let config = fake.config("organisation", "bucket");
+        let result = with_credentials(&credentials, &config, |influxdb_token| {
+            write_to_influxdb(influxdb_token, &config, "cpu usage=1 1".to_string())
+        }).await;
+        assert!(result.unwrap_err().to_string().contains("unauthorized access"));
+        assert_eq!(2, fake.request_count());
+    }
+
+    #[actix_rt::test]
+    async fn wraps_management_requests() {
+        let harness = setup_test_harness();
+        let credentials: Arc<dyn CredentialProvider> = Arc::new(RotatingCredentials {
+            tokens: Mutex::new(vec!["token".into()]),
+        });
+        let config = test_config(harness.url("success"));
+        let result = with_credentials(&credentials, &config, |influxdb_token| list_buckets(influxdb_token, &config)).await;
+        assert_eq!(1, result.unwrap().len());
+        let credentials: Arc<dyn CredentialProvider> = Arc::new(RotatingCredentials {
+            tokens: Mutex::new(vec![]),
+        });
+        let result = with_credentials(&credentials, &config, |influxdb_token| list_buckets(influxdb_token, &config)).await;
+        assert_eq!("Rest call failed No credentials found in test provider", result.unwrap_err().to_string());
+    }
+}
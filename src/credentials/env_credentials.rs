@@ -0,0 +1,48 @@
+use std::env;
+use crate::credentials::credential_provider::CredentialProvider;
+use crate::error::credential_error::CredentialError;
+use crate::model::secret::Secret;
+
+pub struct EnvCredentials {
+    variable: String,
+}
+
+impl EnvCredentials {
+    pub fn new(variable: &str) -> Self {
+        EnvCredentials {
+            variable: variable.to_string(),
+        }
+    }
+}
+
+impl CredentialProvider for EnvCredentials {
+    fn credentials(&self) -> Result<Secret, CredentialError> {
+        env::var(&self.variable)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .map(Secret::from)
+            .ok_or_else(|| CredentialError::Missing(format!("environment variable {}", self.variable)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_variable() {
+        env::set_var("INFLUXDB_CLIENT_TEST_ENV_CREDENTIALS", "token\n");
+        let result = EnvCredentials::new("INFLUXDB_CLIENT_TEST_ENV_CREDENTIALS").credentials().unwrap();
+        assert_eq!("token", result.expose());
+    }
+
+    #[test]
+    fn rejects_missing_variable() {
+        let result = EnvCredentials::new("INFLUXDB_CLIENT_TEST_MISSING_CREDENTIALS").credentials();
+        assert_eq!(
+            "No credentials found in environment variable INFLUXDB_CLIENT_TEST_MISSING_CREDENTIALS",
+            result.unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::credentials::credential_provider::CredentialProvider;
+use crate::error::credential_error::CredentialError;
+use crate::model::secret::Secret;
+
+pub struct FileCredentials {
+    path: PathBuf,
+}
+
+impl FileCredentials {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        FileCredentials {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl CredentialProvider for FileCredentials {
+    fn credentials(&self) -> Result<Secret, CredentialError> {
+        let token = fs::read_to_string(&self.path)?.trim().to_string();
+        if token.is_empty() {
+            return Err(CredentialError::Missing(format!("file {}", self.path.display())));
+        }
+        Ok(Secret::from(token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, contents: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("influxdb-file-credentials-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn reads_trimmed_file() {
+        let result = FileCredentials::new(file("token", " token\n")).credentials().unwrap();
+        assert_eq!("token", result.expose());
+    }
+
+    #[test]
+    fn rejects_empty_file() {
+        let path = file("empty", "\n");
+        let result = FileCredentials::new(&path).credentials();
+        assert_eq!(format!("No credentials found in file {}", path.display()), result.unwrap_err().to_string());
+    }
+
+    #[test]
+    fn rejects_missing_file() {
+        let result = FileCredentials::new("/nonexistent/token").credentials();
+        assert!(matches!(result, Err(CredentialError::Io(_))));
+    }
+}
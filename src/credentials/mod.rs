@@ -0,0 +1,7 @@
+pub mod cached_credentials;
+pub mod command_credentials;
+pub mod credential_provider;
+pub mod credential_request;
+pub mod env_credentials;
+pub mod file_credentials;
+pub mod static_credentials;
\ No newline at end of file
@@ -0,0 +1,32 @@
+use crate::credentials::credential_provider::CredentialProvider;
+use crate::error::credential_error::CredentialError;
+use crate::model::secret::Secret;
+
+pub struct StaticCredentials {
+    token: Secret,
+}
+
+impl StaticCredentials {
+    pub fn new(token: impl Into<Secret>) -> Self {
+        StaticCredentials {
+            token: token.into(),
+        }
+    }
+}
+
+impl CredentialProvider for StaticCredentials {
+    fn credentials(&self) -> Result<Secret, CredentialError> {
+        Ok(self.token.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returns_token() {
+        let result = StaticCredentials::new("token").credentials().unwrap();
+        assert_eq!("token", result.expose());
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt::{Display, Formatter};
+use std::{error, fmt, io};
+use arrow_schema::ArrowError;
+use parquet::errors::ParquetError;
+use crate::error::influxdb_error::InfluxDbError;
+
+pub enum ArrowExportError {
+    Request(InfluxDbError<Option<reqwest::Error>>),
+    Invalid(String),
+    Arrow(ArrowError),
+    Parquet(ParquetError),
+    Io(io::Error),
+}
+
+impl Display for ArrowExportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ArrowExportError::Request(error) => write!(f, "{}", error),
+            ArrowExportError::Invalid(reason) => write!(f, "Arrow conversion failed {}", reason),
+            ArrowExportError::Arrow(error) => write!(f, "Arrow conversion failed {}", error),
+            ArrowExportError::Parquet(error) => write!(f, "Parquet export failed {}", error),
+            ArrowExportError::Io(error) => write!(f, "Parquet export failed {}", error),
+        }
+    }
+}
+
+impl error::Error for ArrowExportError {}
+
+impl fmt::Debug for ArrowExportError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "ArrowExportError({})", self)
+    }
+}
+
+impl From<InfluxDbError<Option<reqwest::Error>>> for ArrowExportError {
+    fn from(error: InfluxDbError<Option<reqwest::Error>>) -> Self {
+        ArrowExportError::Request(error)
+    }
+}
+
+impl From<ArrowError> for ArrowExportError {
+    fn from(error: ArrowError) -> Self {
+        ArrowExportError::Arrow(error)
+    }
+}
+
+impl From<ParquetError> for ArrowExportError {
+    fn from(error: ParquetError) -> Self {
+        ArrowExportError::Parquet(error)
+    }
+}
+
+impl From<io::Error> for ArrowExportError {
+    fn from(error: io::Error) -> Self {
+        ArrowExportError::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_invalid() {
+        let result = ArrowExportError::Invalid("invalid long value 'x'".to_string());
+        assert_eq!("Arrow conversion failed invalid long value 'x'", result.to_string());
+    }
+
+    #[test]
+    fn debug_request() {
+        let result = ArrowExportError::Request(InfluxDbError::Failed(None, "401 Unauthorized".to_string()));
+        assert_eq!("ArrowExportError(Rest call failed 401 Unauthorized)", format!("{:#?}", result));
+    }
+}
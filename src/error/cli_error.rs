@@ -0,0 +1,100 @@
+use std::fmt::{Display, Formatter};
+use std::{error, fmt, io};
+use crate::error::config_error::ConfigError;
+use crate::error::credential_error::CredentialError;
+use crate::error::csv_import_error::CsvImportError;
+use crate::error::influxdb_error::InfluxDbError;
+use crate::error::line_protocol_error::LineProtocolError;
+use crate::error::point_error::PointError;
+
+pub enum CliError {
+    Config(String),
+    Credential(CredentialError),
+    CsvImport(CsvImportError),
+    Io(io::Error),
+    LineProtocol(LineProtocolError),
+    Point(PointError),
+    Request(InfluxDbError<Option<reqwest::Error>>),
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Config(reason) => write!(f, "Invalid configuration: {}", reason),
+            CliError::Credential(error) => write!(f, "{}", error),
+            CliError::CsvImport(error) => write!(f, "{}", error),
+            CliError::Io(error) => write!(f, "Io failed {}", error),
+            CliError::LineProtocol(error) => write!(f, "{}", error),
+            CliError::Point(error) => write!(f, "{}", error),
+            CliError::Request(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl error::Error for CliError {}
+
+impl fmt::Debug for CliError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "CliError({})", self)
+    }
+}
+
+impl From<ConfigError> for CliError {
+    fn from(error: ConfigError) -> Self {
+        let problems: Vec<String> = error.problems.iter().map(ToString::to_string).collect();
+        CliError::Config(problems.join("; "))
+    }
+}
+
+impl From<CredentialError> for CliError {
+    fn from(error: CredentialError) -> Self {
+        CliError::Credential(error)
+    }
+}
+
+impl From<CsvImportError> for CliError {
+    fn from(error: CsvImportError) -> Self {
+        CliError::CsvImport(error)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(error: io::Error) -> Self {
+        CliError::Io(error)
+    }
+}
+
+impl From<LineProtocolError> for CliError {
+    fn from(error: LineProtocolError) -> Self {
+        CliError::LineProtocol(error)
+    }
+}
+
+impl From<PointError> for CliError {
+    fn from(error: PointError) -> Self {
+        CliError::Point(error)
+    }
+}
+
+impl From<InfluxDbError<Option<reqwest::Error>>> for CliError {
+    fn from(error: InfluxDbError<Option<reqwest::Error>>) -> Self {
+        CliError::Request(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_config() {
+        let result = CliError::Config("address is required".to_string());
+        assert_eq!("Invalid configuration: address is required", result.to_string());
+    }
+
+    #[test]
+    fn debug_request() {
+        let result = CliError::Request(InfluxDbError::Failed(None, "401 Unauthorized".to_string()));
+        assert_eq!("CliError(Rest call failed 401 Unauthorized)", format!("{:#?}", result));
+    }
+}
@@ -0,0 +1,64 @@
+use std::fmt::{Display, Formatter};
+use std::{error, fmt};
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum ConfigProblem {
+    Missing(String),
+    InvalidAddress(String),
+    InvalidLimit(String),
+}
+
+pub struct ConfigError {
+    pub problems: Vec<ConfigProblem>,
+}
+
+impl Display for ConfigProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigProblem::Missing(field) => write!(f, "{} is required", field),
+            ConfigProblem::InvalidAddress(address) => write!(f, "address '{}' is not a valid http or https url", address),
+            ConfigProblem::InvalidLimit(limit) => write!(f, "{} must be greater than zero", limit),
+        }
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let problems: Vec<String> = self.problems.iter().map(ConfigProblem::to_string).collect();
+        write!(f, "Invalid configuration: {}", problems.join("; "))
+    }
+}
+
+impl error::Error for ConfigError {}
+
+impl fmt::Debug for ConfigError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "ConfigError({})", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_lists_every_problem() {
+        let result = ConfigError {
+            problems: vec![
+                ConfigProblem::InvalidAddress("localhost".to_string()),
+                ConfigProblem::Missing("bucket".to_string()),
+                ConfigProblem::InvalidLimit("limits.requests_per_second".to_string()),
+            ],
+        };
+        assert_eq!(
+            "Invalid configuration: address 'localhost' is not a valid http or https url; bucket is required; limits.requests_per_second must be greater than zero",
+            result.to_string()
+        );
+    }
+
+    #[test]
+    fn debug_missing() {
+        let result = ConfigError { problems: vec![ConfigProblem::Missing("token".to_string())] };
+        assert_eq!("ConfigError(Invalid configuration: token is required)", format!("{:#?}", result));
+    }
+}
@@ -0,0 +1,49 @@
+use std::fmt::{Display, Formatter};
+use std::{error, fmt, io};
+
+pub enum CredentialError {
+    Missing(String),
+    Io(io::Error),
+    Command(String),
+}
+
+impl Display for CredentialError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialError::Missing(source) => write!(f, "No credentials found in {}", source),
+            CredentialError::Io(error) => write!(f, "Credential io failed {}", error),
+            CredentialError::Command(reason) => write!(f, "Credential command failed {}", reason),
+        }
+    }
+}
+
+impl error::Error for CredentialError {}
+
+impl fmt::Debug for CredentialError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "CredentialError({})", self)
+    }
+}
+
+impl From<io::Error> for CredentialError {
+    fn from(error: io::Error) -> Self {
+        CredentialError::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_missing() {
+        let result = CredentialError::Missing("environment variable INFLUX_TOKEN".to_string());
+        assert_eq!("No credentials found in environment variable INFLUX_TOKEN", result.to_string());
+    }
+
+    #[test]
+    fn debug_command() {
+        let result = CredentialError::Command("vault exited with 1".to_string());
+        assert_eq!("CredentialError(Credential command failed vault exited with 1)", format!("{:#?}", result));
+    }
+}
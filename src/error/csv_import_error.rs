@@ -0,0 +1,58 @@
+use std::fmt::{Display, Formatter};
+use std::{error, fmt};
+use crate::error::influxdb_error::InfluxDbError;
+
+pub enum CsvImportError {
+    Config(String),
+    Csv(csv::Error),
+    Row(u64, String),
+    Write(InfluxDbError<Option<reqwest::Error>>),
+}
+
+impl Display for CsvImportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvImportError::Config(reason) => write!(f, "Invalid csv import: {}", reason),
+            CsvImportError::Csv(error) => write!(f, "Csv read failed {}", error),
+            CsvImportError::Row(line, reason) => write!(f, "Invalid csv row at line {}: {}", line, reason),
+            CsvImportError::Write(error) => write!(f, "Csv import write failed: {}", error),
+        }
+    }
+}
+
+impl error::Error for CsvImportError {}
+
+impl fmt::Debug for CsvImportError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "CsvImportError({})", self)
+    }
+}
+
+impl From<csv::Error> for CsvImportError {
+    fn from(error: csv::Error) -> Self {
+        CsvImportError::Csv(error)
+    }
+}
+
+impl From<InfluxDbError<Option<reqwest::Error>>> for CsvImportError {
+    fn from(error: InfluxDbError<Option<reqwest::Error>>) -> Self {
+        CsvImportError::Write(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_row() {
+        let result = CsvImportError::Row(3, "invalid float value 'x' in column used".to_string());
+        assert_eq!("Invalid csv row at line 3: invalid float value 'x' in column used", result.to_string());
+    }
+
+    #[test]
+    fn debug_write() {
+        let result = CsvImportError::Write(InfluxDbError::Failed(None, "500".to_string()));
+        assert_eq!("CsvImportError(Csv import write failed: Rest call failed 500)", format!("{:#?}", result));
+    }
+}
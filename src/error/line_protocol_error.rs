@@ -0,0 +1,40 @@
+use std::fmt::{Display, Formatter};
+use std::{error, fmt};
+
+#[derive(Clone, PartialEq)]
+pub struct LineProtocolError {
+    pub line: usize,
+    pub column: usize,
+    pub reason: String,
+}
+
+impl Display for LineProtocolError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid line protocol at line {}, column {}: {}", self.line, self.column, self.reason)
+    }
+}
+
+impl error::Error for LineProtocolError {}
+
+impl fmt::Debug for LineProtocolError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "LineProtocolError({})", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let result = LineProtocolError { line: 2, column: 5, reason: "missing field value".to_string() };
+        assert_eq!("Invalid line protocol at line 2, column 5: missing field value", result.to_string());
+    }
+
+    #[test]
+    fn debug() {
+        let result = LineProtocolError { line: 2, column: 5, reason: "missing field value".to_string() };
+        assert_eq!("LineProtocolError(Invalid line protocol at line 2, column 5: missing field value)", format!("{:#?}", result));
+    }
+}
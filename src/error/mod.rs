@@ -1 +1,13 @@
-pub mod influxdb_error;
\ No newline at end of file
+#[cfg(feature = "arrow")]
+pub mod arrow_export_error;
+#[cfg(feature = "cli")]
+pub mod cli_error;
+pub mod config_error;
+pub mod credential_error;
+pub mod csv_import_error;
+pub mod influxdb_error;
+pub mod line_protocol_error;
+pub mod point_error;
+#[cfg(feature = "profiles")]
+pub mod profile_error;
+pub mod write_ahead_error;
\ No newline at end of file
@@ -0,0 +1,44 @@
+use std::fmt::{Display, Formatter};
+use std::{error, fmt};
+
+#[derive(Clone, PartialEq)]
+pub enum PointError {
+    EmptyMeasurement,
+    NoFields(String),
+    LineBreak(String),
+}
+
+impl Display for PointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PointError::EmptyMeasurement => write!(f, "Invalid point: empty measurement"),
+            PointError::NoFields(measurement) => write!(f, "Invalid point {}: no writable fields", measurement),
+            PointError::LineBreak(measurement) => write!(f, "Invalid point {}: line break in name, key or value", measurement),
+        }
+    }
+}
+
+impl error::Error for PointError {}
+
+impl fmt::Debug for PointError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "PointError({})", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_no_fields() {
+        let result = PointError::NoFields("cpu".to_string());
+        assert_eq!("Invalid point cpu: no writable fields", result.to_string());
+    }
+
+    #[test]
+    fn debug_line_break() {
+        let result = PointError::LineBreak("cpu".to_string());
+        assert_eq!("PointError(Invalid point cpu: line break in name, key or value)", format!("{:#?}", result));
+    }
+}
@@ -0,0 +1,55 @@
+use std::fmt::{Display, Formatter};
+use std::{error, fmt, io};
+
+pub enum ProfileError {
+    Io(io::Error),
+    Parse(String),
+    NotFound(String),
+    NoActiveProfile,
+    MissingFields(String, Vec<String>),
+}
+
+impl Display for ProfileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ProfileError::Io(error) => write!(f, "Profile io failed {}", error),
+            ProfileError::Parse(reason) => write!(f, "Invalid configs file: {}", reason),
+            ProfileError::NotFound(name) => write!(f, "Profile {} not found", name),
+            ProfileError::NoActiveProfile => write!(f, "No profile name given and no active profile"),
+            ProfileError::MissingFields(name, fields) => {
+                write!(f, "Profile {} is missing {}", name, fields.join(", "))
+            }
+        }
+    }
+}
+
+impl error::Error for ProfileError {}
+
+impl fmt::Debug for ProfileError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "ProfileError({})", self)
+    }
+}
+
+impl From<io::Error> for ProfileError {
+    fn from(error: io::Error) -> Self {
+        ProfileError::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_missing_fields() {
+        let result = ProfileError::MissingFields("staging".to_string(), vec!["url".to_string(), "token".to_string()]);
+        assert_eq!("Profile staging is missing url, token", result.to_string());
+    }
+
+    #[test]
+    fn debug_not_found() {
+        let result = ProfileError::NotFound("prod".to_string());
+        assert_eq!("ProfileError(Profile prod not found)", format!("{:#?}", result));
+    }
+}
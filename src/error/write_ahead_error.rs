@@ -0,0 +1,59 @@
+use std::fmt::{Display, Formatter};
+use std::{error, fmt, io};
+use crate::error::influxdb_error::InfluxDbError;
+
+pub enum WriteAheadError {
+    Io(io::Error),
+    BufferFull,
+    RecordTooLarge(u64),
+    Write(InfluxDbError<Option<reqwest::Error>>),
+}
+
+impl Display for WriteAheadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteAheadError::Io(error) => write!(f, "Write ahead buffer io failed {}", error),
+            WriteAheadError::BufferFull => write!(f, "Write ahead buffer is full"),
+            WriteAheadError::RecordTooLarge(size) => {
+                write!(f, "Record of {} bytes exceeds the write ahead buffer capacity", size)
+            }
+            WriteAheadError::Write(error) => write!(f, "Write ahead replay failed: {}", error),
+        }
+    }
+}
+
+impl error::Error for WriteAheadError {}
+
+impl fmt::Debug for WriteAheadError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "WriteAheadError({})", self)
+    }
+}
+
+impl From<io::Error> for WriteAheadError {
+    fn from(error: io::Error) -> Self {
+        WriteAheadError::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_buffer_full() {
+        assert_eq!("Write ahead buffer is full", WriteAheadError::BufferFull.to_string());
+    }
+
+    #[test]
+    fn display_write() {
+        let result = WriteAheadError::Write(InfluxDbError::Failed(None, "500".to_string()));
+        assert_eq!("Write ahead replay failed: Rest call failed 500", result.to_string());
+    }
+
+    #[test]
+    fn debug_record_too_large() {
+        let result = WriteAheadError::RecordTooLarge(10);
+        assert_eq!("WriteAheadError(Record of 10 bytes exceeds the write ahead buffer capacity)", format!("{:#?}", result));
+    }
+}
@@ -1,5 +1,16 @@
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod buffer;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod credentials;
 pub mod error;
 pub mod mapper;
+#[cfg(feature = "profiles")]
+pub mod profile;
 pub mod repository;
+#[cfg(any(test, feature = "test-support"))]
 pub mod test_support;
 pub mod model;
+pub mod telemetry;
+pub mod writer;
@@ -0,0 +1,186 @@
+use std::sync::Arc;
+use arrow_array::builder::{BooleanBuilder, Float64Builder, Int64Builder, StringBuilder, StringDictionaryBuilder, TimestampNanosecondBuilder, UInt64Builder};
+use arrow_array::types::Int32Type;
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use crate::error::arrow_export_error::ArrowExportError;
+use crate::mapper::time_mapper::from_rfc3339;
+use crate::model::flux_table::{FluxColumn, FluxTable};
+
+const RESERVED_COLUMNS: [&str; 2] = ["result", "table"];
+const TIME_COLUMNS: [&str; 3] = ["_time", "_start", "_stop"];
+
+pub fn to_record_batches(tables: &[FluxTable]) -> Result<Vec<RecordBatch>, ArrowExportError> {
+    tables.iter().map(to_record_batch).collect()
+}
+
+pub fn to_record_batch(table: &FluxTable) -> Result<RecordBatch, ArrowExportError> {
+    let mut fields = vec![];
+    let mut arrays = vec![];
+    for (index, column) in table.columns.iter().enumerate() {
+        let values: Vec<&str> = table
+            .rows
+            .iter()
+            .map(|row| row.get(index).map(String::as_str).unwrap_or_default())
+            .collect();
+        let data_type = to_data_type(column, &values);
+        arrays.push(to_array(column, &data_type, &values)?);
+        fields.push(Field::new(&column.name, data_type, true));
+    }
+    Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+}
+
+fn to_data_type(column: &FluxColumn, values: &[&str]) -> DataType {
+    match column.data_type.as_str() {
+        "long" => DataType::Int64,
+        "unsignedLong" => DataType::UInt64,
+        "double" => DataType::Float64,
+        "boolean" => DataType::Boolean,
+        data_type if data_type.starts_with("dateTime") => timestamp_type(),
+        _ if TIME_COLUMNS.contains(&column.name.as_str()) => timestamp_type(),
+        _ if is_tag(column) => DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+        "" => infer_data_type(values),
+        _ => DataType::Utf8,
+    }
+}
+
+fn is_tag(column: &FluxColumn) -> bool {
+    !column.name.starts_with('_') && !RESERVED_COLUMNS.contains(&column.name.as_str())
+}
+
+fn infer_data_type(values: &[&str]) -> DataType {
+    let present: Vec<&&str> = values.iter().filter(|value| !value.is_empty()).collect();
+    if present.is_empty() {
+        return DataType::Utf8;
+    }
+    if present.iter().all(|value| value.parse::<bool>().is_ok()) {
+        return DataType::Boolean;
+    }
+    if present.iter().all(|value| value.parse::<f64>().map(f64::is_finite).unwrap_or(false)) {
+        return DataType::Float64;
+    }
+    DataType::Utf8
+}
+
+fn timestamp_type() -> DataType {
+    DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into()))
+}
+
+fn to_array(column: &FluxColumn, data_type: &DataType, values: &[&str]) -> Result<ArrayRef, ArrowExportError> {
+    let invalid = |value: &str| ArrowExportError::Invalid(format!("invalid {} value '{}' in column {}", data_type, value, column.name));
+    let array: ArrayRef = match data_type {
+        DataType::Int64 => {
+            let mut builder = Int64Builder::with_capacity(values.len());
+            for value in values {
+                builder.append_option(parse(value).map_err(|_| invalid(value))?);
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::UInt64 => {
+            let mut builder = UInt64Builder::with_capacity(values.len());
+            for value in values {
+                builder.append_option(parse(value).map_err(|_| invalid(value))?);
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::Float64 => {
+            let mut builder = Float64Builder::with_capacity(values.len());
+            for value in values {
+                builder.append_option(parse(value).map_err(|_| invalid(value))?);
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::Boolean => {
+            let mut builder = BooleanBuilder::with_capacity(values.len());
+            for value in values {
+                builder.append_option(parse(value).map_err(|_| invalid(value))?);
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::Timestamp(_, _) => {
+            let mut builder = TimestampNanosecondBuilder::with_capacity(values.len()).with_timezone("UTC");
+            for value in values {
+                let timestamp = match value.is_empty() {
+                    true => None,
+                    false => Some(from_rfc3339(value).or_else(|| value.parse().ok()).ok_or_else(|| invalid(value))?),
+                };
+                builder.append_option(timestamp);
+            }
+            Arc::new(builder.finish())
+        }
+        DataType::Dictionary(_, _) => {
+            let mut builder = StringDictionaryBuilder::<Int32Type>::new();
+            for value in values {
+                match value.is_empty() {
+                    true => builder.append_null(),
+                    false => builder.append_value(value),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        _ => {
+            let mut builder = StringBuilder::with_capacity(values.len(), values.iter().map(|value| value.len()).sum());
+            for value in values {
+                builder.append_value(value);
+            }
+            Arc::new(builder.finish())
+        }
+    };
+    Ok(array)
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<Option<T>, T::Err> {
+    match value.is_empty() {
+        true => Ok(None),
+        false => value.parse().map(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Float64Type, TimestampNanosecondType};
+    use arrow_array::Array;
+    use super::*;
+    use crate::mapper::flux_csv_mapper::parse_flux_csv;
+
+    const BODY: &str = "#datatype,string,long,dateTime:RFC3339,double,string,string,string\n#group,false,false,false,false,true,true,true\n#default,_result,,,,,,\n,result,table,_time,_value,_field,_measurement,host\n,,0,2024-05-01T00:10:00Z,12.5,usage_user,cpu,server01\n,,0,2024-05-01T00:20:00Z,,usage_user,cpu,server01\n,,1,2024-05-01T00:10:00Z,40.125,usage_user,cpu,server02\n";
+
+    #[test]
+    fn maps_annotated_tables() {
+        let result = to_record_batches(&parse_flux_csv(BODY).unwrap()).unwrap();
+        assert_eq!(2, result.len());
+        let schema = result[0].schema();
+        assert_eq!(&DataType::Int64, schema.field_with_name("table").unwrap().data_type());
+        assert_eq!(&timestamp_type(), schema.field_with_name("_time").unwrap().data_type());
+        assert_eq!(&DataType::Float64, schema.field_with_name("_value").unwrap().data_type());
+        assert_eq!(&DataType::Utf8, schema.field_with_name("_field").unwrap().data_type());
+        assert_eq!(
+            &DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+            schema.field_with_name("host").unwrap().data_type()
+        );
+        let time = result[0].column_by_name("_time").unwrap().as_primitive::<TimestampNanosecondType>();
+        assert_eq!(1_714_522_200_000_000_000, time.value(0));
+        let value = result[0].column_by_name("_value").unwrap().as_primitive::<Float64Type>();
+        assert_eq!(12.5, value.value(0));
+        assert!(value.is_null(1));
+        assert_eq!(1, result[1].num_rows());
+    }
+
+    #[test]
+    fn infers_unannotated_columns() {
+        let tables = parse_flux_csv("_measurement,_field,_value,_time,host\ncpu,up,1.5,10,a\n").unwrap();
+        let result = to_record_batch(&tables[0]).unwrap();
+        let schema = result.schema();
+        assert_eq!(&DataType::Float64, schema.field_with_name("_value").unwrap().data_type());
+        assert_eq!(&timestamp_type(), schema.field_with_name("_time").unwrap().data_type());
+        assert_eq!(10, result.column_by_name("_time").unwrap().as_primitive::<TimestampNanosecondType>().value(0));
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let tables = parse_flux_csv("#datatype,long\n#group,false\n#default,\n,count\n,x\n").unwrap();
+        let result = to_record_batch(&tables[0]);
+        assert_eq!("Arrow conversion failed invalid Int64 value 'x' in column count", result.unwrap_err().to_string());
+    }
+}
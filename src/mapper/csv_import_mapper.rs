@@ -0,0 +1,325 @@
+use std::collections::BTreeMap;
+use csv::StringRecord;
+use crate::model::csv_import_config::{ColumnRole, CsvImportConfig, FieldType, TimeFormat};
+use crate::model::point::{FieldValue, Point};
+
+pub (crate) struct CsvRowMapper {
+    roles: BTreeMap<String, ColumnRole>,
+    measurement: Option<String>,
+    tags: BTreeMap<String, String>,
+    data_types: Option<Vec<ColumnRole>>,
+    flux_types: Option<Vec<String>>,
+    flux_groups: Option<Vec<bool>>,
+    constants: Vec<(String, ColumnRole, String)>,
+    columns: Vec<(String, ColumnRole)>,
+    field_column: Option<usize>,
+}
+
+impl CsvRowMapper {
+    pub (crate) fn new(config: &CsvImportConfig) -> Self {
+        CsvRowMapper {
+            roles: config.columns.clone(),
+            measurement: config.measurement.clone(),
+            tags: config.tags.clone(),
+            data_types: None,
+            flux_types: None,
+            flux_groups: None,
+            constants: vec![],
+            columns: vec![],
+            field_column: None,
+        }
+    }
+
+    pub (crate) fn has_header(&self) -> bool {
+        !self.columns.is_empty()
+    }
+
+    pub (crate) fn annotate(&mut self, record: &StringRecord) -> Result<(), String> {
+        let first = record.get(0).unwrap_or_default();
+        let (annotation, first_value) = first.split_once(' ').unwrap_or((first, ""));
+        match annotation {
+            "#datatype" if first == "#datatype" => {
+                let flux_types = std::iter::once(Ok(String::new()))
+                    .chain(record.iter().skip(1).map(parse_flux_data_type))
+                    .collect::<Result<Vec<String>, String>>()?;
+                self.start_table();
+                self.flux_types = Some(flux_types);
+            }
+            "#datatype" => {
+                let data_types = std::iter::once(first_value)
+                    .chain(record.iter().skip(1))
+                    .map(parse_data_type)
+                    .collect::<Result<Vec<ColumnRole>, String>>()?;
+                self.start_table();
+                self.data_types = Some(data_types);
+            }
+            "#group" => {
+                self.flux_groups = Some(std::iter::once(false).chain(record.iter().skip(1).map(|group| group == "true")).collect());
+            }
+            "#constant" => {
+                let role = parse_data_type(first_value)?;
+                let (name, value) = match (record.get(1), record.get(2)) {
+                    (Some(value), None) if role == ColumnRole::Measurement => ("", value),
+                    (Some(name), Some(value)) => (name, value),
+                    _ => return Err(format!("invalid constant annotation '{}'", record.iter().collect::<Vec<&str>>().join(","))),
+                };
+                self.constants.push((name.to_string(), role, value.to_string()));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub (crate) fn header(&mut self, record: &StringRecord) -> Result<(), String> {
+        self.columns = record
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let role = match (&self.data_types, &self.flux_types) {
+                    (Some(data_types), _) => data_types.get(index).copied().unwrap_or(ColumnRole::Ignore),
+                    (None, Some(flux_types)) => match self.roles.get(name) {
+                        Some(role) => *role,
+                        None => {
+                            let data_type = flux_types.get(index).map(String::as_str).unwrap_or_default();
+                            let group = self.flux_groups.as_ref().and_then(|groups| groups.get(index)).copied();
+                            to_flux_role(index, name, data_type, group)
+                        }
+                    },
+                    (None, None) => self.roles.get(name).copied().unwrap_or(ColumnRole::Ignore),
+                };
+                (name.to_string(), role)
+            })
+            .collect();
+        self.field_column = match self.flux_types {
+            Some(_) => record.iter().position(|name| name == "_field"),
+            None => None,
+        };
+        let roles: Vec<ColumnRole> = self
+            .columns
+            .iter()
+            .map(|(_, role)| *role)
+            .chain(self.constants.iter().map(|(_, role, _)| *role))
+            .collect();
+        if self.measurement.is_none() && !roles.contains(&ColumnRole::Measurement) {
+            return Err("no measurement column or constant".to_string());
+        }
+        if !roles.iter().any(|role| matches!(role, ColumnRole::Field(_))) {
+            return Err("no field columns".to_string());
+        }
+        Ok(())
+    }
+
+    pub (crate) fn to_point(&self, record: &StringRecord) -> Result<Point, String> {
+        if record.len() > self.columns.len() {
+            return Err(format!("expected {} columns but found {}", self.columns.len(), record.len()));
+        }
+        let mut measurement = self.measurement.clone();
+        let mut tags = self.tags.clone();
+        let mut fields = vec![];
+        let mut timestamp = None;
+        let cells = self
+            .constants
+            .iter()
+            .map(|(name, role, value)| (name.as_str(), *role, value.as_str()))
+            .chain(self.columns.iter().enumerate().map(|(index, (name, role))| {
+                let name = match self.field_column {
+                    Some(field_column) if name == "_value" => record.get(field_column).unwrap_or_default(),
+                    _ => name.as_str(),
+                };
+                (name, *role, record.get(index).unwrap_or_default())
+            }));
+        for (name, role, value) in cells {
+            if value.is_empty() {
+                continue;
+            }
+            match role {
+                ColumnRole::Measurement => measurement = Some(value.to_string()),
+                ColumnRole::Tag => {
+                    tags.insert(name.to_string(), value.to_string());
+                }
+                ColumnRole::Field(field_type) => fields.push((name, to_field_value(field_type, name, value)?)),
+                ColumnRole::Time(format) => timestamp = Some(to_timestamp(format, name, value)?),
+                ColumnRole::Ignore => {}
+            }
+        }
+        let measurement = measurement.ok_or_else(|| "missing measurement".to_string())?;
+        if fields.is_empty() {
+            return Err("no field values".to_string());
+        }
+        let mut point = Point::new(&measurement);
+        for (key, value) in &tags {
+            point = point.tag(key, value);
+        }
+        for (key, value) in fields {
+            point = point.field(key, value);
+        }
+        Ok(match timestamp {
+            Some(timestamp) => point.timestamp(timestamp),
+            None => point,
+        })
+    }
+
+    fn start_table(&mut self) {
+        self.data_types = None;
+        self.flux_types = None;
+        self.flux_groups = None;
+        self.columns = vec![];
+        self.field_column = None;
+    }
+}
+
+fn parse_flux_data_type(data_type: &str) -> Result<String, String> {
+    match data_type.trim() {
+        data_type @ ("string" | "long" | "unsignedLong" | "double" | "boolean" | "duration" | "base64Binary"
+            | "dateTime" | "dateTime:RFC3339" | "dateTime:RFC3339Nano") => Ok(data_type.to_string()),
+        other => Err(format!("unsupported datatype '{}'", other)),
+    }
+}
+
+fn to_flux_role(index: usize, name: &str, data_type: &str, group: Option<bool>) -> ColumnRole {
+    match (index, name, data_type) {
+        (0, _, _) | (_, "result" | "table" | "_start" | "_stop" | "_field", _) => ColumnRole::Ignore,
+        (_, "_measurement", _) => ColumnRole::Measurement,
+        (_, "_time", data_type) if data_type.starts_with("dateTime") => ColumnRole::Time(TimeFormat::Rfc3339),
+        (_, "_value", "string") => ColumnRole::Field(FieldType::String),
+        (_, _, "string") if group.unwrap_or(true) => ColumnRole::Tag,
+        (_, _, "string") => ColumnRole::Field(FieldType::String),
+        (_, _, "double") => ColumnRole::Field(FieldType::Float),
+        (_, _, "long") => ColumnRole::Field(FieldType::Integer),
+        (_, _, "unsignedLong") => ColumnRole::Field(FieldType::UInteger),
+        (_, _, "boolean") => ColumnRole::Field(FieldType::Boolean),
+        _ => ColumnRole::Ignore,
+    }
+}
+
+fn parse_data_type(data_type: &str) -> Result<ColumnRole, String> {
+    match data_type.trim() {
+        "measurement" => Ok(ColumnRole::Measurement),
+        "tag" => Ok(ColumnRole::Tag),
+        "double" => Ok(ColumnRole::Field(FieldType::Float)),
+        "long" => Ok(ColumnRole::Field(FieldType::Integer)),
+        "unsignedLong" => Ok(ColumnRole::Field(FieldType::UInteger)),
+        "boolean" => Ok(ColumnRole::Field(FieldType::Boolean)),
+        "string" | "field" => Ok(ColumnRole::Field(FieldType::String)),
+        "dateTime" | "dateTime:RFC3339" | "dateTime:RFC3339Nano" => Ok(ColumnRole::Time(TimeFormat::Rfc3339)),
+        "dateTime:number" => Ok(ColumnRole::Time(TimeFormat::EpochNanoseconds)),
+        "ignore" | "ignored" => Ok(ColumnRole::Ignore),
+        other => Err(format!("unsupported datatype '{}'", other)),
+    }
+}
+
+fn to_field_value(field_type: FieldType, name: &str, value: &str) -> Result<FieldValue, String> {
+    let type_name = match field_type {
+        FieldType::Float => "float",
+        FieldType::Integer => "integer",
+        FieldType::UInteger => "uinteger",
+        FieldType::Boolean => "boolean",
+        FieldType::String => "string",
+    };
+    let invalid = || format!("invalid {} value '{}' in column {}", type_name, value, name);
+    match field_type {
+        FieldType::Float => value.parse().map(FieldValue::Float).map_err(|_| invalid()),
+        FieldType::Integer => value.parse().map(FieldValue::Integer).map_err(|_| invalid()),
+        FieldType::UInteger => value.parse().map(FieldValue::UInteger).map_err(|_| invalid()),
+        FieldType::Boolean => match value.to_lowercase().as_str() {
+            "true" | "t" | "yes" | "y" | "1" => Ok(FieldValue::Boolean(true)),
+            "false" | "f" | "no" | "n" | "0" => Ok(FieldValue::Boolean(false)),
+            _ => Err(invalid()),
+        },
+        FieldType::String => Ok(FieldValue::String(value.to_string())),
+    }
+}
+
+fn to_timestamp(format: TimeFormat, name: &str, value: &str) -> Result<i64, String> {
+    format
+        .to_nanos(value)
+        .ok_or_else(|| format!("invalid time '{}' in column {}", value, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(values: &[&str]) -> StringRecord {
+        StringRecord::from(values.to_vec())
+    }
+
+    #[test]
+    fn maps_plain_rows_with_roles() {
+        let config = CsvImportConfig {
+            columns: BTreeMap::from([
+                ("host".to_string(), ColumnRole::Tag),
+                ("used".to_string(), ColumnRole::Field(FieldType::Float)),
+                ("ts".to_string(), ColumnRole::Time(TimeFormat::EpochSeconds)),
+            ]),
+            measurement: Some("mem".to_string()),
+            tags: BTreeMap::from([("source".to_string(), "partner".to_string())]),
+            ..CsvImportConfig::default()
+        };
+        let mut mapper = CsvRowMapper::new(&config);
+        mapper.header(&record(&["host", "used", "ts", "note"])).unwrap();
+        let result = mapper.to_point(&record(&["a", "64.5", "10", "ignored"])).unwrap();
+        assert_eq!(
+            Point::new("mem")
+                .tag("host", "a")
+                .tag("source", "partner")
+                .field("used", 64.5)
+                .timestamp(10_000_000_000),
+            result
+        );
+    }
+
+    #[test]
+    fn maps_annotated_rows() {
+        let mut mapper = CsvRowMapper::new(&CsvImportConfig::default());
+        mapper.annotate(&record(&["#constant measurement", "cpu"])).unwrap();
+        mapper.annotate(&record(&["#constant tag", "region", "eu"])).unwrap();
+        mapper.annotate(&record(&["#datatype tag", "long", "boolean", "dateTime:RFC3339"])).unwrap();
+        mapper.header(&record(&["host", "count", "up", "time"])).unwrap();
+        let result = mapper.to_point(&record(&["a", "3", "t", "2024-05-01T00:10:00Z"])).unwrap();
+        assert_eq!(
+            Point::new("cpu")
+                .tag("host", "a")
+                .tag("region", "eu")
+                .field("count", 3i64)
+                .field("up", true)
+                .timestamp(1_714_522_200_000_000_000),
+            result
+        );
+    }
+
+    #[test]
+    fn maps_flux_annotated_rows() {
+        let mut mapper = CsvRowMapper::new(&CsvImportConfig::default());
+        mapper.annotate(&record(&["#datatype", "string", "long", "dateTime:RFC3339", "double", "string", "string", "string"])).unwrap();
+        mapper.annotate(&record(&["#group", "false", "false", "false", "false", "true", "true", "true"])).unwrap();
+        mapper.annotate(&record(&["#default", "_result", "", "", "", "", "", ""])).unwrap();
+        mapper.header(&record(&["", "result", "table", "_time", "_value", "_field", "_measurement", "host"])).unwrap();
+        let result = mapper.to_point(&record(&["", "", "0", "2024-05-01T00:10:00Z", "1.5", "usage", "cpu", "a"])).unwrap();
+        assert_eq!(Point::new("cpu").tag("host", "a").field("usage", 1.5).timestamp(1_714_522_200_000_000_000), result);
+        mapper.annotate(&record(&["#datatype", "string", "long", "dateTime:RFC3339", "string", "string", "string"])).unwrap();
+        assert!(!mapper.has_header());
+        mapper.header(&record(&["", "result", "table", "_time", "_value", "_field", "_measurement"])).unwrap();
+        let result = mapper.to_point(&record(&["", "", "1", "2024-05-01T00:10:00Z", "ok", "state", "cpu"])).unwrap();
+        assert_eq!(Point::new("cpu").field("state", "ok").timestamp(1_714_522_200_000_000_000), result);
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let mut mapper = CsvRowMapper::new(&CsvImportConfig::default());
+        mapper.annotate(&record(&["#datatype measurement", "double"])).unwrap();
+        mapper.header(&record(&["m", "used"])).unwrap();
+        assert_eq!(Err("invalid float value 'x' in column used".to_string()), mapper.to_point(&record(&["mem", "x"])));
+        assert_eq!(Err("no field values".to_string()), mapper.to_point(&record(&["mem", ""])));
+        assert_eq!(Err("expected 2 columns but found 3".to_string()), mapper.to_point(&record(&["mem", "1", "2"])));
+    }
+
+    #[test]
+    fn rejects_missing_roles() {
+        let mut mapper = CsvRowMapper::new(&CsvImportConfig::default());
+        assert_eq!(Err("no measurement column or constant".to_string()), mapper.header(&record(&["used"])));
+        let mut mapper = CsvRowMapper::new(&CsvImportConfig::default());
+        assert_eq!(Err("unsupported datatype 'decimal'".to_string()), mapper.annotate(&record(&["#datatype decimal"])));
+        assert_eq!(Err("unsupported datatype 'decimal'".to_string()), mapper.annotate(&record(&["#datatype", "string", "decimal"])));
+    }
+}
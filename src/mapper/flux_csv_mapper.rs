@@ -0,0 +1,220 @@
+use reqwest::Error;
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::time_mapper::from_rfc3339;
+use crate::model::flux_table::{FluxColumn, FluxTable};
+use crate::model::point::{FieldValue, Point};
+
+const RESERVED_COLUMNS: [&str; 2] = ["result", "table"];
+
+pub fn parse_flux_csv(body: &str) -> Result<Vec<FluxTable>, InfluxDbError<Option<Error>>> {
+    let mut tables = vec![];
+    let mut block = vec![];
+    for line in body.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                tables.extend(parse_block(&block.join("\n"))?);
+                block.clear();
+            }
+        } else {
+            block.push(line);
+        }
+    }
+    if !block.is_empty() {
+        tables.extend(parse_block(&block.join("\n"))?);
+    }
+    Ok(tables)
+}
+
+pub fn flux_tables_to_points(tables: &[FluxTable]) -> Result<Vec<Point>, InfluxDbError<Option<Error>>> {
+    let mut points = vec![];
+    for table in tables {
+        let measurement = table.column_index("_measurement").ok_or_else(|| invalid_response("missing _measurement column"))?;
+        let field = table.column_index("_field").ok_or_else(|| invalid_response("missing _field column"))?;
+        let value = table.column_index("_value").ok_or_else(|| invalid_response("missing _value column"))?;
+        let time = table.column_index("_time");
+        let tags: Vec<usize> = (0..table.columns.len())
+            .filter(|index| {
+                let name = &table.columns[*index].name;
+                !name.starts_with('_') && !RESERVED_COLUMNS.contains(&name.as_str())
+            })
+            .collect();
+        for row in &table.rows {
+            let mut point = Point::new(&row[measurement]).field(&row[field], to_field_value(&table.columns[value], &row[value])?);
+            for tag in &tags {
+                if !row[*tag].is_empty() {
+                    point = point.tag(&table.columns[*tag].name, &row[*tag]);
+                }
+            }
+            if let Some(time) = time.filter(|time| !row[*time].is_empty()) {
+                point = point.timestamp(to_timestamp(&row[time])?);
+            }
+            points.push(point);
+        }
+    }
+    Ok(points)
+}
+
+fn parse_block(block: &str) -> Result<Vec<FluxTable>, InfluxDbError<Option<Error>>> {
+    let mut data_types = vec![];
+    let mut groups = vec![];
+    let mut defaults = vec![];
+    let mut header: Option<Vec<String>> = None;
+    let mut rows = vec![];
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(block.as_bytes());
+    for record in reader.records() {
+        let record: Vec<String> = record
+            .map_err(|error| invalid_response(&error.to_string()))?
+            .iter()
+            .map(str::to_string)
+            .collect();
+        match record.first().map(String::as_str) {
+            Some("#datatype") => data_types = record,
+            Some("#group") => groups = record,
+            Some("#default") => defaults = record,
+            _ if header.is_none() => header = Some(record),
+            _ => rows.push(record),
+        }
+    }
+    let header = match header {
+        Some(header) => header,
+        None => return Ok(vec![]),
+    };
+    let skip = if header.first().map(String::is_empty).unwrap_or(false) { 1 } else { 0 };
+    let columns: Vec<FluxColumn> = header
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .map(|(index, name)| FluxColumn {
+            name: name.clone(),
+            data_type: data_types.get(index).cloned().unwrap_or_default(),
+            group: groups.get(index).map(|group| group == "true").unwrap_or(false),
+            default_value: defaults.get(index).cloned().unwrap_or_default(),
+        })
+        .collect();
+    if let Some(error) = columns.iter().position(|column| column.name == "error") {
+        if let Some(row) = rows.first() {
+            return Err(InfluxDbError::Failed(None, row.get(error + skip).cloned().unwrap_or_default()));
+        }
+    }
+    let table_index = columns.iter().position(|column| column.name == "table");
+    let mut tables: Vec<FluxTable> = vec![];
+    let mut current_table = None;
+    for row in rows {
+        let values: Vec<String> = columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| match row.get(index + skip) {
+                Some(value) if !value.is_empty() => value.clone(),
+                _ => column.default_value.clone(),
+            })
+            .collect();
+        let table_id = table_index.map(|index| values[index].clone());
+        if tables.is_empty() || table_id != current_table {
+            tables.push(FluxTable { columns: columns.clone(), rows: vec![] });
+            current_table = table_id;
+        }
+        if let Some(table) = tables.last_mut() {
+            table.rows.push(values);
+        }
+    }
+    Ok(tables)
+}
+
+fn to_field_value(column: &FluxColumn, value: &str) -> Result<FieldValue, InfluxDbError<Option<Error>>> {
+    let invalid = || invalid_response(&format!("invalid {} value '{}'", column.data_type, value));
+    match column.data_type.as_str() {
+        "double" => value.parse().map(FieldValue::Float).map_err(|_| invalid()),
+        "long" => value.parse().map(FieldValue::Integer).map_err(|_| invalid()),
+        "unsignedLong" => value.parse().map(FieldValue::UInteger).map_err(|_| invalid()),
+        "boolean" => value.parse().map(FieldValue::Boolean).map_err(|_| invalid()),
+        "string" => Ok(FieldValue::String(value.to_string())),
+        _ => Ok(infer_field_value(value)),
+    }
+}
+
+fn infer_field_value(value: &str) -> FieldValue {
+    if let Ok(boolean) = value.parse::<bool>() {
+        return FieldValue::Boolean(boolean);
+    }
+    match value.parse::<f64>() {
+        Ok(float) if float.is_finite() => FieldValue::Float(float),
+        _ => FieldValue::String(value.to_string()),
+    }
+}
+
+fn to_timestamp(value: &str) -> Result<i64, InfluxDbError<Option<Error>>> {
+    from_rfc3339(value)
+        .or_else(|| value.parse().ok())
+        .ok_or_else(|| invalid_response(&format!("invalid time '{}'", value)))
+}
+
+fn invalid_response(reason: &str) -> InfluxDbError<Option<Error>> {
+    InfluxDbError::Failed(None, format!("invalid response {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULTI_TABLE: &str = "#datatype,string,long,dateTime:RFC3339,dateTime:RFC3339,dateTime:RFC3339,double,string,string,string\r\n#group,false,false,true,true,false,false,true,true,true\r\n#default,_result,,,,,,,,\r\n,result,table,_start,_stop,_time,_value,_field,_measurement,host\r\n,,0,2024-05-01T00:00:00Z,2024-05-01T01:00:00Z,2024-05-01T00:10:00Z,12.5,usage_user,cpu,server01\r\n,,0,2024-05-01T00:00:00Z,2024-05-01T01:00:00Z,2024-05-01T00:20:00Z,13.25,usage_user,cpu,server01\r\n,,1,2024-05-01T00:00:00Z,2024-05-01T01:00:00Z,2024-05-01T00:10:00Z,40.125,usage_user,cpu,server02\r\n\r\n#datatype,string,long,dateTime:RFC3339,dateTime:RFC3339,dateTime:RFC3339,long,string,string,string\r\n#group,false,false,true,true,false,false,true,true,true\r\n#default,_result,,,,,,,,\r\n,result,table,_start,_stop,_time,_value,_field,_measurement,host\r\n,,2,2024-05-01T00:00:00Z,2024-05-01T01:00:00Z,2024-05-01T00:10:00Z,8,n_cpus,system,server01\r\n\r\n";
+
+    #[test]
+    fn parses_annotated_tables() {
+        let result = parse_flux_csv(MULTI_TABLE).unwrap();
+        assert_eq!(3, result.len());
+        assert_eq!(2, result[0].rows.len());
+        assert_eq!(Some("_result"), result[0].value(0, "result"));
+        assert_eq!(Some("server02"), result[1].value(0, "host"));
+        assert_eq!("long", result[2].columns[result[2].column_index("_value").unwrap()].data_type);
+        assert!(result[2].columns[result[2].column_index("host").unwrap()].group);
+    }
+
+    #[test]
+    fn parses_plain_csv() {
+        let result = parse_flux_csv(",result,table,_value,_field,_measurement\n,_result,0,1.5,usage,cpu\n").unwrap();
+        assert_eq!(1, result.len());
+        assert_eq!("", result[0].columns[0].data_type);
+        assert_eq!(Some("1.5"), result[0].value(0, "_value"));
+    }
+
+    #[test]
+    fn reports_flux_errors() {
+        let body = "#datatype,string,string\n#group,true,true\n#default,,\n,error,reference\n,type error: undefined identifier,897\n";
+        let result = parse_flux_csv(body);
+        assert_eq!("Rest call failed type error: undefined identifier", result.unwrap_err().to_string());
+    }
+
+    #[test]
+    fn maps_tables_to_points() {
+        let result = flux_tables_to_points(&parse_flux_csv(MULTI_TABLE).unwrap()).unwrap();
+        assert_eq!(4, result.len());
+        assert_eq!(
+            Point::new("cpu")
+                .tag("host", "server01")
+                .field("usage_user", 12.5)
+                .timestamp(1_714_522_200_000_000_000),
+            result[0]
+        );
+        assert_eq!(Some(&FieldValue::Integer(8)), result[3].fields.get("n_cpus"));
+    }
+
+    #[test]
+    fn infers_values_without_annotations() {
+        let tables = parse_flux_csv("_measurement,_field,_value,_time\ncpu,up,true,10\ncpu,state,ok,11\n").unwrap();
+        let result = flux_tables_to_points(&tables).unwrap();
+        assert_eq!(Some(&FieldValue::Boolean(true)), result[0].fields.get("up"));
+        assert_eq!(Some(&FieldValue::String("ok".to_string())), result[1].fields.get("state"));
+        assert_eq!(Some(11), result[1].timestamp);
+    }
+
+    #[test]
+    fn reports_missing_columns() {
+        let tables = parse_flux_csv("_field,_value\nup,true\n").unwrap();
+        let result = flux_tables_to_points(&tables);
+        assert_eq!("Rest call failed invalid response missing _measurement column", result.unwrap_err().to_string());
+    }
+}
@@ -0,0 +1,123 @@
+use reqwest::Error;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::influxdb_v3_query::{InfluxDbV3Query, QueryFormat};
+
+pub (crate) fn to_influxdb_v3_query_body(influxdb_config: &InfluxdbConfig, query: &InfluxDbV3Query) -> String {
+    let mut body = json!({
+        "db": influxdb_config.bucket,
+        "q": query.query,
+        "format": query.format,
+    });
+    if !query.params.is_empty() {
+        body["params"] = Value::Object(query.params.clone());
+    }
+    body.to_string()
+}
+
+pub (crate) fn map_v3_rows<R: DeserializeOwned>(format: QueryFormat, body: &[u8]) -> Result<Vec<R>, InfluxDbError<Option<Error>>> {
+    match format {
+        QueryFormat::Json => serde_json::from_slice(body)
+            .map_err(|error| invalid_response(error.to_string())),
+        QueryFormat::Jsonl => body
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.iter().all(u8::is_ascii_whitespace))
+            .map(|line| serde_json::from_slice(line).map_err(|error| invalid_response(error.to_string())))
+            .collect(),
+        QueryFormat::Csv => csv::Reader::from_reader(body)
+            .deserialize()
+            .map(|row| row.map_err(|error| invalid_response(error.to_string())))
+            .collect(),
+        QueryFormat::Parquet => Err(InfluxDbError::Failed(
+            None,
+            "parquet results cannot be mapped to rows".to_string(),
+        )),
+    }
+}
+
+fn invalid_response(reason: String) -> InfluxDbError<Option<Error>> {
+    InfluxDbError::Failed(None, format!("invalid response {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Row {
+        host: String,
+        usage: f64,
+    }
+
+    fn config() -> InfluxdbConfig {
+        InfluxdbConfig {
+            address: "address".to_string(),
+            organisation: "organisation".to_string(),
+            bucket: "bucket".to_string(),
+            influxdb_token_path: "influxdb_token_path".to_string(),
+            limits: None,
+        }
+    }
+
+    #[test]
+    fn to_influxdb_v3_query_body_correct() {
+        let result = to_influxdb_v3_query_body(&config(), &InfluxDbV3Query::sql("SELECT 1"));
+        assert_eq!(r#"{"db":"bucket","format":"json","q":"SELECT 1"}"#, result);
+    }
+
+    #[test]
+    fn to_influxdb_v3_query_body_with_params() {
+        let query = InfluxDbV3Query::sql("SELECT * FROM cpu WHERE host = $host")
+            .with_format(QueryFormat::Jsonl)
+            .with_param("host", Value::String("a".to_string()));
+        let result = to_influxdb_v3_query_body(&config(), &query);
+        assert_eq!(
+            r#"{"db":"bucket","format":"jsonl","params":{"host":"a"},"q":"SELECT * FROM cpu WHERE host = $host"}"#,
+            result
+        );
+    }
+
+    #[test]
+    fn map_v3_rows_json() {
+        let result: Vec<Row> = map_v3_rows(
+            QueryFormat::Json,
+            r#"[{"host":"a","usage":1.5},{"host":"b","usage":2.0}]"#.as_bytes()
+        ).expect("Cannot map");
+        assert_eq!(vec![Row { host: "a".to_string(), usage: 1.5 }, Row { host: "b".to_string(), usage: 2.0 }], result);
+    }
+
+    #[test]
+    fn map_v3_rows_jsonl() {
+        let result: Vec<Row> = map_v3_rows(
+            QueryFormat::Jsonl,
+            "{\"host\":\"a\",\"usage\":1.5}\n{\"host\":\"b\",\"usage\":2.0}\n".as_bytes()
+        ).expect("Cannot map");
+        assert_eq!(vec![Row { host: "a".to_string(), usage: 1.5 }, Row { host: "b".to_string(), usage: 2.0 }], result);
+    }
+
+    #[test]
+    fn map_v3_rows_csv() {
+        let result: Vec<Row> = map_v3_rows(
+            QueryFormat::Csv,
+            "host,usage\na,1.5\nb,2.0\n".as_bytes()
+        ).expect("Cannot map");
+        assert_eq!(vec![Row { host: "a".to_string(), usage: 1.5 }, Row { host: "b".to_string(), usage: 2.0 }], result);
+    }
+
+    #[test]
+    fn map_v3_rows_invalid() {
+        let result: Result<Vec<Row>, _> = map_v3_rows(QueryFormat::Json, "not json".as_bytes());
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().starts_with("Rest call failed invalid response"));
+    }
+
+    #[test]
+    fn map_v3_rows_parquet() {
+        let result: Result<Vec<Row>, _> = map_v3_rows(QueryFormat::Parquet, &[]);
+        assert!(result.is_err());
+        assert_eq!("Rest call failed parquet results cannot be mapped to rows", result.unwrap_err().to_string());
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::BTreeMap;
+use crate::error::line_protocol_error::LineProtocolError;
+use crate::model::point::{FieldValue, Point};
+
+const MEASUREMENT_ESCAPES: [char; 2] = [',', ' '];
+const KEY_ESCAPES: [char; 3] = [',', '=', ' '];
+
+pub fn parse_line_protocol(input: &str) -> Result<Vec<Point>, LineProtocolError> {
+    let mut points = vec![];
+    for (index, line) in input.split('\n').enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        points.push(LineParser::new(line, index + 1).parse()?);
+    }
+    Ok(points)
+}
+
+struct LineParser {
+    characters: Vec<char>,
+    position: usize,
+    line: usize,
+}
+
+impl LineParser {
+    fn new(line: &str, number: usize) -> Self {
+        LineParser {
+            characters: line.chars().collect(),
+            position: 0,
+            line: number,
+        }
+    }
+
+    fn parse(mut self) -> Result<Point, LineProtocolError> {
+        self.skip_spaces();
+        let measurement = self.read_key(&MEASUREMENT_ESCAPES, &[',', ' ']);
+        if measurement.is_empty() {
+            return Err(self.error("missing measurement"));
+        }
+        let mut tags = BTreeMap::new();
+        while self.peek() == Some(',') {
+            self.position += 1;
+            let start = self.position;
+            let key = self.read_key(&KEY_ESCAPES, &[',', ' ', '=']);
+            if key.is_empty() {
+                return Err(self.error_at(start, "missing tag key"));
+            }
+            if self.peek() != Some('=') {
+                return Err(self.error("missing tag value"));
+            }
+            self.position += 1;
+            let start = self.position;
+            let value = self.read_key(&KEY_ESCAPES, &[',', ' ', '=']);
+            if value.is_empty() {
+                return Err(self.error_at(start, "missing tag value"));
+            }
+            if self.peek() == Some('=') {
+                return Err(self.error("unescaped '=' in tag value"));
+            }
+            tags.insert(key, value);
+        }
+        if self.peek() != Some(' ') {
+            return Err(self.error("missing fields"));
+        }
+        self.skip_spaces();
+        let mut fields = BTreeMap::new();
+        loop {
+            let start = self.position;
+            let key = self.read_key(&KEY_ESCAPES, &[',', ' ', '=']);
+            if key.is_empty() {
+                return Err(self.error_at(start, "missing field key"));
+            }
+            if self.peek() != Some('=') {
+                return Err(self.error("missing field value"));
+            }
+            self.position += 1;
+            let value = self.read_field_value()?;
+            fields.insert(key, value);
+            if self.peek() != Some(',') {
+                break;
+            }
+            self.position += 1;
+        }
+        let mut timestamp = None;
+        if self.peek() == Some(' ') {
+            self.skip_spaces();
+            if self.peek().is_some() {
+                let start = self.position;
+                let value = self.read_until(&[' ']);
+                timestamp = Some(value.parse::<i64>().map_err(|_| self.error_at(start, "invalid timestamp"))?);
+            }
+        }
+        self.skip_spaces();
+        if self.peek().is_some() {
+            return Err(self.error("unexpected trailing characters"));
+        }
+        Ok(Point { measurement, tags, fields, timestamp })
+    }
+
+    fn read_key(&mut self, escapes: &[char], delimiters: &[char]) -> String {
+        let mut result = String::new();
+        while let Some(character) = self.peek() {
+            if character == '\\' {
+                if let Some(next) = self.characters.get(self.position + 1).filter(|next| escapes.contains(next)) {
+                    result.push(*next);
+                    self.position += 2;
+                    continue;
+                }
+            } else if delimiters.contains(&character) {
+                break;
+            }
+            result.push(character);
+            self.position += 1;
+        }
+        result
+    }
+
+    fn read_until(&mut self, delimiters: &[char]) -> String {
+        let start = self.position;
+        while self.peek().map(|character| !delimiters.contains(&character)).unwrap_or(false) {
+            self.position += 1;
+        }
+        self.characters[start..self.position].iter().collect()
+    }
+
+    fn read_field_value(&mut self) -> Result<FieldValue, LineProtocolError> {
+        let start = self.position;
+        if self.peek() == Some('"') {
+            self.position += 1;
+            let mut result = String::new();
+            loop {
+                match self.peek() {
+                    None => return Err(self.error_at(start, "unterminated string field value")),
+                    Some('\\') if matches!(self.characters.get(self.position + 1), Some('"') | Some('\\')) => {
+                        result.push(self.characters[self.position + 1]);
+                        self.position += 2;
+                    }
+                    Some('"') => {
+                        self.position += 1;
+                        return Ok(FieldValue::String(result));
+                    }
+                    Some(character) => {
+                        result.push(character);
+                        self.position += 1;
+                    }
+                }
+            }
+        }
+        let value = self.read_until(&[',', ' ']);
+        parse_field_value(&value).ok_or_else(|| {
+            if value.is_empty() {
+                self.error_at(start, "missing field value")
+            } else {
+                self.error_at(start, &format!("invalid field value '{}'", value))
+            }
+        })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.characters.get(self.position).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek() == Some(' ') || self.peek() == Some('\t') {
+            self.position += 1;
+        }
+    }
+
+    fn error(&self, reason: &str) -> LineProtocolError {
+        self.error_at(self.position, reason)
+    }
+
+    fn error_at(&self, position: usize, reason: &str) -> LineProtocolError {
+        LineProtocolError {
+            line: self.line,
+            column: position + 1,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+fn parse_field_value(value: &str) -> Option<FieldValue> {
+    match value {
+        "t" | "T" | "true" | "True" | "TRUE" => return Some(FieldValue::Boolean(true)),
+        "f" | "F" | "false" | "False" | "FALSE" => return Some(FieldValue::Boolean(false)),
+        _ => {}
+    }
+    if let Some(integer) = value.strip_suffix('i') {
+        return integer.parse().ok().map(FieldValue::Integer);
+    }
+    if let Some(unsigned) = value.strip_suffix('u') {
+        return unsigned.parse().ok().map(FieldValue::UInteger);
+    }
+    if value.is_empty() || !value.chars().all(|character| character.is_ascii_digit() || "+-.eE".contains(character)) {
+        return None;
+    }
+    value.parse::<f64>().ok().filter(|float| float.is_finite()).map(FieldValue::Float)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_all_field_types() {
+        let result = parse_line_protocol(r#"cpu,host=a,region=eu f=1.5,e=-1e3,i=-3i,u=4u,s="ok",b=t,B=FALSE 1700000000000000000"#).unwrap();
+        assert_eq!(
+            vec![
+                Point::new("cpu")
+                    .tag("host", "a")
+                    .tag("region", "eu")
+                    .field("f", 1.5)
+                    .field("e", -1000.0)
+                    .field("i", -3_i64)
+                    .field("u", 4_u64)
+                    .field("s", "ok")
+                    .field("b", true)
+                    .field("B", false)
+                    .timestamp(1_700_000_000_000_000_000)
+            ],
+            result
+        );
+    }
+
+    #[test]
+    fn parses_escapes() {
+        let result = parse_line_protocol(r#"my\ cpu\,x,ta\ g\=k=v\,a\ l\=ue f\=k="say \"hi\" \\ there",plain\x=1i"#).unwrap();
+        assert_eq!(1, result.len());
+        assert_eq!("my cpu,x", result[0].measurement);
+        assert_eq!(Some(&"v,a l=ue".to_string()), result[0].tags.get("ta g=k"));
+        assert_eq!(Some(&FieldValue::String(r#"say "hi" \ there"#.to_string())), result[0].fields.get("f=k"));
+        assert_eq!(Some(&FieldValue::Integer(1)), result[0].fields.get(r"plain\x"));
+        assert_eq!(None, result[0].timestamp);
+    }
+
+    #[test]
+    fn measurement_keeps_escaped_equals() {
+        let result = parse_line_protocol(r"a\=b f=1").unwrap();
+        assert_eq!(r"a\=b", result[0].measurement);
+    }
+
+    #[test]
+    fn string_field_may_contain_delimiters() {
+        let result = parse_line_protocol(r#"log message="a, b=c d" 5"#).unwrap();
+        assert_eq!(Some(&FieldValue::String("a, b=c d".to_string())), result[0].fields.get("message"));
+        assert_eq!(Some(5), result[0].timestamp);
+    }
+
+    #[test]
+    fn skips_comments_and_blank_lines() {
+        let result = parse_line_protocol("# comment\r\n\r\ncpu f=1\r\n  \ncpu f=2\n").unwrap();
+        assert_eq!(2, result.len());
+    }
+
+    #[test]
+    fn reports_missing_field_value() {
+        let result = parse_line_protocol("cpu f=1\ncpu,host=a usage=");
+        assert_eq!(
+            LineProtocolError { line: 2, column: 18, reason: "missing field value".to_string() },
+            result.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn reports_missing_fields() {
+        let result = parse_line_protocol("cpu,host=a");
+        assert_eq!(
+            LineProtocolError { line: 1, column: 11, reason: "missing fields".to_string() },
+            result.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn reports_missing_tag_value() {
+        let result = parse_line_protocol("cpu,host= f=1");
+        assert_eq!(
+            LineProtocolError { line: 1, column: 10, reason: "missing tag value".to_string() },
+            result.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn reports_invalid_field_value() {
+        let result = parse_line_protocol("cpu f=1.5x");
+        assert_eq!(
+            LineProtocolError { line: 1, column: 7, reason: "invalid field value '1.5x'".to_string() },
+            result.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn reports_unterminated_string() {
+        let result = parse_line_protocol(r#"cpu f="abc"#);
+        assert_eq!(
+            LineProtocolError { line: 1, column: 7, reason: "unterminated string field value".to_string() },
+            result.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn reports_invalid_timestamp() {
+        let result = parse_line_protocol("cpu f=1 12a");
+        assert_eq!(
+            LineProtocolError { line: 1, column: 9, reason: "invalid timestamp".to_string() },
+            result.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn reports_trailing_characters() {
+        let result = parse_line_protocol("cpu f=1 12 13");
+        assert_eq!(
+            LineProtocolError { line: 1, column: 12, reason: "unexpected trailing characters".to_string() },
+            result.unwrap_err()
+        );
+    }
+
+    #[test]
+    fn reports_columns_in_characters() {
+        let result = parse_line_protocol("températur f=");
+        assert_eq!(
+            LineProtocolError { line: 1, column: 14, reason: "missing field value".to_string() },
+            result.unwrap_err()
+        );
+    }
+}
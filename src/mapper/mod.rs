@@ -1,4 +1,12 @@
+#[cfg(feature = "arrow")]
+pub mod arrow_mapper;
+pub (crate) mod csv_import_mapper;
+pub mod flux_csv_mapper;
 pub mod influxdb_payload_mapper;
+pub (crate) mod influxdb_v3_mapper;
+pub mod line_protocol_parser;
+pub mod point_mapper;
 pub (crate) mod request_mapper;
 pub (crate) mod response_mapper;
+pub (crate) mod time_mapper;
 pub (crate) mod url_mapper;
\ No newline at end of file
@@ -0,0 +1,179 @@
+use log::warn;
+use crate::error::point_error::PointError;
+use crate::mapper::influxdb_payload_mapper::InfluxDbPayloadMapper;
+use crate::model::point::{FieldValue, Point};
+
+#[derive(Clone)]
+pub struct PointMapper;
+
+impl InfluxDbPayloadMapper<Point> for PointMapper {
+    fn item(&self, payload: Point) -> String {
+        to_line_protocol(&payload).unwrap_or_else(|error| {
+            warn!("Skipping point: {}", error);
+            String::new()
+        })
+    }
+
+    fn items(&self, payloads: Vec<Point>) -> String {
+        payloads
+            .into_iter()
+            .map(|payload| self.item(payload))
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+pub fn to_line_protocol(point: &Point) -> Result<String, PointError> {
+    if point.measurement.is_empty() {
+        return Err(PointError::EmptyMeasurement);
+    }
+    if has_line_break(point) {
+        return Err(PointError::LineBreak(point.measurement.clone()));
+    }
+    let mut line = escape(&point.measurement, &[',', ' ']);
+    for (key, value) in point.tags.iter().filter(|(key, value)| !key.is_empty() && !value.is_empty()) {
+        line.push(',');
+        line.push_str(&escape(key, &[',', '=', ' ']));
+        line.push('=');
+        line.push_str(&escape(value, &[',', '=', ' ']));
+    }
+    let fields: Vec<String> = point
+        .fields
+        .iter()
+        .filter(|(_, value)| !matches!(value, FieldValue::Float(float) if !float.is_finite()))
+        .map(|(key, value)| format!("{}={}", escape(key, &[',', '=', ' ']), format_field_value(value)))
+        .collect();
+    if fields.is_empty() {
+        return Err(PointError::NoFields(point.measurement.clone()));
+    }
+    line.push(' ');
+    line.push_str(&fields.join(","));
+    if let Some(timestamp) = point.timestamp {
+        line.push(' ');
+        line.push_str(&timestamp.to_string());
+    }
+    Ok(line)
+}
+
+fn has_line_break(point: &Point) -> bool {
+    let is_line_break = |value: &str| value.contains(['\n', '\r']);
+    is_line_break(&point.measurement)
+        || point.tags.iter().any(|(key, value)| is_line_break(key) || is_line_break(value))
+        || point.fields.iter().any(|(key, value)| {
+            is_line_break(key) || matches!(value, FieldValue::String(value) if is_line_break(value))
+        })
+}
+
+fn format_field_value(value: &FieldValue) -> String {
+    match value {
+        FieldValue::Float(value) => value.to_string(),
+        FieldValue::Integer(value) => format!("{}i", value),
+        FieldValue::UInteger(value) => format!("{}u", value),
+        FieldValue::String(value) => format!("\"{}\"", escape(value, &['\\', '"'])),
+        FieldValue::Boolean(value) => value.to_string(),
+    }
+}
+
+fn escape(value: &str, characters: &[char]) -> String {
+    let mut result = String::with_capacity(value.len());
+    for character in value.chars() {
+        if characters.contains(&character) {
+            result.push('\\');
+        }
+        result.push(character);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapper::line_protocol_parser::parse_line_protocol;
+
+    #[test]
+    fn maps_point() {
+        let result = PointMapper.item(
+            Point::new("cpu")
+                .tag("host", "a")
+                .field("usage", 1.5)
+                .field("count", 3_i64)
+                .field("total", 4_u64)
+                .field("up", true)
+                .field("state", "ok")
+                .timestamp(10)
+        );
+        assert_eq!(r#"cpu,host=a count=3i,state="ok",total=4u,up=true,usage=1.5 10"#, result);
+    }
+
+    #[test]
+    fn maps_points() {
+        let result = PointMapper.items(vec![
+            Point::new("cpu").field("usage", 1.0),
+            Point::new("mem").field("used", 2_i64),
+        ]);
+        assert_eq!("cpu usage=1\nmem used=2i", result);
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        let result = to_line_protocol(
+            &Point::new("my cpu,x")
+                .tag("ta g=k", "v,a l=ue")
+                .field("f=k", r#"say "hi" \ there"#)
+        ).unwrap();
+        assert_eq!(r#"my\ cpu\,x,ta\ g\=k=v\,a\ l\=ue f\=k="say \"hi\" \\ there""#, result);
+    }
+
+    #[test]
+    fn skips_non_finite_floats() {
+        let result = to_line_protocol(&Point::new("cpu").field("a", f64::NAN).field("b", 1.0));
+        assert_eq!(Ok("cpu b=1".to_string()), result);
+    }
+
+    #[test]
+    fn rejects_points_without_writable_fields() {
+        let result = to_line_protocol(&Point::new("cpu").tag("host", "a").field("a", f64::NAN).field("b", f64::INFINITY));
+        assert_eq!(Err(PointError::NoFields("cpu".to_string())), result);
+        assert_eq!(Err(PointError::NoFields("cpu".to_string())), to_line_protocol(&Point::new("cpu")));
+        assert_eq!(Err(PointError::EmptyMeasurement), to_line_protocol(&Point::new("").field("a", 1.0)));
+    }
+
+    #[test]
+    fn skips_empty_tags() {
+        let result = to_line_protocol(&Point::new("cpu").tag("host", "").tag("", "a").tag("region", "eu").field("a", 1.0));
+        assert_eq!(Ok("cpu,region=eu a=1".to_string()), result);
+    }
+
+    #[test]
+    fn rejects_line_breaks() {
+        let expected = Err(PointError::LineBreak("cpu".to_string()));
+        assert_eq!(expected, to_line_protocol(&Point::new("cpu").tag("host", "a\nb").field("a", 1.0)));
+        assert_eq!(expected, to_line_protocol(&Point::new("cpu").tag("ho\rst", "a").field("a", 1.0)));
+        assert_eq!(expected, to_line_protocol(&Point::new("cpu").field("message", "first\nsecond")));
+        assert_eq!(expected, to_line_protocol(&Point::new("cpu").field("a\nb", 1.0)));
+        assert_eq!(Err(PointError::LineBreak("c\npu".to_string())), to_line_protocol(&Point::new("c\npu").field("a", 1.0)));
+    }
+
+    #[test]
+    fn mapper_skips_invalid_points() {
+        let result = PointMapper.items(vec![Point::new("cpu").field("a", f64::NAN), Point::new("mem").field("used", 1_i64)]);
+        assert_eq!("mem used=1i", result);
+        let result = PointMapper.items(vec![Point::new("cpu").field("a", 1.0), Point::new(""), Point::new("mem").field("used", 1_i64)]);
+        assert_eq!("cpu a=1\nmem used=1i", result);
+    }
+
+    #[test]
+    fn round_trips_through_parser() {
+        let point = Point::new("my cpu,x")
+            .tag("ta g=k", "v,a l=ue")
+            .field("f=k", r#"say "hi" \ there"#)
+            .field("i", -3_i64)
+            .field("u", 4_u64)
+            .field("f", 0.1)
+            .field("b", false)
+            .timestamp(-5);
+        let result = parse_line_protocol(&to_line_protocol(&point).unwrap()).unwrap();
+        assert_eq!(vec![point], result);
+    }
+}
@@ -1,14 +1,77 @@
-use reqwest::{RequestBuilder, Client};
+use reqwest::{Method, RequestBuilder, Client};
 use std::time::Duration;
+use crate::model::secret::Secret;
 
-pub (crate) fn get_request(influxdb_token: String, url: String, body: String) -> RequestBuilder {
+pub (crate) fn get_request(influxdb_token: Secret, url: String, body: String) -> RequestBuilder {
     let client = Client::new();
     client.post(&url)
-        .header("Authorization", format!("Token {}", influxdb_token))
+        .header("Authorization", format!("Token {}", influxdb_token.expose()))
         .body(body)
         .timeout(Duration::from_secs(5))
 }
 
+pub (crate) fn get_v3_request(influxdb_token: Secret, url: String, body: String) -> RequestBuilder {
+    let client = Client::new();
+    client.post(&url)
+        .header("Authorization", format!("Bearer {}", influxdb_token.expose()))
+        .body(body)
+        .timeout(Duration::from_secs(5))
+}
+
+pub (crate) fn get_api_request(influxdb_token: Secret, method: Method, url: String) -> RequestBuilder {
+    let client = Client::new();
+    client.request(method, &url)
+        .header("Authorization", format!("Token {}", influxdb_token.expose()))
+        .timeout(Duration::from_secs(5))
+}
+
+pub (crate) fn get_basic_request(username: &str, password: &Secret, method: Method, url: String) -> RequestBuilder {
+    let client = Client::new();
+    client.request(method, &url)
+        .basic_auth(username, Some(password.expose()))
+        .timeout(Duration::from_secs(5))
+}
+
+pub (crate) fn get_session_request(session: &Secret, method: Method, url: String) -> RequestBuilder {
+    let client = Client::new();
+    client.request(method, &url)
+        .header("Cookie", session.expose())
+        .timeout(Duration::from_secs(5))
+}
+
+#[cfg(feature = "blocking")]
+pub (crate) fn get_blocking_request(influxdb_token: Secret, url: String, body: String) -> reqwest::blocking::RequestBuilder {
+    let client = reqwest::blocking::Client::new();
+    client.post(&url)
+        .header("Authorization", format!("Token {}", influxdb_token.expose()))
+        .body(body)
+        .timeout(Duration::from_secs(5))
+}
+
+#[cfg(feature = "blocking")]
+pub (crate) fn get_blocking_api_request(influxdb_token: Secret, method: Method, url: String) -> reqwest::blocking::RequestBuilder {
+    let client = reqwest::blocking::Client::new();
+    client.request(method, &url)
+        .header("Authorization", format!("Token {}", influxdb_token.expose()))
+        .timeout(Duration::from_secs(5))
+}
+
+#[cfg(feature = "blocking")]
+pub (crate) fn get_blocking_basic_request(username: &str, password: &Secret, method: Method, url: String) -> reqwest::blocking::RequestBuilder {
+    let client = reqwest::blocking::Client::new();
+    client.request(method, &url)
+        .basic_auth(username, Some(password.expose()))
+        .timeout(Duration::from_secs(5))
+}
+
+#[cfg(feature = "blocking")]
+pub (crate) fn get_blocking_v3_request(influxdb_token: Secret, url: String, body: String) -> reqwest::blocking::RequestBuilder {
+    let client = reqwest::blocking::Client::new();
+    client.post(&url)
+        .header("Authorization", format!("Bearer {}", influxdb_token.expose()))
+        .body(body)
+        .timeout(Duration::from_secs(5))
+}
 
 #[cfg(test)]
 mod tests {
@@ -17,7 +80,7 @@ mod tests {
     #[test]
     fn get_request_correct() {
         let result = get_request(
-            "token".to_string(),
+            Secret::from("token"),
             "http://example.com".to_string(),
             "body".to_string(),
         ).build();
@@ -34,4 +97,56 @@ mod tests {
         let body = body.as_bytes().unwrap();
         assert_eq!("body".as_bytes(), body);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn get_v3_request_correct() {
+        let result = get_v3_request(
+            Secret::from("token"),
+            "http://example.com".to_string(),
+            "body".to_string(),
+        ).build();
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!("http://example.com/", result.url().to_string());
+        let token = result.headers().get("Authorization");
+        assert!(token.is_some());
+        assert_eq!("Bearer token".as_bytes(), token.unwrap().as_bytes());
+        let body = result.body().unwrap().as_bytes().unwrap();
+        assert_eq!("body".as_bytes(), body);
+    }
+
+    #[test]
+    fn get_api_request_correct() {
+        let result = get_api_request(
+            Secret::from("token"),
+            Method::DELETE,
+            "http://example.com/api/v2/buckets/1".to_string(),
+        ).build().unwrap();
+        assert_eq!(Method::DELETE, result.method());
+        assert_eq!("http://example.com/api/v2/buckets/1", result.url().to_string());
+        assert_eq!("Token token".as_bytes(), result.headers().get("Authorization").unwrap().as_bytes());
+    }
+
+    #[test]
+    fn get_basic_request_correct() {
+        let result = get_basic_request(
+            "user",
+            &Secret::from("pass"),
+            Method::POST,
+            "http://example.com/api/v2/signin".to_string(),
+        ).build().unwrap();
+        assert_eq!(Method::POST, result.method());
+        assert_eq!("Basic dXNlcjpwYXNz".as_bytes(), result.headers().get("Authorization").unwrap().as_bytes());
+    }
+
+    #[test]
+    fn get_session_request_correct() {
+        let result = get_session_request(
+            &Secret::from("influxdb-oss-session=abc"),
+            Method::POST,
+            "http://example.com/api/v2/signout".to_string(),
+        ).build().unwrap();
+        assert_eq!("influxdb-oss-session=abc".as_bytes(), result.headers().get("Cookie").unwrap().as_bytes());
+        assert!(result.headers().get("Authorization").is_none());
+    }
+}
@@ -1,7 +1,19 @@
-use reqwest::{Response, Error};
+use std::time::Duration;
+use reqwest::{Response, Error, StatusCode};
+use reqwest::header::{RETRY_AFTER, SET_COOKIE};
+use serde::de::DeserializeOwned;
 use crate::error::influxdb_error::InfluxDbError;
+use crate::model::client_stats::ErrorKind;
 use log::{debug, error};
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub (crate) enum WriteFailure {
+    Retryable(Option<Duration>),
+    InvalidData,
+    Unauthorized,
+    Rejected,
+}
+
 pub (crate) async fn map_response(result: Result<Response, Error>) -> Result<String, InfluxDbError<Option<Error>>> {
     return match result {
         Err(error) => {
@@ -25,6 +37,176 @@ pub (crate) async fn map_response(result: Result<Response, Error>) -> Result<Str
     };
 }
 
+pub (crate) async fn map_json_response<T: DeserializeOwned>(result: Result<Response, Error>) -> Result<T, InfluxDbError<Option<Error>>> {
+    let body = map_response(result).await?;
+    serde_json::from_str(&body)
+        .map_err(|error| InfluxDbError::Failed(None, format!("invalid response {}", error)))
+}
+
+pub (crate) async fn map_bytes_response(result: Result<Response, Error>) -> Result<Vec<u8>, InfluxDbError<Option<Error>>> {
+    match result {
+        Err(error) => {
+            error!("Error: {:#?}", error);
+            Err(InfluxDbError::Failed(Some(error), "request failed".to_string()))
+        }
+        Ok(result) => {
+            let status = result.status();
+            if !status.is_success() {
+                let body = result.text().await;
+                debug!("Result: {:#?}", body);
+                return Err(map_bad_response(body, status.to_string()));
+            }
+            match result.bytes().await {
+                Ok(body) => {
+                    debug!("Result: {} bytes", body.len());
+                    Ok(body.to_vec())
+                }
+                Err(error) => Err(InfluxDbError::Failed(Some(error), "".to_string())),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+pub (crate) fn map_blocking_response(result: Result<reqwest::blocking::Response, Error>) -> Result<String, InfluxDbError<Option<Error>>> {
+    match result {
+        Err(error) => {
+            error!("Error: {:#?}", error);
+            Err(InfluxDbError::Failed(Some(error), "request failed".to_string()))
+        }
+        Ok(result) => {
+            let status = result.status();
+            let body = result.text();
+            debug!("Result: {:#?}", body);
+            if !status.is_success() {
+                return Err(map_bad_response(body, status.to_string()));
+            }
+            body.map_err(|error| InfluxDbError::Failed(Some(error), "".to_string()))
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+pub (crate) fn map_blocking_json_response<T: DeserializeOwned>(result: Result<reqwest::blocking::Response, Error>) -> Result<T, InfluxDbError<Option<Error>>> {
+    let body = map_blocking_response(result)?;
+    serde_json::from_str(&body)
+        .map_err(|error| InfluxDbError::Failed(None, format!("invalid response {}", error)))
+}
+
+#[cfg(feature = "blocking")]
+pub (crate) fn map_blocking_bytes_response(result: Result<reqwest::blocking::Response, Error>) -> Result<Vec<u8>, InfluxDbError<Option<Error>>> {
+    match result {
+        Err(error) => {
+            error!("Error: {:#?}", error);
+            Err(InfluxDbError::Failed(Some(error), "request failed".to_string()))
+        }
+        Ok(result) => {
+            let status = result.status();
+            if !status.is_success() {
+                let body = result.text();
+                debug!("Result: {:#?}", body);
+                return Err(map_bad_response(body, status.to_string()));
+            }
+            result
+                .bytes()
+                .map(|body| body.to_vec())
+                .map_err(|error| InfluxDbError::Failed(Some(error), "".to_string()))
+        }
+    }
+}
+
+pub (crate) fn to_session_cookie(response: &Response) -> Option<String> {
+    let cookies: Vec<&str> = response
+        .headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| value.split(';').next())
+        .map(str::trim)
+        .filter(|cookie| !cookie.is_empty())
+        .collect();
+    match cookies.is_empty() {
+        true => None,
+        false => Some(cookies.join("; ")),
+    }
+}
+
+pub (crate) fn to_write_failure(result: &Result<Response, Error>) -> Option<WriteFailure> {
+    let response = match result {
+        Err(_) => return Some(WriteFailure::Retryable(None)),
+        Ok(response) => response,
+    };
+    match response.status() {
+        status if status.is_success() => None,
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => Some(WriteFailure::Retryable(retry_after(response))),
+        status if status.is_server_error() => Some(WriteFailure::Retryable(None)),
+        StatusCode::BAD_REQUEST | StatusCode::PAYLOAD_TOO_LARGE | StatusCode::UNPROCESSABLE_ENTITY => Some(WriteFailure::InvalidData),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Some(WriteFailure::Unauthorized),
+        _ => Some(WriteFailure::Rejected),
+    }
+}
+
+pub (crate) fn is_unauthorized(error: &InfluxDbError<Option<Error>>) -> bool {
+    let InfluxDbError::Failed(_, message) = error;
+    if message.starts_with("401") || message.starts_with("403") {
+        return true;
+    }
+    serde_json::from_str::<serde_json::Value>(message)
+        .ok()
+        .and_then(|body| body["code"].as_str().map(|code| code == "unauthorized" || code == "forbidden"))
+        .unwrap_or(false)
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+pub (crate) fn is_node_failure(error_kind: Option<ErrorKind>) -> bool {
+    matches!(error_kind, Some(ErrorKind::Transport | ErrorKind::Timeout | ErrorKind::ServerError))
+}
+
+pub (crate) fn to_error_kind(result: &Result<Response, Error>) -> Option<ErrorKind> {
+    match result {
+        Err(error) if error.is_timeout() => Some(ErrorKind::Timeout),
+        Err(_) => Some(ErrorKind::Transport),
+        Ok(response) => status_error_kind(response.status()),
+    }
+}
+
+#[cfg(feature = "blocking")]
+pub (crate) fn to_blocking_error_kind(result: &Result<reqwest::blocking::Response, Error>) -> Option<ErrorKind> {
+    match result {
+        Err(error) if error.is_timeout() => Some(ErrorKind::Timeout),
+        Err(_) => Some(ErrorKind::Transport),
+        Ok(response) => status_error_kind(response.status()),
+    }
+}
+
+pub (crate) fn outcome<T, E>(error_kind: Option<ErrorKind>, result: &Result<T, E>) -> Option<ErrorKind> {
+    match result {
+        Ok(_) => None,
+        Err(_) => error_kind.or(Some(ErrorKind::Transport)),
+    }
+}
+
+pub (crate) fn status_error_kind(status: StatusCode) -> Option<ErrorKind> {
+    match status {
+        status if status.is_success() => None,
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Some(ErrorKind::Unauthorized),
+        StatusCode::TOO_MANY_REQUESTS => Some(ErrorKind::RateLimited),
+        status if status.is_server_error() => Some(ErrorKind::ServerError),
+        _ => Some(ErrorKind::ClientError),
+    }
+}
+
 fn map_bad_response(body: reqwest::Result<String>, status: String) -> InfluxDbError<Option<Error>> {
     InfluxDbError::Failed(
         None,
@@ -44,6 +226,7 @@ fn map_bad_response(body: reqwest::Result<String>, status: String) -> InfluxDbEr
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::fake_influxdb::{FakeFailure, FakeInfluxDb};
     use crate::test_support::http_server::setup_test_harness;
     use reqwest::Client;
 
@@ -146,4 +329,114 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!("test", result.unwrap().to_string());
     }
-}
\ No newline at end of file
+
+    #[actix_rt::test]
+    async fn map_bytes_response_error_with_body() {
+        let harness = setup_test_harness();
+        let url = harness.url("body-response");
+        let client = Client::new();
+        let result = client.post(url)
+            .send()
+            .await;
+        let result = map_bytes_response(result).await;
+        assert!(result.is_err());
+        assert_eq!("Rest call failed Some terrible error", result.unwrap_err().to_string());
+    }
+
+    #[actix_rt::test]
+    async fn map_bytes_response_success() {
+        let harness = setup_test_harness();
+        let url = harness.url("success");
+        let client = Client::new();
+        let result = client.post(url)
+            .send()
+            .await;
+        let result = map_bytes_response(result).await;
+        assert!(result.is_ok());
+        assert_eq!("test".as_bytes(), result.unwrap().as_slice());
+    }
+
+    #[actix_rt::test]
+    async fn to_write_failure_server_error() {
+        let harness = setup_test_harness();
+        let result = Client::new().post(harness.url("body-response")).send().await;
+        assert_eq!(Some(WriteFailure::Retryable(None)), to_write_failure(&result));
+    }
+
+    #[actix_rt::test]
+    async fn to_write_failure_client_error() {
+        let harness = setup_test_harness();
+        let result = Client::new().post(harness.url("some-bad-url")).send().await;
+        assert_eq!(Some(WriteFailure::Rejected), to_write_failure(&result));
+    }
+
+    #[actix_rt::test]
+    async fn to_write_failure_failed_request() {
+        let result = Client::new().post("http://127.0.0.1:1").send().await;
+        assert_eq!(Some(WriteFailure::Retryable(None)), to_write_failure(&result));
+    }
+
+    #[test]
+    fn is_unauthorized_matches_status_and_code() {
+        assert!(is_unauthorized(&InfluxDbError::Failed(None, "401 Unauthorized".to_string())));
+        assert!(is_unauthorized(&InfluxDbError::Failed(None, r#"{"code":"forbidden","message":"insufficient permissions"}"#.to_string())));
+        assert!(!is_unauthorized(&InfluxDbError::Failed(None, r#"{"code":"not found","message":"bucket not found"}"#.to_string())));
+        assert!(!is_unauthorized(&InfluxDbError::Failed(None, "request failed".to_string())));
+    }
+
+    #[actix_rt::test]
+    async fn to_write_failure_reads_retry_after() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        fake.inject_failure(FakeFailure::TooManyRequests { retry_after_seconds: 3 });
+        let url = format!("{}/api/v2/write?bucket=bucket", fake.address());
+        let result = Client::new().post(&url).header("Authorization", "Token token").body("cpu usage=1").send().await;
+        assert_eq!(Some(WriteFailure::Retryable(Some(Duration::from_secs(3)))), to_write_failure(&result));
+        let result = Client::new().post(&url).header("Authorization", "Token wrong").body("cpu usage=1").send().await;
+        assert_eq!(Some(WriteFailure::Unauthorized), to_write_failure(&result));
+        let result = Client::new().post(&url).header("Authorization", "Token token").body("cpu usage=").send().await;
+        assert_eq!(Some(WriteFailure::InvalidData), to_write_failure(&result));
+    }
+
+    #[actix_rt::test]
+    async fn is_node_failure_server_error() {
+        let harness = setup_test_harness();
+        let result = Client::new().post(harness.url("body-response")).send().await;
+        assert!(is_node_failure(to_error_kind(&result)));
+    }
+
+    #[actix_rt::test]
+    async fn is_node_failure_client_error() {
+        let harness = setup_test_harness();
+        let result = Client::new().post(harness.url("some-bad-url")).send().await;
+        assert!(!is_node_failure(to_error_kind(&result)));
+    }
+
+    #[actix_rt::test]
+    async fn is_node_failure_failed_request() {
+        let result = Client::new().post("http://127.0.0.1:1").send().await;
+        assert!(is_node_failure(to_error_kind(&result)));
+    }
+
+    #[actix_rt::test]
+    async fn to_error_kind_classifies_results() {
+        let harness = setup_test_harness();
+        let success = Client::new().post(harness.url("success")).send().await;
+        let server_error = Client::new().post(harness.url("body-response")).send().await;
+        let client_error = Client::new().post(harness.url("some-bad-url")).send().await;
+        let failed = Client::new().post("http://127.0.0.1:1").send().await;
+        assert_eq!(None, to_error_kind(&success));
+        assert_eq!(Some(ErrorKind::ServerError), to_error_kind(&server_error));
+        assert_eq!(Some(ErrorKind::ClientError), to_error_kind(&client_error));
+        assert_eq!(Some(ErrorKind::Transport), to_error_kind(&failed));
+        assert_eq!(Some(ErrorKind::RateLimited), status_error_kind(StatusCode::TOO_MANY_REQUESTS));
+        assert_eq!(Some(ErrorKind::Unauthorized), status_error_kind(StatusCode::FORBIDDEN));
+    }
+
+    #[actix_rt::test]
+    async fn map_json_response_invalid_body() {
+        let harness = setup_test_harness();
+        let result = Client::new().post(harness.url("success")).send().await;
+        let result: Result<serde_json::Value, _> = map_json_response(result).await;
+        assert_eq!("Rest call failed invalid response expected ident at line 1 column 2", result.unwrap_err().to_string());
+    }
+}
@@ -0,0 +1,142 @@
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+#[cfg(any(test, feature = "test-support"))]
+pub (crate) fn to_rfc3339(timestamp_nanos: i64) -> String {
+    let seconds = timestamp_nanos.div_euclid(NANOS_PER_SECOND);
+    let nanos = timestamp_nanos.rem_euclid(NANOS_PER_SECOND);
+    let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+    let second_of_day = seconds.rem_euclid(SECONDS_PER_DAY);
+    let mut result = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        second_of_day / 3600,
+        (second_of_day % 3600) / 60,
+        second_of_day % 60
+    );
+    if nanos > 0 {
+        let fraction = format!("{:09}", nanos);
+        result.push('.');
+        result.push_str(fraction.trim_end_matches('0'));
+    }
+    result.push('Z');
+    result
+}
+
+pub (crate) fn from_rfc3339(value: &str) -> Option<i64> {
+    let bytes = value.as_bytes();
+    if !value.is_ascii() || bytes.len() < 20 || bytes[4] != b'-' || bytes[7] != b'-' || bytes[13] != b':' || bytes[16] != b':' {
+        return None;
+    }
+    if bytes[10] != b'T' && bytes[10] != b't' && bytes[10] != b' ' {
+        return None;
+    }
+    let year = parse_digits(&value[0..4])?;
+    let month = parse_digits(&value[5..7])?;
+    let day = parse_digits(&value[8..10])?;
+    let hour = parse_digits(&value[11..13])?;
+    let minute = parse_digits(&value[14..16])?;
+    let second = parse_digits(&value[17..19])?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    let mut rest = &value[19..];
+    let mut nanos = 0;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 || digits > 9 {
+            return None;
+        }
+        nanos = parse_digits(&fraction[..digits])? * 10_i64.pow(9 - digits as u32);
+        rest = &fraction[digits..];
+    }
+    let offset_seconds = match rest {
+        "Z" | "z" => 0,
+        _ if rest.len() == 6 && rest.as_bytes()[3] == b':' => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            sign * (parse_digits(&rest[1..3])? * 3600 + parse_digits(&rest[4..6])? * 60)
+        }
+        _ => return None,
+    };
+    let days = days_from_civil(year, month as u32, day as u32);
+    let seconds = days * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second - offset_seconds;
+    seconds.checked_mul(NANOS_PER_SECOND)?.checked_add(nanos)
+}
+
+fn parse_digits(value: &str) -> Option<i64> {
+    if value.is_empty() || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+#[cfg(any(test, feature = "test-support"))]
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 } as i64;
+    let day_of_year = (153 * shifted_month + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_rfc3339_epoch() {
+        assert_eq!("1970-01-01T00:00:00Z", to_rfc3339(0));
+    }
+
+    #[test]
+    fn to_rfc3339_with_fraction() {
+        assert_eq!("2020-02-29T12:34:56.5Z", to_rfc3339(1_582_979_696_500_000_000));
+    }
+
+    #[test]
+    fn to_rfc3339_before_epoch() {
+        assert_eq!("1969-12-31T23:59:59.999999999Z", to_rfc3339(-1));
+    }
+
+    #[test]
+    fn from_rfc3339_round_trip() {
+        for value in [0, -1, 1_582_979_696_500_000_000, 1_700_000_000_123_456_789] {
+            assert_eq!(Some(value), from_rfc3339(&to_rfc3339(value)));
+        }
+    }
+
+    #[test]
+    fn from_rfc3339_with_offset() {
+        assert_eq!(Some(0), from_rfc3339("1970-01-01T01:30:00+01:30"));
+        assert_eq!(Some(3_600_000_000_000), from_rfc3339("1970-01-01T00:00:00-01:00"));
+    }
+
+    #[test]
+    fn from_rfc3339_invalid() {
+        assert_eq!(None, from_rfc3339("1970-01-01"));
+        assert_eq!(None, from_rfc3339("1970-13-01T00:00:00Z"));
+        assert_eq!(None, from_rfc3339("1970-01-01T00:00:00"));
+        assert_eq!(None, from_rfc3339("1970-01-01T00:00:00.Z"));
+    }
+}
@@ -1,4 +1,7 @@
 use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::influxdb_v3_query::QueryLanguage;
+use crate::model::influxdb_v3_write_options::InfluxDbV3WriteOptions;
+use crate::model::precision::Precision;
 
 pub (crate) fn to_influxdb_write_url(influxdb_config: &InfluxdbConfig) -> String {
     format!(
@@ -9,6 +12,16 @@ pub (crate) fn to_influxdb_write_url(influxdb_config: &InfluxdbConfig) -> String
     )
 }
 
+pub (crate) fn to_influxdb_write_url_with_precision(influxdb_config: &InfluxdbConfig, precision: Precision) -> String {
+    format!(
+        "{}/api/v2/write?org={}&bucket={}&precision={}",
+        influxdb_config.address.to_owned(),
+        influxdb_config.organisation.to_owned(),
+        influxdb_config.bucket.to_owned(),
+        precision.v2_value()
+    )
+}
+
 pub (crate) fn to_influxdb_read_url(influxdb_config: &InfluxdbConfig) -> String {
     format!(
         "{}/api/v2/query?org={}",
@@ -17,6 +30,29 @@ pub (crate) fn to_influxdb_read_url(influxdb_config: &InfluxdbConfig) -> String
     )
 }
 
+pub (crate) fn to_influxdb_v3_write_url(influxdb_config: &InfluxdbConfig, options: &InfluxDbV3WriteOptions) -> String {
+    format!(
+        "{}/api/v3/write_lp?db={}&precision={}&accept_partial={}&no_sync={}",
+        influxdb_config.address.to_owned(),
+        influxdb_config.bucket.to_owned(),
+        options.precision.v3_value(),
+        options.accept_partial,
+        options.no_sync
+    )
+}
+
+pub (crate) fn to_influxdb_v3_query_url(influxdb_config: &InfluxdbConfig, language: QueryLanguage) -> String {
+    let endpoint = match language {
+        QueryLanguage::Sql => "query_sql",
+        QueryLanguage::InfluxQl => "query_influxql",
+    };
+    format!("{}/api/v3/{}", influxdb_config.address.to_owned(), endpoint)
+}
+
+pub (crate) fn to_influxdb_api_url(influxdb_config: &InfluxdbConfig, path: &str) -> String {
+    format!("{}/api/v2/{}", influxdb_config.address.to_owned(), path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -28,10 +64,26 @@ mod tests {
             organisation: "organisation".to_string(),
             bucket: "bucket".to_string(),
             influxdb_token_path: "influxdb_token_path".to_string(),
+            limits: None,
         });
         assert_eq!("address/api/v2/write?org=organisation&bucket=bucket&precision=s", result);
     }
 
+    #[test]
+    fn to_influxdb_write_url_with_precision_correct() {
+        let result = to_influxdb_write_url_with_precision(
+            &InfluxdbConfig {
+                address: "address".to_string(),
+                organisation: "organisation".to_string(),
+                bucket: "bucket".to_string(),
+                influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
+            },
+            Precision::Nanosecond
+        );
+        assert_eq!("address/api/v2/write?org=organisation&bucket=bucket&precision=ns", result);
+    }
+
     #[test]
     fn to_influxdb_read_url_correct() {
         let result = to_influxdb_read_url(&InfluxdbConfig {
@@ -39,7 +91,55 @@ mod tests {
             organisation: "organisation".to_string(),
             bucket: "bucket".to_string(),
             influxdb_token_path: "influxdb_token_path".to_string(),
+            limits: None,
         });
         assert_eq!("address/api/v2/query?org=organisation", result);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn to_influxdb_v3_write_url_correct() {
+        let result = to_influxdb_v3_write_url(
+            &InfluxdbConfig {
+                address: "address".to_string(),
+                organisation: "organisation".to_string(),
+                bucket: "bucket".to_string(),
+                influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
+            },
+            &InfluxDbV3WriteOptions {
+                accept_partial: false,
+                no_sync: true,
+                precision: Precision::Second,
+            }
+        );
+        assert_eq!("address/api/v3/write_lp?db=bucket&precision=second&accept_partial=false&no_sync=true", result);
+    }
+
+    #[test]
+    fn to_influxdb_v3_query_url_correct() {
+        let config = InfluxdbConfig {
+            address: "address".to_string(),
+            organisation: "organisation".to_string(),
+            bucket: "bucket".to_string(),
+            influxdb_token_path: "influxdb_token_path".to_string(),
+            limits: None,
+        };
+        assert_eq!("address/api/v3/query_sql", to_influxdb_v3_query_url(&config, QueryLanguage::Sql));
+        assert_eq!("address/api/v3/query_influxql", to_influxdb_v3_query_url(&config, QueryLanguage::InfluxQl));
+    }
+
+    #[test]
+    fn to_influxdb_api_url_correct() {
+        let result = to_influxdb_api_url(
+            &InfluxdbConfig {
+                address: "address".to_string(),
+                organisation: "organisation".to_string(),
+                bucket: "bucket".to_string(),
+                influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
+            },
+            "buckets?org=organisation"
+        );
+        assert_eq!("address/api/v2/buckets?org=organisation", result);
+    }
+}
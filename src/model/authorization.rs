@@ -0,0 +1,71 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PermissionResource {
+    #[serde(rename = "type")]
+    pub resource_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "orgID", default, skip_serializing_if = "Option::is_none")]
+    pub org_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Permission {
+    pub action: String,
+    pub resource: PermissionResource,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Authorization {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "orgID")]
+    pub org_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    pub permissions: Vec<Permission>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Authorizations {
+    pub authorizations: Vec<Authorization>,
+}
+
+impl Permission {
+    pub fn bucket(action: &str, org_id: &str, bucket_id: &str) -> Self {
+        Permission {
+            action: action.to_string(),
+            resource: PermissionResource {
+                resource_type: "buckets".to_string(),
+                id: Some(bucket_id.to_string()),
+                org_id: Some(org_id.to_string()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize() {
+        let payload = Authorization {
+            id: None,
+            org_id: "org".to_string(),
+            description: Some("writer".to_string()),
+            status: None,
+            token: None,
+            permissions: vec![Permission::bucket("write", "org", "1")],
+        };
+        assert_eq!(
+            r#"{"orgID":"org","description":"writer","permissions":[{"action":"write","resource":{"type":"buckets","id":"1","orgID":"org"}}]}"#,
+            serde_json::to_string(&payload).expect("Cannot serialize")
+        );
+    }
+}
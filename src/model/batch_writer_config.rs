@@ -0,0 +1,50 @@
+use serde::{Serialize, Deserialize};
+use crate::model::precision::Precision;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct BatchWriterConfig {
+    pub batch_size: usize,
+    pub flush_interval_ms: u64,
+    pub queue_capacity: usize,
+    pub max_buffered_lines: usize,
+    pub max_retries: u32,
+    pub retry_interval_ms: u64,
+    pub precision: Precision,
+}
+
+impl Default for BatchWriterConfig {
+    fn default() -> Self {
+        BatchWriterConfig {
+            batch_size: 5000,
+            flush_interval_ms: 1000,
+            queue_capacity: 10_000,
+            max_buffered_lines: 100_000,
+            max_retries: 3,
+            retry_interval_ms: 500,
+            precision: Precision::Second,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let payload = r#"{"batch_size":10,"flush_interval_ms":20,"queue_capacity":30,"max_buffered_lines":40,"max_retries":1,"retry_interval_ms":50,"precision":"nanosecond"}"#;
+        let result: BatchWriterConfig = serde_json::from_str(payload).expect("Cannot deserialize");
+        assert_eq!(
+            BatchWriterConfig {
+                batch_size: 10,
+                flush_interval_ms: 20,
+                queue_capacity: 30,
+                max_buffered_lines: 40,
+                max_retries: 1,
+                retry_interval_ms: 50,
+                precision: Precision::Nanosecond,
+            },
+            result
+        );
+    }
+}
@@ -0,0 +1,10 @@
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct BatchWriterStats {
+    pub written_lines: u64,
+    pub written_batches: u64,
+    pub retries: u64,
+    pub failed_batches: u64,
+    pub dropped_lines: u64,
+    pub pending_lines: usize,
+    pub last_error: Option<String>,
+}
@@ -0,0 +1,55 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct RetentionRule {
+    #[serde(rename = "type")]
+    pub rule_type: String,
+    #[serde(rename = "everySeconds")]
+    pub every_seconds: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Bucket {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "orgID")]
+    pub org_id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "retentionRules", default)]
+    pub retention_rules: Vec<RetentionRule>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Buckets {
+    pub buckets: Vec<Bucket>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize() {
+        let payload = Bucket {
+            id: None,
+            org_id: "org".to_string(),
+            name: "bucket".to_string(),
+            description: None,
+            retention_rules: vec![RetentionRule { rule_type: "expire".to_string(), every_seconds: 3600 }],
+        };
+        assert_eq!(
+            r#"{"orgID":"org","name":"bucket","retentionRules":[{"type":"expire","everySeconds":3600}]}"#,
+            serde_json::to_string(&payload).expect("Cannot serialize")
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        let payload = r#"{"buckets":[{"id":"1","orgID":"org","name":"bucket","type":"user","retentionRules":[]}]}"#;
+        let result: Buckets = serde_json::from_str(payload).expect("Cannot deserialize");
+        assert_eq!(Some("1".to_string()), result.buckets[0].id);
+        assert_eq!("bucket", result.buckets[0].name);
+    }
+}
@@ -0,0 +1,210 @@
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, Value};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum CheckStatusLevel {
+    Any,
+    Unknown,
+    Ok,
+    Info,
+    Warn,
+    Crit,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ThresholdCondition {
+    Greater { value: f64 },
+    Lesser { value: f64 },
+    Range { min: f64, max: f64, within: bool },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Threshold {
+    pub level: CheckStatusLevel,
+    #[serde(rename = "allValues", default)]
+    pub all_values: bool,
+    #[serde(flatten)]
+    pub condition: ThresholdCondition,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum CheckKind {
+    Threshold {
+        thresholds: Vec<Threshold>,
+    },
+    Deadman {
+        #[serde(rename = "timeSince")]
+        time_since: String,
+        #[serde(rename = "staleTime", default, skip_serializing_if = "Option::is_none")]
+        stale_time: Option<String>,
+        #[serde(rename = "reportZero", default)]
+        report_zero: bool,
+        level: CheckStatusLevel,
+    },
+    #[serde(untagged)]
+    Unknown(Map<String, Value>),
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct CheckQuery {
+    pub text: String,
+    #[serde(rename = "editMode", default = "default_edit_mode")]
+    pub edit_mode: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct CheckTag {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Check {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "orgID")]
+    pub org_id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    pub query: CheckQuery,
+    pub every: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<String>,
+    #[serde(rename = "statusMessageTemplate", default, skip_serializing_if = "Option::is_none")]
+    pub status_message_template: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<CheckTag>,
+    #[serde(flatten)]
+    pub kind: CheckKind,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Checks {
+    pub checks: Vec<Check>,
+}
+
+fn default_edit_mode() -> String {
+    "advanced".to_string()
+}
+
+impl CheckQuery {
+    pub fn new(text: &str) -> Self {
+        CheckQuery {
+            text: text.to_string(),
+            edit_mode: default_edit_mode(),
+        }
+    }
+}
+
+impl Threshold {
+    pub fn greater(level: CheckStatusLevel, value: f64) -> Self {
+        Threshold {
+            level,
+            all_values: false,
+            condition: ThresholdCondition::Greater { value },
+        }
+    }
+
+    pub fn lesser(level: CheckStatusLevel, value: f64) -> Self {
+        Threshold {
+            level,
+            all_values: false,
+            condition: ThresholdCondition::Lesser { value },
+        }
+    }
+
+    pub fn range(level: CheckStatusLevel, min: f64, max: f64, within: bool) -> Self {
+        Threshold {
+            level,
+            all_values: false,
+            condition: ThresholdCondition::Range { min, max, within },
+        }
+    }
+}
+
+impl Check {
+    pub fn threshold(org_id: &str, name: &str, query: &str, every: &str, thresholds: Vec<Threshold>) -> Self {
+        Check::new(org_id, name, query, every, CheckKind::Threshold { thresholds })
+    }
+
+    pub fn deadman(org_id: &str, name: &str, query: &str, every: &str, time_since: &str, level: CheckStatusLevel) -> Self {
+        Check::new(org_id, name, query, every, CheckKind::Deadman {
+            time_since: time_since.to_string(),
+            stale_time: None,
+            report_zero: false,
+            level,
+        })
+    }
+
+    fn new(org_id: &str, name: &str, query: &str, every: &str, kind: CheckKind) -> Self {
+        Check {
+            id: None,
+            org_id: org_id.to_string(),
+            name: name.to_string(),
+            description: None,
+            status: None,
+            query: CheckQuery::new(query),
+            every: every.to_string(),
+            offset: None,
+            status_message_template: None,
+            tags: vec![],
+            kind,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_threshold_check() {
+        let payload = Check::threshold(
+            "org-id",
+            "cpu",
+            "from(bucket: \"telegraf\")",
+            "1m",
+            vec![Threshold::greater(CheckStatusLevel::Crit, 90.0), Threshold::range(CheckStatusLevel::Warn, 70.0, 90.0, true)]
+        );
+        assert_eq!(
+            r#"{"orgID":"org-id","name":"cpu","query":{"text":"from(bucket: \"telegraf\")","editMode":"advanced"},"every":"1m","type":"threshold","thresholds":[{"level":"CRIT","allValues":false,"type":"greater","value":90.0},{"level":"WARN","allValues":false,"type":"range","min":70.0,"max":90.0,"within":true}]}"#,
+            serde_json::to_string(&payload).unwrap()
+        );
+    }
+
+    #[test]
+    fn deserialize_deadman_check() {
+        let payload = r#"{"id":"check-id","orgID":"org-id","name":"heartbeat","type":"deadman","query":{"text":"q"},"every":"1m","timeSince":"90s","staleTime":"10m","reportZero":true,"level":"CRIT","status":"active","links":{"self":"/api/v2/checks/check-id"}}"#;
+        let result: Check = serde_json::from_str(payload).unwrap();
+        assert_eq!(
+            CheckKind::Deadman {
+                time_since: "90s".to_string(),
+                stale_time: Some("10m".to_string()),
+                report_zero: true,
+                level: CheckStatusLevel::Crit,
+            },
+            result.kind
+        );
+        assert_eq!("advanced", result.query.edit_mode);
+        assert_eq!(Some("active".to_string()), result.status);
+    }
+
+    #[test]
+    fn deserialize_unknown_check() {
+        let payload = r#"{"checks":[{"id":"check-id","orgID":"org-id","name":"flux","type":"custom","query":{"text":"q"},"every":"1m","script":"x"}]}"#;
+        let result: Checks = serde_json::from_str(payload).unwrap();
+        let kind: Map<String, Value> = serde_json::from_str(r#"{"type":"custom","script":"x"}"#).unwrap();
+        assert_eq!(CheckKind::Unknown(kind), result.checks[0].kind);
+        assert_eq!("flux", result.checks[0].name);
+        assert_eq!(
+            r#"{"id":"check-id","orgID":"org-id","name":"flux","query":{"text":"q","editMode":"advanced"},"every":"1m","script":"x","type":"custom"}"#,
+            serde_json::to_string(&result.checks[0]).unwrap()
+        );
+    }
+}
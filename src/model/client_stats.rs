@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+
+const LATENCY_BUCKETS_MS: [f64; 12] = [1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0];
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Transport,
+    Timeout,
+    Unauthorized,
+    RateLimited,
+    ClientError,
+    ServerError,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum ClientEvent {
+    Write {
+        address: String,
+        points: u64,
+        bytes: u64,
+        latency_ms: f64,
+        error: Option<ErrorKind>,
+    },
+    Query {
+        address: String,
+        bytes: u64,
+        latency_ms: f64,
+        error: Option<ErrorKind>,
+    },
+    Retry {
+        address: String,
+        attempt: u32,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct LatencyHistogram {
+    pub count: u64,
+    pub sum_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub bucket_bounds_ms: Vec<f64>,
+    pub bucket_counts: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct ClientStats {
+    pub written_points: u64,
+    pub written_bytes: u64,
+    pub written_batches: u64,
+    pub failed_points: u64,
+    pub retries: u64,
+    pub write_failures: BTreeMap<ErrorKind, u64>,
+    pub write_latency: LatencyHistogram,
+    pub queries: u64,
+    pub query_bytes: u64,
+    pub query_failures: BTreeMap<ErrorKind, u64>,
+    pub query_latency: LatencyHistogram,
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency_ms: f64) {
+        self.min_ms = if self.count == 0 { latency_ms } else { self.min_ms.min(latency_ms) };
+        self.max_ms = self.max_ms.max(latency_ms);
+        self.count += 1;
+        self.sum_ms += latency_ms;
+        let bucket = self
+            .bucket_bounds_ms
+            .iter()
+            .position(|bound| latency_ms <= *bound)
+            .unwrap_or(self.bucket_bounds_ms.len());
+        if let Some(count) = self.bucket_counts.get_mut(bucket) {
+            *count += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        if other.count == 0 {
+            return;
+        }
+        self.min_ms = if self.count == 0 { other.min_ms } else { self.min_ms.min(other.min_ms) };
+        self.max_ms = self.max_ms.max(other.max_ms);
+        self.count += other.count;
+        self.sum_ms += other.sum_ms;
+        for (count, other) in self.bucket_counts.iter_mut().zip(&other.bucket_counts) {
+            *count += other;
+        }
+    }
+
+    pub fn mean_ms(&self) -> Option<f64> {
+        match self.count {
+            0 => None,
+            count => Some(self.sum_ms / count as f64),
+        }
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        LatencyHistogram {
+            count: 0,
+            sum_ms: 0.0,
+            min_ms: 0.0,
+            max_ms: 0.0,
+            bucket_bounds_ms: LATENCY_BUCKETS_MS.to_vec(),
+            bucket_counts: vec![0; LATENCY_BUCKETS_MS.len() + 1],
+        }
+    }
+}
+
+impl ClientStats {
+    pub fn merge(&mut self, other: &ClientStats) {
+        self.written_points += other.written_points;
+        self.written_bytes += other.written_bytes;
+        self.written_batches += other.written_batches;
+        self.failed_points += other.failed_points;
+        self.retries += other.retries;
+        self.write_latency.merge(&other.write_latency);
+        self.queries += other.queries;
+        self.query_bytes += other.query_bytes;
+        self.query_latency.merge(&other.query_latency);
+        for (kind, count) in &other.write_failures {
+            *self.write_failures.entry(*kind).or_default() += count;
+        }
+        for (kind, count) in &other.query_failures {
+            *self.query_failures.entry(*kind).or_default() += count;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_latency_buckets() {
+        let mut result = LatencyHistogram::default();
+        result.record(3.0);
+        result.record(30.0);
+        result.record(20_000.0);
+        assert_eq!(3, result.count);
+        assert_eq!(3.0, result.min_ms);
+        assert_eq!(20_000.0, result.max_ms);
+        assert_eq!(1, result.bucket_counts[1]);
+        assert_eq!(1, result.bucket_counts[4]);
+        assert_eq!(1, result.bucket_counts[12]);
+        assert_eq!(Some(20_033.0 / 3.0), result.mean_ms());
+    }
+
+    #[test]
+    fn ignores_missing_buckets() {
+        let mut result = LatencyHistogram {
+            bucket_counts: vec![],
+            ..LatencyHistogram::default()
+        };
+        result.record(3.0);
+        assert_eq!(1, result.count);
+        assert!(result.bucket_counts.is_empty());
+    }
+
+    #[test]
+    fn merges_stats() {
+        let stats = |points: u64, failures: u64, latency_ms: f64| {
+            let mut query_latency = LatencyHistogram::default();
+            query_latency.record(latency_ms);
+            ClientStats {
+                written_points: points,
+                write_failures: BTreeMap::from([(ErrorKind::ServerError, failures)]),
+                query_latency,
+                ..ClientStats::default()
+            }
+        };
+        let mut result = stats(2, 1, 10.0);
+        result.merge(&stats(3, 2, 2.0));
+        assert_eq!(5, result.written_points);
+        assert_eq!(Some(&3), result.write_failures.get(&ErrorKind::ServerError));
+        assert_eq!(2.0, result.query_latency.min_ms);
+        assert_eq!(2, result.query_latency.count);
+    }
+
+    #[test]
+    fn serialize_failures() {
+        let stats = ClientStats {
+            write_failures: BTreeMap::from([(ErrorKind::RateLimited, 1)]),
+            ..ClientStats::default()
+        };
+        let result = serde_json::to_value(&stats).unwrap();
+        assert_eq!(1, result["write_failures"]["rate_limited"]);
+    }
+}
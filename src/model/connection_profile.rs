@@ -0,0 +1,42 @@
+use serde::{Serialize, Deserialize};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct ConnectionProfile {
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub token: Option<Secret>,
+    #[serde(default)]
+    pub org: Option<String>,
+    #[serde(default)]
+    pub bucket: Option<String>,
+    #[serde(default)]
+    pub active: bool,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct InfluxdbProfile {
+    pub name: String,
+    pub influxdb_token: Secret,
+    pub influxdb_config: InfluxdbConfig,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_partial() {
+        let result: ConnectionProfile = serde_json::from_str(r#"{"url":"http://localhost:8086","active":true}"#).unwrap();
+        assert_eq!(
+            ConnectionProfile {
+                url: Some("http://localhost:8086".to_string()),
+                active: true,
+                ..ConnectionProfile::default()
+            },
+            result
+        );
+    }
+}
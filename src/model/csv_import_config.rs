@@ -0,0 +1,129 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+use crate::mapper::time_mapper::from_rfc3339;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    Float,
+    Integer,
+    UInteger,
+    Boolean,
+    String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeFormat {
+    Rfc3339,
+    EpochSeconds,
+    EpochMilliseconds,
+    EpochMicroseconds,
+    EpochNanoseconds,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnRole {
+    Measurement,
+    Tag,
+    Field(FieldType),
+    Time(TimeFormat),
+    Ignore,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum RowErrorPolicy {
+    Skip,
+    Fail,
+    Log,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct CsvImportConfig {
+    #[serde(default)]
+    pub columns: BTreeMap<String, ColumnRole>,
+    #[serde(default)]
+    pub measurement: Option<String>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    #[serde(default = "default_delimiter")]
+    pub delimiter: u8,
+    #[serde(default = "default_batch_size")]
+    pub batch_size: usize,
+    #[serde(default = "default_row_error_policy")]
+    pub row_error_policy: RowErrorPolicy,
+}
+
+fn default_delimiter() -> u8 {
+    b','
+}
+
+fn default_batch_size() -> usize {
+    5000
+}
+
+fn default_row_error_policy() -> RowErrorPolicy {
+    RowErrorPolicy::Fail
+}
+
+impl TimeFormat {
+    pub fn to_nanos(&self, value: &str) -> Option<i64> {
+        let multiplier = match self {
+            TimeFormat::Rfc3339 => return from_rfc3339(value).or_else(|| value.parse().ok()),
+            TimeFormat::EpochSeconds => 1_000_000_000,
+            TimeFormat::EpochMilliseconds => 1_000_000,
+            TimeFormat::EpochMicroseconds => 1_000,
+            TimeFormat::EpochNanoseconds => 1,
+        };
+        value.parse::<i64>().ok()?.checked_mul(multiplier)
+    }
+}
+
+impl Default for CsvImportConfig {
+    fn default() -> Self {
+        CsvImportConfig {
+            columns: BTreeMap::new(),
+            measurement: None,
+            tags: BTreeMap::new(),
+            delimiter: default_delimiter(),
+            batch_size: default_batch_size(),
+            row_error_policy: default_row_error_policy(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let payload = r#"{"columns":{"m":"measurement","host":"tag","used":{"field":"float"},"ts":{"time":"epoch_seconds"},"note":"ignore"},"row_error_policy":"skip"}"#;
+        let result: CsvImportConfig = serde_json::from_str(payload).expect("Cannot deserialize");
+        assert_eq!(
+            CsvImportConfig {
+                columns: BTreeMap::from([
+                    ("m".to_string(), ColumnRole::Measurement),
+                    ("host".to_string(), ColumnRole::Tag),
+                    ("used".to_string(), ColumnRole::Field(FieldType::Float)),
+                    ("ts".to_string(), ColumnRole::Time(TimeFormat::EpochSeconds)),
+                    ("note".to_string(), ColumnRole::Ignore),
+                ]),
+                row_error_policy: RowErrorPolicy::Skip,
+                ..CsvImportConfig::default()
+            },
+            result
+        );
+    }
+
+    #[test]
+    fn time_format_to_nanos() {
+        assert_eq!(Some(1_500_000_000), TimeFormat::EpochMilliseconds.to_nanos("1500"));
+        assert_eq!(Some(1_714_522_200_000_000_000), TimeFormat::Rfc3339.to_nanos("2024-05-01T00:10:00Z"));
+        assert_eq!(Some(42), TimeFormat::Rfc3339.to_nanos("42"));
+        assert_eq!(None, TimeFormat::EpochSeconds.to_nanos("soon"));
+        assert_eq!(None, TimeFormat::EpochSeconds.to_nanos("9223372036854775807"));
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct CsvImportStats {
+    pub rows: u64,
+    pub written_points: u64,
+    pub skipped_rows: u64,
+    pub batches: u64,
+}
@@ -0,0 +1,98 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Dbrp {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "orgID")]
+    pub org_id: String,
+    #[serde(rename = "bucketID")]
+    pub bucket_id: String,
+    pub database: String,
+    pub retention_policy: String,
+    #[serde(default)]
+    pub default: bool,
+    #[serde(rename = "virtual", default, skip_serializing)]
+    pub is_virtual: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct DbrpUpdate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention_policy: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct DbrpFilter {
+    #[serde(rename = "bucketID", default, skip_serializing_if = "Option::is_none")]
+    pub bucket_id: Option<String>,
+    #[serde(rename = "db", default, skip_serializing_if = "Option::is_none")]
+    pub database: Option<String>,
+    #[serde(rename = "rp", default, skip_serializing_if = "Option::is_none")]
+    pub retention_policy: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Dbrps {
+    pub content: Vec<Dbrp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct DbrpResponse {
+    pub content: Dbrp,
+}
+
+impl Dbrp {
+    pub fn new(org_id: &str, bucket_id: &str, database: &str, retention_policy: &str) -> Self {
+        Dbrp {
+            id: None,
+            org_id: org_id.to_string(),
+            bucket_id: bucket_id.to_string(),
+            database: database.to_string(),
+            retention_policy: retention_policy.to_string(),
+            default: false,
+            is_virtual: false,
+        }
+    }
+
+    pub fn as_default(self) -> Self {
+        Dbrp { default: true, ..self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize() {
+        let payload = Dbrp::new("org-id", "bucket-id", "telegraf", "autogen").as_default();
+        assert_eq!(
+            r#"{"orgID":"org-id","bucketID":"bucket-id","database":"telegraf","retention_policy":"autogen","default":true}"#,
+            serde_json::to_string(&payload).unwrap()
+        );
+    }
+
+    #[test]
+    fn deserialize() {
+        let payload = r#"{"content":[{"id":"dbrp-id","orgID":"org-id","bucketID":"bucket-id","database":"telegraf","retention_policy":"autogen","default":true,"virtual":true}]}"#;
+        let result: Dbrps = serde_json::from_str(payload).unwrap();
+        assert_eq!(Some("dbrp-id".to_string()), result.content[0].id);
+        assert!(result.content[0].default);
+        assert!(result.content[0].is_virtual);
+    }
+
+    #[test]
+    fn serialize_filter() {
+        let filter = DbrpFilter {
+            database: Some("telegraf".to_string()),
+            default: Some(true),
+            ..DbrpFilter::default()
+        };
+        assert_eq!(r#"{"db":"telegraf","default":true}"#, serde_json::to_string(&filter).unwrap());
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum FailoverStrategy {
+    Ordered,
+    Weighted,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct WeightedAddress {
+    pub address: String,
+    pub weight: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct FailoverConfig {
+    pub addresses: Vec<WeightedAddress>,
+    pub strategy: FailoverStrategy,
+    pub health_check_interval_ms: u64,
+}
+
+impl FailoverConfig {
+    pub fn ordered(addresses: &[&str]) -> Self {
+        FailoverConfig {
+            addresses: addresses
+                .iter()
+                .map(|address| WeightedAddress { address: address.to_string(), weight: 1 })
+                .collect(),
+            strategy: FailoverStrategy::Ordered,
+            health_check_interval_ms: 10_000,
+        }
+    }
+
+    pub fn weighted(addresses: &[(&str, u32)]) -> Self {
+        FailoverConfig {
+            addresses: addresses
+                .iter()
+                .map(|(address, weight)| WeightedAddress { address: address.to_string(), weight: *weight })
+                .collect(),
+            strategy: FailoverStrategy::Weighted,
+            health_check_interval_ms: 10_000,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let payload = r#"{"addresses":[{"address":"http://a","weight":3},{"address":"http://b","weight":1}],"strategy":"weighted","health_check_interval_ms":10000}"#;
+        let result: FailoverConfig = serde_json::from_str(payload).expect("Cannot deserialize");
+        assert_eq!(FailoverConfig::weighted(&[("http://a", 3), ("http://b", 1)]), result);
+    }
+
+    #[test]
+    fn ordered_addresses_have_equal_weight() {
+        let result = FailoverConfig::ordered(&["http://a", "http://b"]);
+        assert_eq!(FailoverStrategy::Ordered, result.strategy);
+        assert!(result.addresses.iter().all(|address| address.weight == 1));
+    }
+}
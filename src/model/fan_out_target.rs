@@ -0,0 +1,13 @@
+use serde::{Serialize, Deserialize};
+use crate::model::batch_writer_config::BatchWriterConfig;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct FanOutTarget {
+    pub name: String,
+    pub influxdb_token: Secret,
+    pub influxdb_config: InfluxdbConfig,
+    #[serde(default)]
+    pub batch_config: BatchWriterConfig,
+}
@@ -0,0 +1,49 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct FluxColumn {
+    pub name: String,
+    pub data_type: String,
+    pub group: bool,
+    pub default_value: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct FluxTable {
+    pub columns: Vec<FluxColumn>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl FluxTable {
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|column| column.name == name)
+    }
+
+    pub fn value(&self, row: usize, name: &str) -> Option<&str> {
+        let index = self.column_index(name)?;
+        self.rows.get(row)?.get(index).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_values_by_column_name() {
+        let column = |name: &str| FluxColumn {
+            name: name.to_string(),
+            data_type: "string".to_string(),
+            group: false,
+            default_value: "".to_string(),
+        };
+        let table = FluxTable {
+            columns: vec![column("_field"), column("host")],
+            rows: vec![vec!["usage".to_string(), "a".to_string()]],
+        };
+        assert_eq!(Some(1), table.column_index("host"));
+        assert_eq!(Some("a"), table.value(0, "host"));
+        assert_eq!(None, table.value(1, "host"));
+        assert_eq!(None, table.value(0, "missing"));
+    }
+}
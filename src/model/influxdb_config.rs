@@ -1,4 +1,9 @@
+use reqwest::Url;
 use serde::{Serialize, Deserialize};
+use crate::error::config_error::{ConfigError, ConfigProblem};
+use crate::model::influxdb_config_builder::InfluxdbConfigBuilder;
+use crate::model::request_limits::RequestLimits;
+use crate::model::secret::Secret;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct InfluxdbConfig {
@@ -6,6 +11,100 @@ pub struct InfluxdbConfig {
     pub organisation: String,
     pub bucket: String,
     pub influxdb_token_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<RequestLimits>,
+}
+
+impl InfluxdbConfig {
+    pub fn builder() -> InfluxdbConfigBuilder {
+        InfluxdbConfigBuilder::default()
+    }
+
+    pub fn from_env() -> Result<(InfluxdbConfig, Secret), ConfigError> {
+        InfluxdbConfig::from_env_with(|name| std::env::var(name).ok())
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let problems = self.problems();
+        match problems.is_empty() {
+            true => Ok(()),
+            false => Err(ConfigError { problems }),
+        }
+    }
+
+    pub (crate) fn from_env_with(
+        environment: impl Fn(&str) -> Option<String>
+    ) -> Result<(InfluxdbConfig, Secret), ConfigError> {
+        let config = InfluxdbConfig::from_env_unchecked(&environment);
+        let token = environment("INFLUX_TOKEN").map(|token| Secret::from(token.trim())).unwrap_or_default();
+        let mut problems = config.problems();
+        if token.is_empty() {
+            problems.push(ConfigProblem::Missing("token".to_string()));
+        }
+        match problems.is_empty() {
+            true => Ok((config, token)),
+            false => Err(ConfigError { problems }),
+        }
+    }
+
+    pub (crate) fn from_env_unchecked(environment: impl Fn(&str) -> Option<String>) -> InfluxdbConfig {
+        let mut builder = InfluxdbConfig::builder();
+        if let Some(address) = environment("INFLUX_HOST") {
+            builder = builder.address(address);
+        }
+        if let Some(organisation) = environment("INFLUX_ORG") {
+            builder = builder.organisation(organisation);
+        }
+        if let Some(bucket) = environment("INFLUX_BUCKET") {
+            builder = builder.bucket(bucket);
+        }
+        builder.build_unchecked()
+    }
+
+    fn problems(&self) -> Vec<ConfigProblem> {
+        let mut problems = vec![];
+        if self.address.is_empty() {
+            problems.push(ConfigProblem::Missing("address".to_string()));
+        } else if !is_http_url(&self.address) {
+            problems.push(ConfigProblem::InvalidAddress(self.address.clone()));
+        }
+        if self.organisation.trim().is_empty() {
+            problems.push(ConfigProblem::Missing("organisation".to_string()));
+        }
+        if self.bucket.trim().is_empty() {
+            problems.push(ConfigProblem::Missing("bucket".to_string()));
+        }
+        if let Some(limits) = &self.limits {
+            let counts = [
+                ("max_in_flight_writes", limits.max_in_flight_writes),
+                ("max_in_flight_queries", limits.max_in_flight_queries),
+            ];
+            let rates = [
+                ("requests_per_second", limits.requests_per_second),
+                ("request_burst", limits.request_burst),
+                ("bytes_per_second", limits.bytes_per_second),
+                ("byte_burst", limits.byte_burst),
+            ];
+            for (name, value) in counts {
+                if value == Some(0) {
+                    problems.push(ConfigProblem::InvalidLimit(format!("limits.{}", name)));
+                }
+            }
+            for (name, value) in rates {
+                if value.map(|value| !(value > 0.0 && value.is_finite())).unwrap_or(false) {
+                    problems.push(ConfigProblem::InvalidLimit(format!("limits.{}", name)));
+                }
+            }
+        }
+        problems
+    }
+}
+
+fn is_http_url(address: &str) -> bool {
+    match Url::parse(address) {
+        Ok(url) => matches!(url.scheme(), "http" | "https") && url.host_str().map(|host| !host.is_empty()).unwrap_or(false),
+        Err(_) => false,
+    }
 }
 
 #[cfg(test)]
@@ -19,6 +118,7 @@ mod tests {
             organisation: "organisation".to_string(),
             bucket: "bucket".to_string(),
             influxdb_token_path: "influxdb_token_path".to_string(),
+            limits: None,
         };
         assert_eq!(
             r#"{"address":"address","organisation":"organisation","bucket":"bucket","influxdb_token_path":"influxdb_token_path"}"#,
@@ -36,8 +136,70 @@ mod tests {
                 organisation: "organisation".to_string(),
                 bucket: "bucket".to_string(),
                 influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
             },
             result
         );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn deserialize_limits() {
+        let payload = r#"{"address":"address","organisation":"organisation","bucket":"bucket","influxdb_token_path":"","limits":{"max_in_flight_writes":4,"bytes_per_second":1048576.0}}"#;
+        let result: InfluxdbConfig = serde_json::from_str(payload).expect("Cannot deserialize");
+        assert_eq!(
+            Some(RequestLimits {
+                max_in_flight_writes: Some(4),
+                bytes_per_second: Some(1_048_576.0),
+                ..RequestLimits::default()
+            }),
+            result.limits
+        );
+    }
+
+    fn environment(values: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let values: std::collections::HashMap<String, String> = values.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect();
+        move |name| values.get(name).cloned()
+    }
+
+    #[test]
+    fn from_env_reads_standard_variables() {
+        let (config, token) = InfluxdbConfig::from_env_with(environment(&[
+            ("INFLUX_HOST", "http://localhost:8086/"),
+            ("INFLUX_ORG", "org"),
+            ("INFLUX_BUCKET", "bucket"),
+            ("INFLUX_TOKEN", "token"),
+        ])).unwrap();
+        assert_eq!("http://localhost:8086", config.address);
+        assert_eq!("org", config.organisation);
+        assert_eq!("bucket", config.bucket);
+        assert_eq!("token", token.expose());
+    }
+
+    #[test]
+    fn from_env_reports_every_problem() {
+        let result = InfluxdbConfig::from_env_with(environment(&[("INFLUX_HOST", "ftp://localhost"), ("INFLUX_BUCKET", " ")]));
+        assert_eq!(
+            "Invalid configuration: address 'ftp://localhost' is not a valid http or https url; organisation is required; bucket is required; token is required",
+            result.unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn validate_rejects_invalid_limits() {
+        let config = InfluxdbConfig {
+            address: "http://localhost:8086".to_string(),
+            organisation: "org".to_string(),
+            bucket: "bucket".to_string(),
+            influxdb_token_path: "".to_string(),
+            limits: Some(RequestLimits {
+                max_in_flight_queries: Some(2),
+                bytes_per_second: Some(-1.0),
+                ..RequestLimits::default()
+            }),
+        };
+        assert_eq!(
+            vec![ConfigProblem::InvalidLimit("limits.bytes_per_second".to_string())],
+            config.validate().unwrap_err().problems
+        );
+    }
+}
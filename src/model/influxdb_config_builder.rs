@@ -0,0 +1,101 @@
+use crate::error::config_error::ConfigError;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::request_limits::RequestLimits;
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct InfluxdbConfigBuilder {
+    address: Option<String>,
+    organisation: Option<String>,
+    bucket: Option<String>,
+    influxdb_token_path: Option<String>,
+    limits: Option<RequestLimits>,
+}
+
+impl InfluxdbConfigBuilder {
+    pub fn address(mut self, address: impl Into<String>) -> Self {
+        self.address = Some(address.into());
+        self
+    }
+
+    pub fn organisation(mut self, organisation: impl Into<String>) -> Self {
+        self.organisation = Some(organisation.into());
+        self
+    }
+
+    pub fn bucket(mut self, bucket: impl Into<String>) -> Self {
+        self.bucket = Some(bucket.into());
+        self
+    }
+
+    pub fn influxdb_token_path(mut self, influxdb_token_path: impl Into<String>) -> Self {
+        self.influxdb_token_path = Some(influxdb_token_path.into());
+        self
+    }
+
+    pub fn limits(mut self, limits: RequestLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    pub fn build(self) -> Result<InfluxdbConfig, ConfigError> {
+        let config = self.build_unchecked();
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub (crate) fn build_unchecked(self) -> InfluxdbConfig {
+        InfluxdbConfig {
+            address: self.address.unwrap_or_default().trim().trim_end_matches('/').to_string(),
+            organisation: self.organisation.unwrap_or_default(),
+            bucket: self.bucket.unwrap_or_default(),
+            influxdb_token_path: self.influxdb_token_path.unwrap_or_default(),
+            limits: self.limits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::config_error::ConfigProblem;
+
+    #[test]
+    fn builds_valid_config() {
+        let result = InfluxdbConfig::builder()
+            .address("https://influx.example.com/")
+            .organisation("org")
+            .bucket("bucket")
+            .build()
+            .unwrap();
+        assert_eq!(
+            InfluxdbConfig {
+                address: "https://influx.example.com".to_string(),
+                organisation: "org".to_string(),
+                bucket: "bucket".to_string(),
+                influxdb_token_path: "".to_string(),
+                limits: None,
+            },
+            result
+        );
+    }
+
+    #[test]
+    fn reports_every_problem() {
+        let result = InfluxdbConfig::builder()
+            .address("localhost:8086")
+            .limits(RequestLimits {
+                max_in_flight_writes: Some(0),
+                ..RequestLimits::default()
+            })
+            .build();
+        assert_eq!(
+            vec![
+                ConfigProblem::InvalidAddress("localhost:8086".to_string()),
+                ConfigProblem::Missing("organisation".to_string()),
+                ConfigProblem::Missing("bucket".to_string()),
+                ConfigProblem::InvalidLimit("limits.max_in_flight_writes".to_string()),
+            ],
+            result.unwrap_err().problems
+        );
+    }
+}
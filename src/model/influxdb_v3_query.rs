@@ -0,0 +1,81 @@
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, Value};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryLanguage {
+    Sql,
+    InfluxQl,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryFormat {
+    Json,
+    Jsonl,
+    Csv,
+    Parquet,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct InfluxDbV3Query {
+    pub language: QueryLanguage,
+    pub query: String,
+    pub format: QueryFormat,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub params: Map<String, Value>,
+}
+
+impl InfluxDbV3Query {
+    pub fn sql(query: &str) -> Self {
+        InfluxDbV3Query {
+            language: QueryLanguage::Sql,
+            query: query.to_string(),
+            format: QueryFormat::Json,
+            params: Map::new(),
+        }
+    }
+
+    pub fn influxql(query: &str) -> Self {
+        InfluxDbV3Query {
+            language: QueryLanguage::InfluxQl,
+            query: query.to_string(),
+            format: QueryFormat::Json,
+            params: Map::new(),
+        }
+    }
+
+    pub fn with_format(mut self, format: QueryFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_param(mut self, name: &str, value: Value) -> Self {
+        self.params.insert(name.to_string(), value);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sql_defaults_to_json() {
+        let result = InfluxDbV3Query::sql("SELECT 1");
+        assert_eq!(QueryLanguage::Sql, result.language);
+        assert_eq!("SELECT 1", result.query);
+        assert_eq!(QueryFormat::Json, result.format);
+        assert!(result.params.is_empty());
+    }
+
+    #[test]
+    fn influxql_with_format_and_param() {
+        let result = InfluxDbV3Query::influxql("SELECT * FROM cpu WHERE host = $host")
+            .with_format(QueryFormat::Csv)
+            .with_param("host", Value::String("a".to_string()));
+        assert_eq!(QueryLanguage::InfluxQl, result.language);
+        assert_eq!(QueryFormat::Csv, result.format);
+        assert_eq!(Some(&Value::String("a".to_string())), result.params.get("host"));
+    }
+}
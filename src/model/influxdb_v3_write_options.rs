@@ -0,0 +1,46 @@
+use serde::{Serialize, Deserialize};
+use crate::model::precision::Precision;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct InfluxDbV3WriteOptions {
+    pub accept_partial: bool,
+    pub no_sync: bool,
+    pub precision: Precision,
+}
+
+impl Default for InfluxDbV3WriteOptions {
+    fn default() -> Self {
+        InfluxDbV3WriteOptions {
+            accept_partial: true,
+            no_sync: false,
+            precision: Precision::Auto,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_matches_server_defaults() {
+        let result = InfluxDbV3WriteOptions::default();
+        assert!(result.accept_partial);
+        assert!(!result.no_sync);
+        assert_eq!(Precision::Auto, result.precision);
+    }
+
+    #[test]
+    fn deserialize() {
+        let payload = r#"{"accept_partial":false,"no_sync":true,"precision":"second"}"#;
+        let result: InfluxDbV3WriteOptions = serde_json::from_str(payload).expect("Cannot deserialize");
+        assert_eq!(
+            InfluxDbV3WriteOptions {
+                accept_partial: false,
+                no_sync: true,
+                precision: Precision::Second,
+            },
+            result
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelResource {
+    Buckets,
+    Tasks,
+    Dashboards,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct LabelProperties {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Label {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "orgID", default)]
+    pub org_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub properties: LabelProperties,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Labels {
+    pub labels: Vec<Label>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct LabelResponse {
+    pub label: Label,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct LabelMapping {
+    #[serde(rename = "labelID")]
+    pub label_id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct LabelledResource {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+}
+
+impl LabelResource {
+    pub fn path(&self) -> &'static str {
+        match self {
+            LabelResource::Buckets => "buckets",
+            LabelResource::Tasks => "tasks",
+            LabelResource::Dashboards => "dashboards",
+        }
+    }
+}
+
+impl Label {
+    pub fn new(org_id: &str, name: &str) -> Self {
+        Label {
+            id: None,
+            org_id: org_id.to_string(),
+            name: name.to_string(),
+            properties: LabelProperties::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize() {
+        let payload = Label {
+            properties: LabelProperties {
+                color: Some("#326BBA".to_string()),
+                description: None,
+            },
+            ..Label::new("org-id", "team:storage")
+        };
+        assert_eq!(
+            r##"{"orgID":"org-id","name":"team:storage","properties":{"color":"#326BBA"}}"##,
+            serde_json::to_string(&payload).unwrap()
+        );
+    }
+
+    #[test]
+    fn deserialize_labelled_resource() {
+        let payload = r#"{"id":"task-id","name":"downsample","status":"active","labels":[{"id":"label-id","orgID":"org-id","name":"env:prod"}]}"#;
+        let result: LabelledResource = serde_json::from_str(payload).unwrap();
+        assert_eq!("env:prod", result.labels[0].name);
+        assert_eq!(LabelProperties::default(), result.labels[0].properties);
+    }
+}
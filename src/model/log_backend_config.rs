@@ -0,0 +1,41 @@
+use serde::{Serialize, Deserialize};
+use crate::model::batch_writer_config::BatchWriterConfig;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct LogBackendConfig {
+    #[serde(default = "default_measurement")]
+    pub measurement: String,
+    #[serde(default = "default_level")]
+    pub level: String,
+    #[serde(default)]
+    pub batch_config: BatchWriterConfig,
+}
+
+fn default_measurement() -> String {
+    "logs".to_string()
+}
+
+fn default_level() -> String {
+    "info".to_string()
+}
+
+impl Default for LogBackendConfig {
+    fn default() -> Self {
+        LogBackendConfig {
+            measurement: default_measurement(),
+            level: default_level(),
+            batch_config: BatchWriterConfig::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_defaults() {
+        let result: LogBackendConfig = serde_json::from_str(r#"{"level":"warn"}"#).expect("Cannot deserialize");
+        assert_eq!(LogBackendConfig { level: "warn".to_string(), ..LogBackendConfig::default() }, result);
+    }
+}
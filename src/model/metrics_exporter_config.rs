@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+use crate::model::batch_writer_config::BatchWriterConfig;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct MetricsExporterConfig {
+    #[serde(default = "default_push_interval_ms")]
+    pub push_interval_ms: u64,
+    #[serde(default)]
+    pub global_tags: BTreeMap<String, String>,
+    #[serde(default = "default_quantiles")]
+    pub quantiles: Vec<f64>,
+    #[serde(default = "default_histogram_buckets")]
+    pub histogram_buckets: Vec<f64>,
+    #[serde(default)]
+    pub batch_config: BatchWriterConfig,
+}
+
+fn default_push_interval_ms() -> u64 {
+    10_000
+}
+
+fn default_quantiles() -> Vec<f64> {
+    vec![0.5, 0.9, 0.99]
+}
+
+fn default_histogram_buckets() -> Vec<f64> {
+    vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0]
+}
+
+impl Default for MetricsExporterConfig {
+    fn default() -> Self {
+        MetricsExporterConfig {
+            push_interval_ms: default_push_interval_ms(),
+            global_tags: BTreeMap::new(),
+            quantiles: default_quantiles(),
+            histogram_buckets: default_histogram_buckets(),
+            batch_config: BatchWriterConfig::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_defaults() {
+        let result: MetricsExporterConfig = serde_json::from_str(r#"{"global_tags":{"service":"api"}}"#).expect("Cannot deserialize");
+        assert_eq!(
+            MetricsExporterConfig {
+                global_tags: BTreeMap::from([("service".to_string(), "api".to_string())]),
+                ..MetricsExporterConfig::default()
+            },
+            result
+        );
+    }
+}
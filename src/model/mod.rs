@@ -1 +1,32 @@
+pub mod authorization;
+pub mod batch_writer_config;
+pub mod batch_writer_stats;
+pub mod bucket;
+pub mod check;
+pub mod client_stats;
+pub mod connection_profile;
+pub mod csv_import_config;
+pub mod csv_import_stats;
+pub mod dbrp;
+pub mod failover_config;
+pub mod fan_out_target;
+pub mod flux_table;
 pub mod influxdb_config;
+pub mod influxdb_config_builder;
+pub mod influxdb_v3_query;
+pub mod influxdb_v3_write_options;
+pub mod label;
+pub mod log_backend_config;
+pub mod metrics_exporter_config;
+pub mod notification_endpoint;
+pub mod notification_rule;
+pub mod organisation;
+pub mod point;
+pub mod precision;
+pub mod request_limits;
+pub mod request_metrics_config;
+pub mod secret;
+pub mod tracing_layer_config;
+pub mod user;
+pub mod write_ahead_config;
+pub mod write_ahead_stats;
\ No newline at end of file
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, Value};
+use crate::model::secret::Secret;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum HttpAuthMethod {
+    None,
+    Basic,
+    Bearer,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum NotificationEndpointKind {
+    Http {
+        url: String,
+        #[serde(default = "default_method")]
+        method: String,
+        #[serde(rename = "authMethod")]
+        auth_method: HttpAuthMethod,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        username: Option<Secret>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        password: Option<Secret>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<Secret>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        headers: BTreeMap<String, String>,
+        #[serde(rename = "contentTemplate", default, skip_serializing_if = "Option::is_none")]
+        content_template: Option<String>,
+    },
+    Slack {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<Secret>,
+    },
+    #[serde(rename = "pagerduty")]
+    PagerDuty {
+        #[serde(rename = "clientURL", default, skip_serializing_if = "Option::is_none")]
+        client_url: Option<String>,
+        #[serde(rename = "routingKey")]
+        routing_key: Secret,
+    },
+    #[serde(untagged)]
+    Unknown(Map<String, Value>),
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct NotificationEndpoint {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "orgID")]
+    pub org_id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(flatten)]
+    pub kind: NotificationEndpointKind,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct NotificationEndpoints {
+    #[serde(rename = "notificationEndpoints")]
+    pub notification_endpoints: Vec<NotificationEndpoint>,
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+impl NotificationEndpoint {
+    pub fn http(org_id: &str, name: &str, url: &str) -> Self {
+        NotificationEndpoint::new(org_id, name, NotificationEndpointKind::Http {
+            url: url.to_string(),
+            method: default_method(),
+            auth_method: HttpAuthMethod::None,
+            username: None,
+            password: None,
+            token: None,
+            headers: BTreeMap::new(),
+            content_template: None,
+        })
+    }
+
+    pub fn slack(org_id: &str, name: &str, url: &str) -> Self {
+        NotificationEndpoint::new(org_id, name, NotificationEndpointKind::Slack {
+            url: Some(url.to_string()),
+            token: None,
+        })
+    }
+
+    pub fn pager_duty(org_id: &str, name: &str, routing_key: impl Into<Secret>) -> Self {
+        NotificationEndpoint::new(org_id, name, NotificationEndpointKind::PagerDuty {
+            client_url: None,
+            routing_key: routing_key.into(),
+        })
+    }
+
+    fn new(org_id: &str, name: &str, kind: NotificationEndpointKind) -> Self {
+        NotificationEndpoint {
+            id: None,
+            org_id: org_id.to_string(),
+            name: name.to_string(),
+            description: None,
+            status: None,
+            kind,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_pager_duty() {
+        let payload = NotificationEndpoint::pager_duty("org-id", "on-call", "routing-key");
+        assert_eq!(
+            r#"{"orgID":"org-id","name":"on-call","type":"pagerduty","routingKey":"routing-key"}"#,
+            serde_json::to_string(&payload).unwrap()
+        );
+        assert!(!format!("{:?}", payload).contains("routing-key"));
+    }
+
+    #[test]
+    fn deserialize_http() {
+        let payload = r#"{"id":"endpoint-id","orgID":"org-id","name":"hook","type":"http","url":"https://example.com/hook","authMethod":"bearer","token":"secret: endpoint-id-token","status":"active"}"#;
+        let result: NotificationEndpoint = serde_json::from_str(payload).unwrap();
+        match result.kind {
+            NotificationEndpointKind::Http { method, auth_method, token, .. } => {
+                assert_eq!("POST", method);
+                assert_eq!(HttpAuthMethod::Bearer, auth_method);
+                assert_eq!(Some("secret: endpoint-id-token"), token.as_ref().map(Secret::expose));
+            }
+            kind => panic!("unexpected endpoint kind {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn deserialize_unknown() {
+        let payload = r#"{"notificationEndpoints":[{"id":"endpoint-id","orgID":"org-id","name":"teams","type":"telegram","channel":"alerts"}]}"#;
+        let result: NotificationEndpoints = serde_json::from_str(payload).unwrap();
+        let endpoint = &result.notification_endpoints[0];
+        let kind: Map<String, Value> = serde_json::from_str(r#"{"type":"telegram","channel":"alerts"}"#).unwrap();
+        assert_eq!(NotificationEndpointKind::Unknown(kind), endpoint.kind);
+        assert_eq!(
+            r#"{"id":"endpoint-id","orgID":"org-id","name":"teams","channel":"alerts","type":"telegram"}"#,
+            serde_json::to_string(endpoint).unwrap()
+        );
+    }
+}
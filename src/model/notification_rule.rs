@@ -0,0 +1,148 @@
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, Value};
+use crate::model::check::CheckStatusLevel;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct StatusRule {
+    #[serde(rename = "currentLevel")]
+    pub current_level: CheckStatusLevel,
+    #[serde(rename = "previousLevel", default, skip_serializing_if = "Option::is_none")]
+    pub previous_level: Option<CheckStatusLevel>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum TagRuleOperator {
+    Equal,
+    NotEqual,
+    EqualRegex,
+    NotEqualRegex,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct TagRule {
+    pub key: String,
+    pub value: String,
+    pub operator: TagRuleOperator,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum NotificationRuleKind {
+    Http {},
+    Slack {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        channel: Option<String>,
+        #[serde(rename = "messageTemplate")]
+        message_template: String,
+    },
+    #[serde(rename = "pagerduty")]
+    PagerDuty {
+        #[serde(rename = "messageTemplate")]
+        message_template: String,
+    },
+    #[serde(untagged)]
+    Unknown(Map<String, Value>),
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct NotificationRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "orgID")]
+    pub org_id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub status: String,
+    #[serde(rename = "endpointID")]
+    pub endpoint_id: String,
+    pub every: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<String>,
+    #[serde(rename = "statusRules")]
+    pub status_rules: Vec<StatusRule>,
+    #[serde(rename = "tagRules", default)]
+    pub tag_rules: Vec<TagRule>,
+    #[serde(flatten)]
+    pub kind: NotificationRuleKind,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct NotificationRules {
+    #[serde(rename = "notificationRules")]
+    pub notification_rules: Vec<NotificationRule>,
+}
+
+impl StatusRule {
+    pub fn is(level: CheckStatusLevel) -> Self {
+        StatusRule {
+            current_level: level,
+            previous_level: None,
+        }
+    }
+
+    pub fn changes(from: CheckStatusLevel, to: CheckStatusLevel) -> Self {
+        StatusRule {
+            current_level: to,
+            previous_level: Some(from),
+        }
+    }
+}
+
+impl NotificationRule {
+    pub fn new(org_id: &str, name: &str, endpoint_id: &str, every: &str, status_rules: Vec<StatusRule>, kind: NotificationRuleKind) -> Self {
+        NotificationRule {
+            id: None,
+            org_id: org_id.to_string(),
+            name: name.to_string(),
+            description: None,
+            status: "active".to_string(),
+            endpoint_id: endpoint_id.to_string(),
+            every: every.to_string(),
+            offset: None,
+            status_rules,
+            tag_rules: vec![],
+            kind,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_slack_rule() {
+        let payload = NotificationRule::new(
+            "org-id",
+            "critical",
+            "endpoint-id",
+            "1m",
+            vec![StatusRule::is(CheckStatusLevel::Crit), StatusRule::changes(CheckStatusLevel::Ok, CheckStatusLevel::Warn)],
+            NotificationRuleKind::Slack {
+                channel: Some("#alerts".to_string()),
+                message_template: "${ r._message }".to_string(),
+            }
+        );
+        assert_eq!(
+            r##"{"orgID":"org-id","name":"critical","status":"active","endpointID":"endpoint-id","every":"1m","statusRules":[{"currentLevel":"CRIT"},{"currentLevel":"WARN","previousLevel":"OK"}],"tagRules":[],"type":"slack","channel":"#alerts","messageTemplate":"${ r._message }"}"##,
+            serde_json::to_string(&payload).unwrap()
+        );
+    }
+
+    #[test]
+    fn deserialize_http_rule() {
+        let payload = r#"{"id":"rule-id","orgID":"org-id","name":"hook","status":"active","endpointID":"endpoint-id","every":"5m","type":"http","statusRules":[{"currentLevel":"ANY"}],"tagRules":[{"key":"env","value":"prod","operator":"equal"}]}"#;
+        let result: NotificationRule = serde_json::from_str(payload).unwrap();
+        assert_eq!(NotificationRuleKind::Http {}, result.kind);
+        assert_eq!(vec![StatusRule::is(CheckStatusLevel::Any)], result.status_rules);
+        let payload = payload.replace(r#""type":"http""#, r#""type":"telegram","parseMode":"MarkdownV2""#);
+        let result: NotificationRule = serde_json::from_str(&payload).unwrap();
+        let kind: Map<String, Value> = serde_json::from_str(r#"{"type":"telegram","parseMode":"MarkdownV2"}"#).unwrap();
+        assert_eq!(NotificationRuleKind::Unknown(kind), result.kind);
+        assert_eq!(TagRuleOperator::Equal, result.tag_rules[0].operator);
+        let round_trip: NotificationRule = serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+        assert_eq!(result, round_trip);
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum FieldValue {
+    Float(f64),
+    Integer(i64),
+    UInteger(u64),
+    String(String),
+    Boolean(bool),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Point {
+    pub measurement: String,
+    pub tags: BTreeMap<String, String>,
+    pub fields: BTreeMap<String, FieldValue>,
+    pub timestamp: Option<i64>,
+}
+
+impl Point {
+    pub fn new(measurement: &str) -> Self {
+        Point {
+            measurement: measurement.to_string(),
+            tags: BTreeMap::new(),
+            fields: BTreeMap::new(),
+            timestamp: None,
+        }
+    }
+
+    pub fn tag(mut self, key: &str, value: &str) -> Self {
+        self.tags.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn field(mut self, key: &str, value: impl Into<FieldValue>) -> Self {
+        self.fields.insert(key.to_string(), value.into());
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: i64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+}
+
+impl From<f64> for FieldValue {
+    fn from(value: f64) -> Self {
+        FieldValue::Float(value)
+    }
+}
+
+impl From<i64> for FieldValue {
+    fn from(value: i64) -> Self {
+        FieldValue::Integer(value)
+    }
+}
+
+impl From<u64> for FieldValue {
+    fn from(value: u64) -> Self {
+        FieldValue::UInteger(value)
+    }
+}
+
+impl From<bool> for FieldValue {
+    fn from(value: bool) -> Self {
+        FieldValue::Boolean(value)
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(value: String) -> Self {
+        FieldValue::String(value)
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(value: &str) -> Self {
+        FieldValue::String(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_point() {
+        let result = Point::new("cpu")
+            .tag("host", "a")
+            .field("usage", 1.5)
+            .field("count", 3_i64)
+            .field("state", "ok")
+            .timestamp(10);
+        assert_eq!("cpu", result.measurement);
+        assert_eq!(Some(&"a".to_string()), result.tags.get("host"));
+        assert_eq!(Some(&FieldValue::Float(1.5)), result.fields.get("usage"));
+        assert_eq!(Some(&FieldValue::Integer(3)), result.fields.get("count"));
+        assert_eq!(Some(&FieldValue::String("ok".to_string())), result.fields.get("state"));
+        assert_eq!(Some(10), result.timestamp);
+    }
+}
@@ -0,0 +1,61 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Precision {
+    Auto,
+    Second,
+    Millisecond,
+    Microsecond,
+    Nanosecond,
+}
+
+impl Precision {
+    pub fn v3_value(&self) -> &'static str {
+        match self {
+            Precision::Auto => "auto",
+            Precision::Second => "second",
+            Precision::Millisecond => "millisecond",
+            Precision::Microsecond => "microsecond",
+            Precision::Nanosecond => "nanosecond",
+        }
+    }
+
+    pub fn v2_value(&self) -> &'static str {
+        match self {
+            Precision::Second => "s",
+            Precision::Millisecond => "ms",
+            Precision::Microsecond => "us",
+            Precision::Auto | Precision::Nanosecond => "ns",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn v3_value_correct() {
+        assert_eq!("auto", Precision::Auto.v3_value());
+        assert_eq!("second", Precision::Second.v3_value());
+        assert_eq!("millisecond", Precision::Millisecond.v3_value());
+        assert_eq!("microsecond", Precision::Microsecond.v3_value());
+        assert_eq!("nanosecond", Precision::Nanosecond.v3_value());
+    }
+
+    #[test]
+    fn v2_value_correct() {
+        assert_eq!("ns", Precision::Auto.v2_value());
+        assert_eq!("s", Precision::Second.v2_value());
+        assert_eq!("ms", Precision::Millisecond.v2_value());
+        assert_eq!("us", Precision::Microsecond.v2_value());
+        assert_eq!("ns", Precision::Nanosecond.v2_value());
+    }
+
+    #[test]
+    fn deserialize() {
+        let result: Precision = serde_json::from_str(r#""millisecond""#).expect("Cannot deserialize");
+        assert_eq!(Precision::Millisecond, result);
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct RequestLimits {
+    #[serde(default)]
+    pub max_in_flight_writes: Option<usize>,
+    #[serde(default)]
+    pub max_in_flight_queries: Option<usize>,
+    #[serde(default)]
+    pub requests_per_second: Option<f64>,
+    #[serde(default)]
+    pub request_burst: Option<f64>,
+    #[serde(default)]
+    pub bytes_per_second: Option<f64>,
+    #[serde(default)]
+    pub byte_burst: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let payload = r#"{"max_in_flight_writes":8,"max_in_flight_queries":2,"requests_per_second":50.0,"request_burst":10.0}"#;
+        let result: RequestLimits = serde_json::from_str(payload).expect("Cannot deserialize");
+        assert_eq!(
+            RequestLimits {
+                max_in_flight_writes: Some(8),
+                max_in_flight_queries: Some(2),
+                requests_per_second: Some(50.0),
+                request_burst: Some(10.0),
+                bytes_per_second: None,
+                byte_burst: None,
+            },
+            result
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+use crate::model::batch_writer_config::BatchWriterConfig;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct RequestMetricsConfig {
+    #[serde(default = "default_measurement")]
+    pub measurement: String,
+    #[serde(default)]
+    pub excluded_paths: Vec<String>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    #[serde(default)]
+    pub batch_config: BatchWriterConfig,
+}
+
+fn default_measurement() -> String {
+    "http_requests".to_string()
+}
+
+impl RequestMetricsConfig {
+    pub fn is_excluded(&self, path: &str) -> bool {
+        self.excluded_paths.iter().any(|excluded| match excluded.strip_suffix('*') {
+            Some(prefix) => path.starts_with(prefix),
+            None => path == excluded,
+        })
+    }
+}
+
+impl Default for RequestMetricsConfig {
+    fn default() -> Self {
+        RequestMetricsConfig {
+            measurement: default_measurement(),
+            excluded_paths: vec![],
+            tags: BTreeMap::new(),
+            batch_config: BatchWriterConfig::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_defaults() {
+        let result: RequestMetricsConfig = serde_json::from_str("{}").expect("Cannot deserialize");
+        assert_eq!(RequestMetricsConfig::default(), result);
+    }
+
+    #[test]
+    fn excludes_exact_and_prefix_paths() {
+        let config = RequestMetricsConfig {
+            excluded_paths: vec!["/health".to_string(), "/static/*".to_string()],
+            ..RequestMetricsConfig::default()
+        };
+        assert!(config.is_excluded("/health"));
+        assert!(config.is_excluded("/static/app.js"));
+        assert!(!config.is_excluded("/healthz"));
+        assert!(!config.is_excluded("/users"));
+    }
+}
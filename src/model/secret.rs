@@ -0,0 +1,71 @@
+use std::fmt::{self, Display, Formatter};
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Secret(value.into())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Display for Secret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "[REDACTED]")
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "Secret([REDACTED])")
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Secret(value.to_string())
+    }
+}
+
+impl From<&Secret> for Secret {
+    fn from(value: &Secret) -> Self {
+        value.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_formatting() {
+        let secret = Secret::from("token");
+        assert_eq!("[REDACTED]", secret.to_string());
+        assert_eq!("Secret([REDACTED])", format!("{:?}", secret));
+        assert!(!format!("{:#?}", Some(&secret)).contains("token"));
+        assert_eq!("token", secret.expose());
+    }
+
+    #[test]
+    fn serde_transparent() {
+        let secret: Secret = serde_json::from_str(r#""token""#).unwrap();
+        assert_eq!(Secret::new("token"), secret);
+        assert_eq!(r#""token""#, serde_json::to_string(&secret).unwrap());
+    }
+}
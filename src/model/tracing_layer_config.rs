@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+use crate::model::batch_writer_config::BatchWriterConfig;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct TracingLayerConfig {
+    #[serde(default)]
+    pub measurements: BTreeMap<String, String>,
+    #[serde(default)]
+    pub tag_fields: Vec<String>,
+    #[serde(default)]
+    pub value_fields: Vec<String>,
+    #[serde(default)]
+    pub event_measurement: Option<String>,
+    #[serde(default = "default_event_level")]
+    pub event_level: String,
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: f64,
+    #[serde(default)]
+    pub batch_config: BatchWriterConfig,
+}
+
+fn default_event_level() -> String {
+    "info".to_string()
+}
+
+fn default_sample_rate() -> f64 {
+    1.0
+}
+
+impl Default for TracingLayerConfig {
+    fn default() -> Self {
+        TracingLayerConfig {
+            measurements: BTreeMap::new(),
+            tag_fields: vec![],
+            value_fields: vec![],
+            event_measurement: None,
+            event_level: default_event_level(),
+            sample_rate: default_sample_rate(),
+            batch_config: BatchWriterConfig::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_defaults() {
+        let result: TracingLayerConfig = serde_json::from_str("{}").expect("Cannot deserialize");
+        assert_eq!(TracingLayerConfig::default(), result);
+    }
+}
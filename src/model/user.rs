@@ -0,0 +1,61 @@
+use serde::{Serialize, Deserialize};
+use crate::model::secret::Secret;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct User {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Users {
+    pub users: Vec<User>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PasswordRequest {
+    pub password: Secret,
+}
+
+impl User {
+    pub fn new(name: &str) -> Self {
+        User {
+            id: None,
+            name: name.to_string(),
+            status: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize() {
+        assert_eq!(r#"{"name":"alice"}"#, serde_json::to_string(&User::new("alice")).unwrap());
+    }
+
+    #[test]
+    fn deserialize_users() {
+        let result: Users = serde_json::from_str(r#"{"users":[{"id":"user-id","name":"alice","status":"active","links":{}}]}"#).unwrap();
+        assert_eq!(
+            vec![User {
+                id: Some("user-id".to_string()),
+                name: "alice".to_string(),
+                status: Some("active".to_string()),
+            }],
+            result.users
+        );
+    }
+
+    #[test]
+    fn serialize_password() {
+        let payload = PasswordRequest { password: Secret::from("secret") };
+        assert_eq!(r#"{"password":"secret"}"#, serde_json::to_string(&payload).unwrap());
+        assert!(!format!("{:?}", payload).contains("secret"));
+    }
+}
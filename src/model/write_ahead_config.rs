@@ -0,0 +1,59 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum OverflowPolicy {
+    DropOldest,
+    RejectNew,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct WriteAheadConfig {
+    pub directory: String,
+    pub segment_max_bytes: u64,
+    pub max_total_bytes: u64,
+    pub overflow_policy: OverflowPolicy,
+    pub sync_on_append: bool,
+}
+
+impl WriteAheadConfig {
+    pub fn new(directory: &str) -> Self {
+        WriteAheadConfig {
+            directory: directory.to_string(),
+            segment_max_bytes: 8 * 1024 * 1024,
+            max_total_bytes: 512 * 1024 * 1024,
+            overflow_policy: OverflowPolicy::DropOldest,
+            sync_on_append: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_uses_defaults() {
+        let result = WriteAheadConfig::new("/var/lib/influxdb-client");
+        assert_eq!("/var/lib/influxdb-client", result.directory);
+        assert_eq!(8 * 1024 * 1024, result.segment_max_bytes);
+        assert_eq!(512 * 1024 * 1024, result.max_total_bytes);
+        assert_eq!(OverflowPolicy::DropOldest, result.overflow_policy);
+        assert!(result.sync_on_append);
+    }
+
+    #[test]
+    fn deserialize() {
+        let payload = r#"{"directory":"wal","segment_max_bytes":10,"max_total_bytes":100,"overflow_policy":"RejectNew","sync_on_append":false}"#;
+        let result: WriteAheadConfig = serde_json::from_str(payload).expect("Cannot deserialize");
+        assert_eq!(
+            WriteAheadConfig {
+                directory: "wal".to_string(),
+                segment_max_bytes: 10,
+                max_total_bytes: 100,
+                overflow_policy: OverflowPolicy::RejectNew,
+                sync_on_append: false,
+            },
+            result
+        );
+    }
+}
@@ -0,0 +1,11 @@
+use std::time::Duration;
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct WriteAheadStats {
+    pub pending_records: usize,
+    pub pending_bytes: u64,
+    pub disk_bytes: u64,
+    pub segments: usize,
+    pub oldest_age: Option<Duration>,
+    pub dropped_records: u64,
+}
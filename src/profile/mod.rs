@@ -0,0 +1 @@
+pub mod profile_loader;
\ No newline at end of file
@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+use crate::error::profile_error::ProfileError;
+use crate::model::connection_profile::{ConnectionProfile, InfluxdbProfile};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+
+pub fn configs_path() -> Option<PathBuf> {
+    configs_path_with(|name| std::env::var(name).ok())
+}
+
+pub fn load_profiles(path: &Path) -> Result<BTreeMap<String, ConnectionProfile>, ProfileError> {
+    let contents = fs::read_to_string(path)?;
+    toml::from_str(&contents).map_err(|error| ProfileError::Parse(error.to_string()))
+}
+
+pub fn load_profile(path: &Path, name: Option<&str>) -> Result<InfluxdbProfile, ProfileError> {
+    load_profile_with(path, name, |name| std::env::var(name).ok())
+}
+
+pub fn load_default_profile(name: Option<&str>) -> Result<InfluxdbProfile, ProfileError> {
+    let path = configs_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "cannot locate the influx configs file"))?;
+    load_profile(&path, name)
+}
+
+pub (crate) fn configs_path_with(environment: impl Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    if let Some(path) = environment("INFLUX_CONFIGS_PATH") {
+        return Some(PathBuf::from(path));
+    }
+    environment("HOME")
+        .or_else(|| environment("USERPROFILE"))
+        .map(|home| Path::new(&home).join(".influxdbv2").join("configs"))
+}
+
+pub (crate) fn load_profile_with(
+    path: &Path,
+    name: Option<&str>,
+    environment: impl Fn(&str) -> Option<String>
+) -> Result<InfluxdbProfile, ProfileError> {
+    let profiles = load_profiles(path)?;
+    let name = name.map(str::to_string).or_else(|| environment("INFLUX_ACTIVE_CONFIG"));
+    let (name, profile) = match name {
+        Some(name) => {
+            let profile = profiles.get(&name).ok_or_else(|| ProfileError::NotFound(name.clone()))?;
+            (name, profile.clone())
+        }
+        None => profiles
+            .into_iter()
+            .find(|(_, profile)| profile.active)
+            .ok_or(ProfileError::NoActiveProfile)?,
+    };
+    to_influxdb_profile(name, profile, environment)
+}
+
+fn to_influxdb_profile(
+    name: String,
+    profile: ConnectionProfile,
+    environment: impl Fn(&str) -> Option<String>
+) -> Result<InfluxdbProfile, ProfileError> {
+    let url = environment("INFLUX_HOST").or(profile.url).filter(|url| !url.is_empty());
+    let token = environment("INFLUX_TOKEN").map(Secret::from).or(profile.token).filter(|token| !token.is_empty());
+    let org = environment("INFLUX_ORG").or(profile.org).filter(|org| !org.is_empty());
+    let bucket = environment("INFLUX_BUCKET").or(profile.bucket).unwrap_or_default();
+    match (url, token, org) {
+        (Some(url), Some(token), Some(org)) => Ok(InfluxdbProfile {
+            name,
+            influxdb_token: token,
+            influxdb_config: InfluxdbConfig {
+                address: url.trim_end_matches('/').to_string(),
+                organisation: org,
+                bucket,
+                influxdb_token_path: "".to_string(),
+                limits: None,
+            },
+        }),
+        (url, token, org) => {
+            let missing = [("url", url.is_none()), ("token", token.is_none()), ("org", org.is_none())]
+                .iter()
+                .filter(|(_, missing)| *missing)
+                .map(|(field, _)| field.to_string())
+                .collect();
+            Err(ProfileError::MissingFields(name, missing))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use super::*;
+
+    const CONFIGS: &str = r#"
+[local]
+  url = "http://localhost:8086/"
+  token = "local-token"
+  org = "dev"
+  active = true
+
+[staging]
+  url = "https://staging.example.com"
+  token = "staging-token"
+  org = "platform"
+  bucket = "metrics"
+
+[broken]
+  url = ""
+"#;
+
+    fn environment(values: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let values: HashMap<String, String> = values.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect();
+        move |name| values.get(name).cloned()
+    }
+
+    fn file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("influxdb-client-profiles-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn loads_active_profile() {
+        let path = file("active", CONFIGS);
+        let result = load_profile_with(&path, None, environment(&[])).unwrap();
+        assert_eq!("local", result.name);
+        assert_eq!("local-token", result.influxdb_token.expose());
+        assert_eq!(
+            InfluxdbConfig {
+                address: "http://localhost:8086".to_string(),
+                organisation: "dev".to_string(),
+                bucket: "".to_string(),
+                influxdb_token_path: "".to_string(),
+                limits: None,
+            },
+            result.influxdb_config
+        );
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn loads_named_profile_with_overrides() {
+        let path = file("named", CONFIGS);
+        let result = load_profile_with(&path, Some("staging"), environment(&[("INFLUX_ORG", "override"), ("INFLUX_TOKEN", "env-token")])).unwrap();
+        assert_eq!("env-token", result.influxdb_token.expose());
+        assert_eq!("override", result.influxdb_config.organisation);
+        assert_eq!("metrics", result.influxdb_config.bucket);
+        let result = load_profile_with(&path, None, environment(&[("INFLUX_ACTIVE_CONFIG", "staging")])).unwrap();
+        assert_eq!("staging", result.name);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reports_missing_fields() {
+        let path = file("missing", CONFIGS);
+        let result = load_profile_with(&path, Some("broken"), environment(&[]));
+        assert_eq!("Profile broken is missing url, token, org", result.unwrap_err().to_string());
+        let result = load_profile_with(&path, Some("broken"), environment(&[("INFLUX_HOST", "http://env:8086"), ("INFLUX_ORG", "org")]));
+        assert_eq!("Profile broken is missing token", result.unwrap_err().to_string());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reports_unknown_and_inactive_profiles() {
+        let path = file("unknown", "[local]\n  url = \"http://localhost:8086\"\n");
+        assert_eq!("Profile prod not found", load_profile_with(&path, Some("prod"), environment(&[])).unwrap_err().to_string());
+        assert_eq!("No profile name given and no active profile", load_profile_with(&path, None, environment(&[])).unwrap_err().to_string());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reports_invalid_files() {
+        let path = file("invalid", "[local\n");
+        assert!(matches!(load_profile_with(&path, None, environment(&[])), Err(ProfileError::Parse(_))));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn locates_configs_file() {
+        assert_eq!(Some(PathBuf::from("/etc/influx/configs")), configs_path_with(environment(&[("INFLUX_CONFIGS_PATH", "/etc/influx/configs")])));
+        assert_eq!(Some(PathBuf::from("/home/dev/.influxdbv2/configs")), configs_path_with(environment(&[("HOME", "/home/dev")])));
+        assert_eq!(None, configs_path_with(environment(&[])));
+    }
+}
@@ -0,0 +1,267 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use arrow_array::RecordBatch;
+use arrow_schema::SchemaRef;
+use log::warn;
+use parquet::arrow::ArrowWriter;
+use crate::error::arrow_export_error::ArrowExportError;
+use crate::mapper::arrow_mapper::to_record_batches;
+use crate::mapper::flux_csv_mapper::parse_flux_csv;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+use crate::repository::influxdb_repository::{read_from_influxdb, stream_from_influxdb};
+
+pub async fn query_to_record_batches(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    body: String
+) -> Result<Vec<RecordBatch>, ArrowExportError> {
+    let result = read_from_influxdb(influxdb_token, influxdb_config, body).await?;
+    to_record_batches(&parse_flux_csv(&result)?)
+}
+
+pub async fn query_to_parquet(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    body: String,
+    path: &Path
+) -> Result<usize, ArrowExportError> {
+    let mut sink = ParquetSink { path, writers: vec![], paths: vec![], rows: 0 };
+    let mut reader = TableReader::default();
+    let result = stream_from_influxdb(influxdb_token, influxdb_config, body, |chunk| reader.push(&mut sink, chunk))
+        .await
+        .and_then(|_| reader.finish(&mut sink))
+        .and_then(|_| sink.close());
+    if result.is_err() {
+        sink.remove();
+    }
+    result
+}
+
+#[derive(Default)]
+struct TableReader {
+    pending: Vec<u8>,
+    prefix: Vec<u8>,
+    header: bool,
+    rows: Vec<u8>,
+}
+
+impl TableReader {
+    fn push(&mut self, sink: &mut ParquetSink<'_>, chunk: &[u8]) -> Result<(), ArrowExportError> {
+        self.pending.extend_from_slice(chunk);
+        let mut lines = vec![];
+        let mut start = 0;
+        let mut quoted = false;
+        for (index, byte) in self.pending.iter().enumerate() {
+            match byte {
+                b'"' => quoted = !quoted,
+                b'\n' if !quoted => {
+                    lines.push(start..index + 1);
+                    start = index + 1;
+                }
+                _ => {}
+            }
+        }
+        let complete: Vec<u8> = self.pending.drain(..start).collect();
+        for line in lines {
+            self.line(sink, &complete[line])?;
+        }
+        self.flush(sink)
+    }
+
+    fn finish(&mut self, sink: &mut ParquetSink<'_>) -> Result<(), ArrowExportError> {
+        let rest = std::mem::take(&mut self.pending);
+        if !rest.is_empty() {
+            self.line(sink, &rest)?;
+        }
+        self.flush(sink)
+    }
+
+    fn line(&mut self, sink: &mut ParquetSink<'_>, line: &[u8]) -> Result<(), ArrowExportError> {
+        if line.iter().all(u8::is_ascii_whitespace) {
+            self.flush(sink)?;
+            self.prefix.clear();
+            self.header = false;
+        } else if self.header {
+            self.rows.extend_from_slice(line);
+        } else {
+            self.header = !line.starts_with(b"#");
+            self.prefix.extend_from_slice(line);
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self, sink: &mut ParquetSink<'_>) -> Result<(), ArrowExportError> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+        let mut table = self.prefix.clone();
+        table.append(&mut self.rows);
+        sink.write(&table)
+    }
+}
+
+struct ParquetSink<'a> {
+    path: &'a Path,
+    writers: Vec<(ArrowWriter<File>, SchemaRef)>,
+    paths: Vec<PathBuf>,
+    rows: usize,
+}
+
+impl ParquetSink<'_> {
+    fn write(&mut self, blocks: &[u8]) -> Result<(), ArrowExportError> {
+        let blocks = String::from_utf8_lossy(blocks);
+        for batch in to_record_batches(&parse_flux_csv(&blocks)?)? {
+            let index = match self.writers.iter().position(|(_, schema)| *schema == batch.schema()) {
+                Some(index) => index,
+                None => self.open(batch.schema())?,
+            };
+            self.writers[index].0.write(&batch)?;
+            self.rows += batch.num_rows();
+        }
+        Ok(())
+    }
+
+    fn open(&mut self, schema: SchemaRef) -> Result<usize, ArrowExportError> {
+        let path = schema_path(self.path, self.paths.len());
+        if !self.paths.is_empty() {
+            warn!("Table schema differs from earlier tables, writing to {:?}", path);
+        }
+        let file = File::create(&path)?;
+        self.paths.push(path);
+        self.writers.push((ArrowWriter::try_new(file, schema.clone(), None)?, schema));
+        Ok(self.writers.len() - 1)
+    }
+
+    fn close(&mut self) -> Result<usize, ArrowExportError> {
+        for (writer, _) in self.writers.drain(..) {
+            writer.close()?;
+        }
+        Ok(self.rows)
+    }
+
+    fn remove(&mut self) {
+        self.writers.clear();
+        for path in self.paths.drain(..) {
+            if let Err(error) = fs::remove_file(&path) {
+                warn!("Failed to remove partial export {:?}: {}", path, error);
+            }
+        }
+    }
+}
+
+fn schema_path(path: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        return path.to_path_buf();
+    }
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    match path.extension() {
+        Some(extension) => path.with_file_name(format!("{}-{}.{}", stem, index, extension.to_string_lossy())),
+        None => path.with_file_name(format!("{}-{}", stem, index)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use super::*;
+    use crate::repository::influxdb_repository::write_to_influxdb;
+    use crate::telemetry::client_stats::address_stats;
+    use crate::test_support::fake_influxdb::{FakeFailure, FakeInfluxDb};
+
+    const QUERY: &str = r#"from(bucket: "bucket") |> range(start: 0)"#;
+
+    async fn seeded() -> FakeInfluxDb {
+        let fake = FakeInfluxDb::start(&["token"]);
+        write_to_influxdb(
+            "token".to_string(),
+            &fake.config("organisation", "bucket"),
+            "cpu,host=a usage=1.5 1\ncpu,host=b usage=2.5 1\ncpu,host=a usage=3.5 2".to_string()
+        ).await.unwrap();
+        fake
+    }
+
+    #[actix_rt::test]
+    async fn query_to_record_batches_success() {
+        let fake = seeded().await;
+        let result = query_to_record_batches("token".to_string(), &fake.config("organisation", "bucket"), QUERY.to_string()).await.unwrap();
+        assert_eq!(3, result.iter().map(RecordBatch::num_rows).sum::<usize>());
+    }
+
+    #[actix_rt::test]
+    async fn query_to_parquet_success() {
+        let fake = seeded().await;
+        let path = std::env::temp_dir().join(format!("influxdb-client-arrow-{}.parquet", std::process::id()));
+        let result = query_to_parquet("token".to_string(), &fake.config("organisation", "bucket"), QUERY.to_string(), &path).await;
+        assert_eq!(3, result.unwrap());
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(&path).unwrap()).unwrap().build().unwrap();
+        let rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+        assert_eq!(3, rows);
+        assert_eq!(1, address_stats(&fake.address()).unwrap().queries);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn table_reader_flushes_complete_rows() {
+        let path = std::env::temp_dir().join(format!("influxdb-client-arrow-rows-{}.parquet", std::process::id()));
+        let mut sink = ParquetSink { path: &path, writers: vec![], paths: vec![], rows: 0 };
+        let mut reader = TableReader::default();
+        reader.push(&mut sink, b"#datatype,string,long,string\r\n#group,false,false,true\r\n").unwrap();
+        reader.push(&mut sink, b"#default,_result,,\r\n,result,table,host\r\n,,0,a\r\n,,0,\"b").unwrap();
+        assert_eq!(1, sink.rows);
+        reader.push(&mut sink, b"\nc\"\r\n,,0,").unwrap();
+        assert_eq!(2, sink.rows);
+        reader.push(&mut sink, b"d\r\n\r\n").unwrap();
+        reader.finish(&mut sink).unwrap();
+        assert_eq!(3, sink.close().unwrap());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn query_to_parquet_splits_differing_schemas() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        write_to_influxdb(
+            "token".to_string(),
+            &fake.config("organisation", "bucket"),
+            "cpu usage=1.5 1\ncpu state=\"ok\" 1\ncpu usage=2.5 2".to_string()
+        ).await.unwrap();
+        let path = std::env::temp_dir().join(format!("influxdb-client-arrow-split-{}.parquet", std::process::id()));
+        let result = query_to_parquet("token".to_string(), &fake.config("organisation", "bucket"), QUERY.to_string(), &path).await;
+        assert_eq!(3, result.unwrap());
+        let rows = |path: &Path| -> usize {
+            let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap()).unwrap().build().unwrap();
+            reader.map(|batch| batch.unwrap().num_rows()).sum()
+        };
+        let second = schema_path(&path, 1);
+        assert_eq!(3, rows(&path) + rows(&second));
+        fs::remove_file(path).unwrap();
+        fs::remove_file(second).unwrap();
+    }
+
+    #[actix_rt::test]
+    async fn query_to_parquet_removes_partial_file() {
+        let fake = seeded().await;
+        fake.inject_failure(FakeFailure::MalformedCsv);
+        let path = std::env::temp_dir().join(format!("influxdb-client-arrow-partial-{}.parquet", std::process::id()));
+        let result = query_to_parquet("token".to_string(), &fake.config("organisation", "bucket"), QUERY.to_string(), &path).await;
+        assert!(result.is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn schema_path_adds_index() {
+        assert_eq!(PathBuf::from("/tmp/export.parquet"), schema_path(Path::new("/tmp/export.parquet"), 0));
+        assert_eq!(PathBuf::from("/tmp/export-2.parquet"), schema_path(Path::new("/tmp/export.parquet"), 2));
+        assert_eq!(PathBuf::from("/tmp/export-1"), schema_path(Path::new("/tmp/export"), 1));
+    }
+
+    #[actix_rt::test]
+    async fn query_to_parquet_error() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let path = std::env::temp_dir().join(format!("influxdb-client-arrow-error-{}.parquet", std::process::id()));
+        let result = query_to_parquet("wrong".to_string(), &fake.config("organisation", "bucket"), QUERY.to_string(), &path).await;
+        assert!(result.is_err());
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,85 @@
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_api_request;
+use crate::mapper::response_mapper::{map_json_response, map_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::authorization::{Authorization, Authorizations};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+
+pub async fn list_authorizations(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig
+) -> Result<Vec<Authorization>, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "authorizations");
+    let result = get_api_request(influxdb_token.into(), Method::GET, url)
+        .query(&[("org", &influxdb_config.organisation)])
+        .send()
+        .await;
+    let authorizations: Authorizations = map_json_response(result).await?;
+    Ok(authorizations.authorizations)
+}
+
+pub async fn create_authorization(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    authorization: &Authorization
+) -> Result<Authorization, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "authorizations");
+    let result = get_api_request(influxdb_token.into(), Method::POST, url)
+        .json(authorization)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn delete_authorization(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    authorization_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("authorizations/{}", authorization_id));
+    let result = get_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send()
+        .await;
+    map_response(result).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::authorization::Permission;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    #[actix_rt::test]
+    async fn list_authorizations_success() {
+        let harness = setup_test_harness();
+        let result = list_authorizations("token".to_string(), &test_config(harness.url("success"))).await.unwrap();
+        assert_eq!(1, result.len());
+        assert_eq!(vec![Permission::bucket("write", "org-id", "bucket-id")], result[0].permissions);
+    }
+
+    #[actix_rt::test]
+    async fn create_authorization_success() {
+        let harness = setup_test_harness();
+        let authorization = Authorization {
+            id: None,
+            org_id: "org-id".to_string(),
+            description: Some("reader".to_string()),
+            status: None,
+            token: None,
+            permissions: vec![Permission::bucket("read", "org-id", "bucket-id")],
+        };
+        let result = create_authorization("token".to_string(), &test_config(harness.url("success")), &authorization).await.unwrap();
+        assert_eq!(Some("new-token".to_string()), result.token);
+        assert_eq!(authorization.permissions, result.permissions);
+    }
+
+    #[actix_rt::test]
+    async fn delete_authorization_success() {
+        let harness = setup_test_harness();
+        let result = delete_authorization("token".to_string(), &test_config(harness.url("success")), "auth-id").await;
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,109 @@
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_api_request;
+use crate::mapper::response_mapper::{map_json_response, map_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::bucket::{Bucket, Buckets};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+
+const PAGE_SIZE: usize = 100;
+
+pub async fn list_buckets(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig
+) -> Result<Vec<Bucket>, InfluxDbError<Option<Error>>> {
+    let influxdb_token = influxdb_token.into();
+    let url = to_influxdb_api_url(influxdb_config, "buckets");
+    let mut buckets = vec![];
+    loop {
+        let result = get_api_request(influxdb_token.clone(), Method::GET, url.clone())
+            .query(&[("org", &influxdb_config.organisation)])
+            .query(&[("limit", PAGE_SIZE), ("offset", buckets.len())])
+            .send()
+            .await;
+        let page: Buckets = map_json_response(result).await?;
+        let count = page.buckets.len();
+        buckets.extend(page.buckets);
+        if count < PAGE_SIZE {
+            return Ok(buckets);
+        }
+    }
+}
+
+pub async fn create_bucket(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    bucket: &Bucket
+) -> Result<Bucket, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "buckets");
+    let result = get_api_request(influxdb_token.into(), Method::POST, url)
+        .json(bucket)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn delete_bucket(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    bucket_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("buckets/{}", bucket_id));
+    let result = get_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send()
+        .await;
+    map_response(result).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::bucket::RetentionRule;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    #[actix_rt::test]
+    async fn list_buckets_success() {
+        let harness = setup_test_harness();
+        let result = list_buckets("token".to_string(), &test_config(harness.url("success"))).await.unwrap();
+        assert_eq!(1, result.len());
+        assert_eq!(Some("bucket-id".to_string()), result[0].id);
+        assert_eq!(vec![RetentionRule { rule_type: "expire".to_string(), every_seconds: 3600 }], result[0].retention_rules);
+    }
+
+    #[actix_rt::test]
+    async fn list_buckets_reads_every_page() {
+        let harness = setup_test_harness();
+        let result = list_buckets("token".to_string(), &test_config(harness.url("paged"))).await.unwrap();
+        assert_eq!(101, result.len());
+    }
+
+    #[actix_rt::test]
+    async fn list_buckets_error() {
+        let harness = setup_test_harness();
+        let result = list_buckets("token".to_string(), &test_config(harness.url("fails"))).await;
+        assert!(result.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn create_bucket_success() {
+        let harness = setup_test_harness();
+        let bucket = Bucket {
+            id: None,
+            org_id: "org-id".to_string(),
+            name: "new".to_string(),
+            description: None,
+            retention_rules: vec![],
+        };
+        let result = create_bucket("token".to_string(), &test_config(harness.url("success")), &bucket).await.unwrap();
+        assert_eq!(Bucket { id: Some("new-bucket-id".to_string()), ..bucket }, result);
+    }
+
+    #[actix_rt::test]
+    async fn delete_bucket_success() {
+        let harness = setup_test_harness();
+        let result = delete_bucket("token".to_string(), &test_config(harness.url("success")), "bucket-id").await;
+        assert!(result.is_ok());
+    }
+}
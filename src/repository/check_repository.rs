@@ -0,0 +1,148 @@
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_api_request;
+use crate::mapper::response_mapper::{map_json_response, map_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::check::{Check, Checks};
+use crate::model::secret::Secret;
+
+const PAGE_SIZE: usize = 100;
+
+pub async fn list_checks(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str
+) -> Result<Vec<Check>, InfluxDbError<Option<Error>>> {
+    let influxdb_token = influxdb_token.into();
+    let url = to_influxdb_api_url(influxdb_config, "checks");
+    let mut checks = vec![];
+    loop {
+        let result = get_api_request(influxdb_token.clone(), Method::GET, url.clone())
+            .query(&[("orgID", org_id)])
+            .query(&[("limit", PAGE_SIZE), ("offset", checks.len())])
+            .send()
+            .await;
+        let page: Checks = map_json_response(result).await?;
+        let count = page.checks.len();
+        checks.extend(page.checks);
+        if count < PAGE_SIZE {
+            return Ok(checks);
+        }
+    }
+}
+
+pub async fn get_check(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    check_id: &str
+) -> Result<Check, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("checks/{}", check_id));
+    let result = get_api_request(influxdb_token.into(), Method::GET, url)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn create_check(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    check: &Check
+) -> Result<Check, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "checks");
+    let result = get_api_request(influxdb_token.into(), Method::POST, url)
+        .json(check)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn update_check(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    check_id: &str,
+    check: &Check
+) -> Result<Check, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("checks/{}", check_id));
+    let result = get_api_request(influxdb_token.into(), Method::PUT, url)
+        .json(check)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn delete_check(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    check_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("checks/{}", check_id));
+    let result = get_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send()
+        .await;
+    map_response(result).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::check::{CheckKind, CheckStatusLevel, Threshold};
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    fn check() -> Check {
+        Check {
+            id: Some("check-id".to_string()),
+            status: Some("active".to_string()),
+            ..Check::threshold("org-id", "cpu", "from(bucket: \"telegraf\")", "1m", vec![Threshold::greater(CheckStatusLevel::Crit, 90.0)])
+        }
+    }
+
+    #[actix_rt::test]
+    async fn list_and_get_checks_success() {
+        let harness = setup_test_harness();
+        let result = list_checks("token".to_string(), &test_config(harness.url("success")), "org-id").await.unwrap();
+        assert_eq!(vec![check()], result);
+        let result = get_check("token".to_string(), &test_config(harness.url("success")), "check-id").await.unwrap();
+        assert_eq!(check(), result);
+    }
+
+    #[actix_rt::test]
+    async fn list_checks_paged() {
+        let harness = setup_test_harness();
+        let result = list_checks("token".to_string(), &test_config(harness.url("paged")), "org-id").await.unwrap();
+        assert_eq!(101, result.len());
+        assert_eq!(check(), result[100]);
+    }
+
+    #[actix_rt::test]
+    async fn list_checks_error() {
+        let harness = setup_test_harness();
+        let result = list_checks("token".to_string(), &test_config(harness.url("success")), "").await;
+        assert!(result.is_err());
+        let result = list_checks("token".to_string(), &test_config(harness.url("fails")), "org-id").await;
+        assert!(result.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn create_update_and_delete_check_success() {
+        let harness = setup_test_harness();
+        let influxdb_config = test_config(harness.url("success"));
+        let deadman = Check::deadman("org-id", "heartbeat", "from(bucket: \"telegraf\")", "1m", "90s", CheckStatusLevel::Crit);
+        let result = create_check("token".to_string(), &influxdb_config, &deadman).await.unwrap();
+        assert_eq!(Some("new-id".to_string()), result.id);
+        assert_eq!(deadman.kind, result.kind);
+        let update = Check {
+            kind: CheckKind::Deadman {
+                time_since: "5m".to_string(),
+                stale_time: Some("1h".to_string()),
+                report_zero: true,
+                level: CheckStatusLevel::Warn,
+            },
+            ..result
+        };
+        let result = update_check("token".to_string(), &influxdb_config, "new-id", &update).await.unwrap();
+        assert_eq!(update, result);
+        assert!(delete_check("token".to_string(), &influxdb_config, "new-id").await.is_ok());
+    }
+}
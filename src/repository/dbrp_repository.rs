@@ -0,0 +1,215 @@
+use log::warn;
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_api_request;
+use crate::mapper::response_mapper::{map_json_response, map_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::bucket::Bucket;
+use crate::model::dbrp::{Dbrp, DbrpFilter, DbrpResponse, DbrpUpdate, Dbrps};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+use crate::repository::bucket_repository::{create_bucket, delete_bucket};
+
+pub async fn list_dbrps(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str,
+    filter: &DbrpFilter
+) -> Result<Vec<Dbrp>, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "dbrps");
+    let result = get_api_request(influxdb_token.into(), Method::GET, url)
+        .query(&[("orgID", org_id)])
+        .query(filter)
+        .send()
+        .await;
+    let dbrps: Dbrps = map_json_response(result).await?;
+    Ok(dbrps.content)
+}
+
+pub async fn get_dbrp(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str,
+    dbrp_id: &str
+) -> Result<Dbrp, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("dbrps/{}", dbrp_id));
+    let result = get_api_request(influxdb_token.into(), Method::GET, url)
+        .query(&[("orgID", org_id)])
+        .send()
+        .await;
+    let response: DbrpResponse = map_json_response(result).await?;
+    Ok(response.content)
+}
+
+pub async fn create_dbrp(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    dbrp: &Dbrp
+) -> Result<Dbrp, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "dbrps");
+    let result = get_api_request(influxdb_token.into(), Method::POST, url)
+        .json(dbrp)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn update_dbrp(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str,
+    dbrp_id: &str,
+    update: &DbrpUpdate
+) -> Result<Dbrp, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("dbrps/{}", dbrp_id));
+    let result = get_api_request(influxdb_token.into(), Method::PATCH, url)
+        .query(&[("orgID", org_id)])
+        .json(update)
+        .send()
+        .await;
+    let response: DbrpResponse = map_json_response(result).await?;
+    Ok(response.content)
+}
+
+pub async fn set_default_dbrp(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str,
+    dbrp_id: &str
+) -> Result<Dbrp, InfluxDbError<Option<Error>>> {
+    let update = DbrpUpdate {
+        retention_policy: None,
+        default: Some(true),
+    };
+    update_dbrp(influxdb_token, influxdb_config, org_id, dbrp_id, &update).await
+}
+
+pub async fn delete_dbrp(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str,
+    dbrp_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("dbrps/{}", dbrp_id));
+    let result = get_api_request(influxdb_token.into(), Method::DELETE, url)
+        .query(&[("orgID", org_id)])
+        .send()
+        .await;
+    map_response(result).await.map(|_| ())
+}
+
+pub async fn create_bucket_with_dbrp(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    bucket: &Bucket,
+    database: &str,
+    retention_policy: &str
+) -> Result<(Bucket, Dbrp), InfluxDbError<Option<Error>>> {
+    let influxdb_token = influxdb_token.into();
+    let bucket = create_bucket(&influxdb_token, influxdb_config, bucket).await?;
+    let bucket_id = bucket
+        .id
+        .clone()
+        .ok_or_else(|| InfluxDbError::Failed(None, "created bucket has no id".to_string()))?;
+    let dbrp = Dbrp::new(&bucket.org_id, &bucket_id, database, retention_policy).as_default();
+    match create_dbrp(&influxdb_token, influxdb_config, &dbrp).await {
+        Ok(dbrp) => Ok((bucket, dbrp)),
+        Err(error) => {
+            if let Err(cleanup) = delete_bucket(influxdb_token, influxdb_config, &bucket_id).await {
+                warn!("Cannot delete bucket {} after failed dbrp creation: {}", bucket_id, cleanup);
+            }
+            Err(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::http_server::{deleted_buckets, setup_test_harness};
+    use crate::test_support::test_config::test_config;
+
+    fn dbrp() -> Dbrp {
+        Dbrp {
+            id: Some("dbrp-id".to_string()),
+            ..Dbrp::new("org-id", "bucket-id", "telegraf", "autogen").as_default()
+        }
+    }
+
+    #[actix_rt::test]
+    async fn list_and_get_dbrps_success() {
+        let harness = setup_test_harness();
+        let influxdb_config = test_config(harness.url("success"));
+        let result = list_dbrps("token".to_string(), &influxdb_config, "org-id", &DbrpFilter::default()).await.unwrap();
+        assert_eq!(vec![dbrp()], result);
+        let filter = DbrpFilter {
+            database: Some("other".to_string()),
+            ..DbrpFilter::default()
+        };
+        let result = list_dbrps("token".to_string(), &influxdb_config, "org-id", &filter).await.unwrap();
+        assert!(result.is_empty());
+        let result = get_dbrp("token".to_string(), &influxdb_config, "org-id", "dbrp-id").await.unwrap();
+        assert_eq!(dbrp(), result);
+    }
+
+    #[actix_rt::test]
+    async fn list_dbrps_error() {
+        let harness = setup_test_harness();
+        let result = list_dbrps("token".to_string(), &test_config(harness.url("success")), "", &DbrpFilter::default()).await;
+        assert!(result.is_err());
+        let result = list_dbrps("token".to_string(), &test_config(harness.url("fails")), "org-id", &DbrpFilter::default()).await;
+        assert!(result.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn create_update_and_delete_dbrp_success() {
+        let harness = setup_test_harness();
+        let influxdb_config = test_config(harness.url("success"));
+        let result = create_dbrp("token".to_string(), &influxdb_config, &Dbrp::new("org-id", "bucket-id", "telegraf", "weekly")).await.unwrap();
+        assert_eq!(Some("new-dbrp-id".to_string()), result.id);
+        assert!(!result.default);
+        let result = set_default_dbrp("token".to_string(), &influxdb_config, "org-id", "dbrp-id").await.unwrap();
+        assert!(result.default);
+        let update = DbrpUpdate {
+            retention_policy: Some("monthly".to_string()),
+            default: None,
+        };
+        let result = update_dbrp("token".to_string(), &influxdb_config, "org-id", "dbrp-id", &update).await.unwrap();
+        assert_eq!("monthly", result.retention_policy);
+        assert!(delete_dbrp("token".to_string(), &influxdb_config, "org-id", "dbrp-id").await.is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn create_bucket_with_dbrp_success() {
+        let harness = setup_test_harness();
+        let bucket = Bucket {
+            id: None,
+            org_id: "org-id".to_string(),
+            name: "telegraf".to_string(),
+            description: None,
+            retention_rules: vec![],
+        };
+        let (bucket, dbrp) = create_bucket_with_dbrp("token".to_string(), &test_config(harness.url("success")), &bucket, "telegraf", "autogen").await.unwrap();
+        assert_eq!(Some("new-bucket-id".to_string()), bucket.id);
+        assert_eq!("new-bucket-id", dbrp.bucket_id);
+        assert_eq!("autogen", dbrp.retention_policy);
+        assert!(dbrp.default);
+    }
+    #[actix_rt::test]
+    async fn create_bucket_with_dbrp_removes_bucket_on_failure() {
+        let harness = setup_test_harness();
+        let bucket = Bucket {
+            id: None,
+            org_id: "org-id".to_string(),
+            name: "conflict".to_string(),
+            description: None,
+            retention_rules: vec![],
+        };
+        let result = create_bucket_with_dbrp("token".to_string(), &test_config(harness.url("success")), &bucket, "conflict", "autogen").await;
+        assert_eq!(
+            r#"Rest call failed {"code":"conflict","message":"database and retention policy already mapped"}"#,
+            result.unwrap_err().to_string()
+        );
+        assert!(deleted_buckets().contains(&"new-bucket-id".to_string()));
+    }
+}
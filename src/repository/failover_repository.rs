@@ -0,0 +1,348 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use log::{debug, info, warn};
+use reqwest::{Client, Error};
+use crate::credentials::credential_provider::CredentialProvider;
+use crate::credentials::credential_request::with_credentials;
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::response_mapper::is_node_failure;
+use crate::model::failover_config::{FailoverConfig, FailoverStrategy};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::precision::Precision;
+use crate::model::secret::Secret;
+use crate::repository::influxdb_repository::{send_read, send_write};
+use crate::telemetry::client_stats::record_retry;
+
+pub struct FailoverPool {
+    strategy: FailoverStrategy,
+    health_check_interval: Duration,
+    nodes: Arc<Mutex<Vec<Node>>>,
+}
+
+struct Node {
+    address: String,
+    weight: i64,
+    current_weight: i64,
+    unhealthy_since: Option<Instant>,
+    last_probe: Option<Instant>,
+}
+
+impl FailoverPool {
+    pub fn new(config: FailoverConfig) -> Self {
+        FailoverPool {
+            strategy: config.strategy,
+            health_check_interval: Duration::from_millis(config.health_check_interval_ms),
+            nodes: Arc::new(Mutex::new(
+                config
+                    .addresses
+                    .into_iter()
+                    .map(|address| Node {
+                        address: address.address,
+                        weight: address.weight.max(1) as i64,
+                        current_weight: 0,
+                        unhealthy_since: None,
+                        last_probe: None,
+                    })
+                    .collect()
+            )),
+        }
+    }
+
+    pub async fn write_to_influxdb(
+        &self,
+        influxdb_token: impl Into<Secret>,
+        influxdb_config: &InfluxdbConfig,
+        body: String
+    ) -> Result<String, InfluxDbError<Option<Error>>> {
+        self.send(influxdb_token.into(), influxdb_config, body, false).await
+    }
+
+    pub async fn read_from_influxdb(
+        &self,
+        influxdb_token: impl Into<Secret>,
+        influxdb_config: &InfluxdbConfig,
+        body: String
+    ) -> Result<String, InfluxDbError<Option<Error>>> {
+        self.send(influxdb_token.into(), influxdb_config, body, true).await
+    }
+
+    pub async fn write_with_credentials(
+        &self,
+        credentials: &Arc<dyn CredentialProvider>,
+        influxdb_config: &InfluxdbConfig,
+        body: String
+    ) -> Result<String, InfluxDbError<Option<Error>>> {
+        with_credentials(credentials, influxdb_config, |influxdb_token| self.write_to_influxdb(influxdb_token, influxdb_config, body.clone())).await
+    }
+
+    pub async fn read_with_credentials(
+        &self,
+        credentials: &Arc<dyn CredentialProvider>,
+        influxdb_config: &InfluxdbConfig,
+        body: String
+    ) -> Result<String, InfluxDbError<Option<Error>>> {
+        with_credentials(credentials, influxdb_config, |influxdb_token| self.read_from_influxdb(influxdb_token, influxdb_config, body.clone())).await
+    }
+
+    pub fn healthy_addresses(&self) -> Vec<String> {
+        self.nodes
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|node| node.unhealthy_since.is_none())
+            .map(|node| node.address.clone())
+            .collect()
+    }
+
+    async fn send(
+        &self,
+        influxdb_token: Secret,
+        influxdb_config: &InfluxdbConfig,
+        body: String,
+        read: bool
+    ) -> Result<String, InfluxDbError<Option<Error>>> {
+        self.probe_unhealthy();
+        let mut last_error = None;
+        for (attempt, address) in self.candidates().into_iter().enumerate() {
+            if attempt > 0 {
+                record_retry(&address, attempt as u32);
+            }
+            let config = InfluxdbConfig {
+                address: address.clone(),
+                ..influxdb_config.clone()
+            };
+            let (result, error_kind) = if read {
+                send_read(influxdb_token.clone(), &config, body.clone()).await
+            } else {
+                let (result, _, error_kind) = send_write(influxdb_token.clone(), &config, Precision::Second, body.clone()).await;
+                (result, error_kind)
+            };
+            let error = match result {
+                Err(error) if is_node_failure(error_kind) => error,
+                result => return result,
+            };
+            warn!("InfluxDB node {} failed, failing over: {:?}", address, error);
+            self.mark_unhealthy(&address);
+            last_error = Some(error);
+        }
+        Err(last_error.unwrap_or_else(|| InfluxDbError::Failed(None, "no healthy InfluxDB addresses".to_string())))
+    }
+
+    fn candidates(&self) -> Vec<String> {
+        let mut nodes = self.nodes.lock().unwrap();
+        let mut healthy: Vec<&mut Node> = nodes.iter_mut().filter(|node| node.unhealthy_since.is_none()).collect();
+        if self.strategy == FailoverStrategy::Ordered || healthy.is_empty() {
+            return healthy.iter().map(|node| node.address.clone()).collect();
+        }
+        let total: i64 = healthy.iter().map(|node| node.weight).sum();
+        for node in healthy.iter_mut() {
+            node.current_weight += node.weight;
+        }
+        let selected = (0..healthy.len())
+            .rev()
+            .max_by_key(|index| healthy[*index].current_weight)
+            .unwrap_or(0);
+        healthy[selected].current_weight -= total;
+        let first = healthy.remove(selected).address.clone();
+        healthy.sort_by_key(|node| -node.weight);
+        std::iter::once(first).chain(healthy.iter().map(|node| node.address.clone())).collect()
+    }
+
+    fn probe_unhealthy(&self) {
+        let mut nodes = self.nodes.lock().unwrap();
+        let now = Instant::now();
+        let due = nodes
+            .iter_mut()
+            .filter(|node| node.unhealthy_since.is_some())
+            .filter(|node| node.last_probe.map(|probe| now.duration_since(probe) >= self.health_check_interval).unwrap_or(true));
+        for node in due {
+            node.last_probe = Some(now);
+            tokio::spawn(probe(self.nodes.clone(), node.address.clone()));
+        }
+    }
+
+    fn mark_unhealthy(&self, address: &str) {
+        let mut nodes = self.nodes.lock().unwrap();
+        if let Some(node) = nodes.iter_mut().find(|node| node.address == address) {
+            node.unhealthy_since.get_or_insert_with(Instant::now);
+            node.last_probe = Some(Instant::now());
+            node.current_weight = 0;
+        }
+    }
+}
+
+async fn probe(nodes: Arc<Mutex<Vec<Node>>>, address: String) {
+    let healthy = Client::new()
+        .get(format!("{}/health", address))
+        .timeout(Duration::from_secs(2))
+        .send()
+        .await
+        .map(|response| response.status().is_success())
+        .unwrap_or(false);
+    debug!("Health check for {} returned {}", address, healthy);
+    if !healthy {
+        return;
+    }
+    let mut nodes = nodes.lock().unwrap();
+    if let Some(node) = nodes.iter_mut().find(|node| node.address == address) {
+        if let Some(since) = node.unhealthy_since.take() {
+            info!("InfluxDB node {} healthy again after {:?}", address, since.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::static_credentials::StaticCredentials;
+    use crate::repository::influxdb_repository::write_to_influxdb;
+    use crate::telemetry::client_stats::address_stats;
+    use crate::test_support::fake_influxdb::{FakeFailure, FakeInfluxDb};
+    use crate::test_support::test_config::test_config;
+
+    const DOWN: &str = "http://127.0.0.1:1";
+
+    fn config(fake: &FakeInfluxDb) -> InfluxdbConfig {
+        fake.config("organisation", "bucket")
+    }
+
+    #[actix_rt::test]
+    async fn fails_over_on_connection_error() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let pool = FailoverPool::new(FailoverConfig::ordered(&[DOWN, &fake.address()]));
+        let result = pool.write_to_influxdb("token".to_string(), &config(&fake), "cpu usage=1 1".to_string()).await;
+        assert!(result.is_ok());
+        assert_eq!(1, fake.point_count("bucket"));
+        assert_eq!(vec![fake.address()], pool.healthy_addresses());
+    }
+
+    #[actix_rt::test]
+    async fn fails_over_on_server_error() {
+        let primary = FakeInfluxDb::start(&["token"]);
+        let secondary = FakeInfluxDb::start(&["token"]);
+        primary.inject_failure(FakeFailure::ServiceUnavailable);
+        let pool = FailoverPool::new(FailoverConfig::ordered(&[&primary.address(), &secondary.address()]));
+        let before = address_stats(&secondary.address()).unwrap_or_default();
+        let result = pool.write_to_influxdb("token".to_string(), &config(&primary), "cpu usage=1 1".to_string()).await;
+        assert!(result.is_ok());
+        assert_eq!(0, primary.point_count("bucket"));
+        assert_eq!(1, secondary.point_count("bucket"));
+        assert_eq!(1, address_stats(&secondary.address()).unwrap().retries - before.retries);
+        assert_eq!(vec![secondary.address()], pool.healthy_addresses());
+    }
+
+    #[actix_rt::test]
+    async fn does_not_fail_over_on_client_error() {
+        let primary = FakeInfluxDb::start(&["token"]);
+        let secondary = FakeInfluxDb::start(&["token"]);
+        let pool = FailoverPool::new(FailoverConfig::ordered(&[&primary.address(), &secondary.address()]));
+        let result = pool.write_to_influxdb("wrong".to_string(), &config(&primary), "cpu usage=1 1".to_string()).await;
+        assert!(result.is_err());
+        assert_eq!(1, primary.request_count());
+        assert_eq!(0, secondary.request_count());
+        assert_eq!(2, pool.healthy_addresses().len());
+    }
+
+    #[actix_rt::test]
+    async fn reads_fail_over() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        write_to_influxdb("token".to_string(), &config(&fake), "cpu,host=a usage=1 1".to_string()).await.unwrap();
+        let pool = FailoverPool::new(FailoverConfig::ordered(&[DOWN, &fake.address()]));
+        let result = pool.read_from_influxdb(
+            "token".to_string(),
+            &config(&fake),
+            r#"from(bucket: "bucket") |> range(start: 0)"#.to_string()
+        ).await;
+        assert!(result.unwrap().contains("usage"));
+    }
+
+    #[actix_rt::test]
+    async fn returns_probed_nodes_to_rotation() {
+        let primary = FakeInfluxDb::start(&["token"]);
+        let secondary = FakeInfluxDb::start(&["token"]);
+        primary.inject_failure(FakeFailure::ServiceUnavailable);
+        let pool = FailoverPool::new(FailoverConfig {
+            health_check_interval_ms: 0,
+            ..FailoverConfig::ordered(&[&primary.address(), &secondary.address()])
+        });
+        pool.write_to_influxdb("token".to_string(), &config(&primary), "cpu usage=1 1".to_string()).await.unwrap();
+        assert_eq!(vec![secondary.address()], pool.healthy_addresses());
+        pool.write_to_influxdb("token".to_string(), &config(&primary), "cpu usage=2 2".to_string()).await.unwrap();
+        for _ in 0..200 {
+            if pool.healthy_addresses().len() == 2 {
+                break;
+            }
+            actix_rt::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(2, pool.healthy_addresses().len());
+        pool.write_to_influxdb("token".to_string(), &config(&primary), "cpu usage=3 3".to_string()).await.unwrap();
+        assert_eq!(vec!["cpu usage=3 3".to_string()], primary.lines("bucket"));
+        assert_eq!(2, secondary.point_count("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn keeps_unhealthy_nodes_out_until_probe_is_due() {
+        let primary = FakeInfluxDb::start(&["token"]);
+        let secondary = FakeInfluxDb::start(&["token"]);
+        primary.inject_failure(FakeFailure::ServiceUnavailable);
+        let pool = FailoverPool::new(FailoverConfig::ordered(&[&primary.address(), &secondary.address()]));
+        pool.write_to_influxdb("token".to_string(), &config(&primary), "cpu usage=1 1".to_string()).await.unwrap();
+        pool.write_to_influxdb("token".to_string(), &config(&primary), "cpu usage=2 2".to_string()).await.unwrap();
+        assert_eq!(1, primary.request_count());
+        assert_eq!(2, secondary.point_count("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn probes_without_blocking_requests() {
+        let unresponsive = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let unresponsive = format!("http://{}", unresponsive.local_addr().unwrap());
+        let secondary = FakeInfluxDb::start(&["token"]);
+        let pool = FailoverPool::new(FailoverConfig {
+            health_check_interval_ms: 0,
+            ..FailoverConfig::ordered(&[&unresponsive, &secondary.address()])
+        });
+        pool.mark_unhealthy(&unresponsive);
+        let started = Instant::now();
+        pool.write_to_influxdb("token".to_string(), &config(&secondary), "cpu usage=1 1".to_string()).await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(1, secondary.point_count("bucket"));
+        assert_eq!(vec![secondary.address()], pool.healthy_addresses());
+    }
+
+    #[actix_rt::test]
+    async fn writes_with_fresh_credentials() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let pool = FailoverPool::new(FailoverConfig::ordered(&[DOWN, &fake.address()]));
+        let credentials: Arc<dyn CredentialProvider> = Arc::new(StaticCredentials::new("token"));
+        pool.write_with_credentials(&credentials, &config(&fake), "cpu usage=1 1".to_string()).await.unwrap();
+        let result = pool.read_with_credentials(&credentials, &config(&fake), r#"from(bucket: "bucket") |> range(start: 0)"#.to_string()).await;
+        assert!(result.unwrap().contains("usage"));
+        let wrong: Arc<dyn CredentialProvider> = Arc::new(StaticCredentials::new("wrong"));
+        let result = pool.write_with_credentials(&wrong, &config(&fake), "cpu usage=2 2".to_string()).await;
+        assert!(result.unwrap_err().to_string().contains("unauthorized access"));
+        assert_eq!(vec![fake.address()], pool.healthy_addresses());
+    }
+
+    #[actix_rt::test]
+    async fn distributes_by_weight() {
+        let heavy = FakeInfluxDb::start(&["token"]);
+        let light = FakeInfluxDb::start(&["token"]);
+        let pool = FailoverPool::new(FailoverConfig::weighted(&[(&heavy.address(), 3), (&light.address(), 1)]));
+        for index in 0..8 {
+            pool.write_to_influxdb("token".to_string(), &config(&heavy), format!("cpu usage={} {}", index, index))
+                .await
+                .unwrap();
+        }
+        assert_eq!(6, heavy.point_count("bucket"));
+        assert_eq!(2, light.point_count("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn fails_when_no_address_is_healthy() {
+        let pool = FailoverPool::new(FailoverConfig::ordered(&[DOWN]));
+        let config = test_config(DOWN.to_string());
+        assert!(pool.write_to_influxdb("token".to_string(), &config, "cpu usage=1 1".to_string()).await.is_err());
+        let result = pool.write_to_influxdb("token".to_string(), &config, "cpu usage=1 1".to_string()).await;
+        assert_eq!("Rest call failed no healthy InfluxDB addresses", result.unwrap_err().to_string());
+    }
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+use reqwest::{Client, Error};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::response_mapper::map_response;
+use crate::model::influxdb_config::InfluxdbConfig;
+
+pub async fn ping(influxdb_config: &InfluxdbConfig) -> Result<(), InfluxDbError<Option<Error>>> {
+    let result = Client::new()
+        .get(format!("{}/ping", influxdb_config.address))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await;
+    map_response(result).await.map(|_| ())
+}
+
+pub async fn health(influxdb_config: &InfluxdbConfig) -> Result<String, InfluxDbError<Option<Error>>> {
+    let result = Client::new()
+        .get(format!("{}/health", influxdb_config.address))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await;
+    map_response(result).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    #[actix_rt::test]
+    async fn ping_success() {
+        let harness = setup_test_harness();
+        assert!(ping(&test_config(harness.url("success"))).await.is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn ping_failed_request() {
+        assert!(ping(&test_config("http://127.0.0.1:1".to_string())).await.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn health_success() {
+        let harness = setup_test_harness();
+        let result = health(&test_config(harness.url("success"))).await;
+        assert!(result.unwrap().contains("pass"));
+    }
+}
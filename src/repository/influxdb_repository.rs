@@ -1,45 +1,166 @@
+use std::sync::Arc;
+use std::time::Instant;
 use log::debug;
+use crate::credentials::credential_provider::CredentialProvider;
+use crate::credentials::credential_request::with_credentials;
 use crate::model::influxdb_config::InfluxdbConfig;
 use reqwest::Error;
-use crate::mapper::response_mapper::map_response;
+use crate::mapper::response_mapper::{map_response, outcome, to_error_kind, to_write_failure, WriteFailure};
 use crate::error::influxdb_error::InfluxDbError;
-use crate::mapper::url_mapper::{to_influxdb_read_url, to_influxdb_write_url};
+use crate::mapper::url_mapper::{to_influxdb_read_url, to_influxdb_write_url, to_influxdb_write_url_with_precision};
 use crate::mapper::request_mapper::get_request;
+use crate::model::client_stats::ErrorKind;
+use crate::model::precision::Precision;
+use crate::model::secret::Secret;
+use crate::repository::request_limiter::{acquire, RequestKind};
+use crate::telemetry::client_stats::{record_query, record_write, WriteSize};
 
 pub async fn write_to_influxdb(
-    influxdb_token: String,
+    influxdb_token: impl Into<Secret>,
     influxdb_config: &InfluxdbConfig,
     body: String
 ) -> Result<String, InfluxDbError<Option<Error>>> {
     let url = to_influxdb_write_url(influxdb_config);
-    debug!("Using token {:#?}", influxdb_token);
     debug!("Using body {:#?}", body);
-    let result = get_request(influxdb_token, url, body)
+    let _permit = acquire(influxdb_config, RequestKind::Write, body.len()).await;
+    let size = WriteSize::of(&body);
+    let started = Instant::now();
+    let result = get_request(influxdb_token.into(), url, body)
         .send()
         .await;
     debug!("Result {:#?}", result);
-    map_response(result).await
+    let error_kind = to_error_kind(&result);
+    let result = map_response(result).await;
+    record_write(&influxdb_config.address, size, started, outcome(error_kind, &result));
+    result
+}
+
+pub async fn write_with_credentials(
+    credentials: &Arc<dyn CredentialProvider>,
+    influxdb_config: &InfluxdbConfig,
+    body: String
+) -> Result<String, InfluxDbError<Option<Error>>> {
+    with_credentials(credentials, influxdb_config, |influxdb_token| write_to_influxdb(influxdb_token, influxdb_config, body.clone())).await
+}
+
+pub (crate) async fn send_write(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    precision: Precision,
+    body: String
+) -> (Result<String, InfluxDbError<Option<Error>>>, Option<WriteFailure>, Option<ErrorKind>) {
+    let url = to_influxdb_write_url_with_precision(influxdb_config, precision);
+    let _permit = acquire(influxdb_config, RequestKind::Write, body.len()).await;
+    let size = WriteSize::of(&body);
+    let started = Instant::now();
+    let result = get_request(influxdb_token.into(), url, body)
+        .send()
+        .await;
+    let failure = to_write_failure(&result);
+    let error_kind = to_error_kind(&result);
+    let result = map_response(result).await;
+    record_write(&influxdb_config.address, size, started, outcome(error_kind, &result));
+    (result, failure, error_kind)
 }
 
 pub async fn read_from_influxdb(
-    influxdb_token: String,
+    influxdb_token: impl Into<Secret>,
     influxdb_config: &InfluxdbConfig,
     body: String
 ) -> Result<String, InfluxDbError<Option<Error>>> {
+    send_read(influxdb_token, influxdb_config, body).await.0
+}
+
+pub (crate) async fn send_read(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    body: String
+) -> (Result<String, InfluxDbError<Option<Error>>>, Option<ErrorKind>) {
     debug!("Body: {}", body);
     let url = to_influxdb_read_url(influxdb_config);
-    let result = get_request(influxdb_token, url, body)
+    let _permit = acquire(influxdb_config, RequestKind::Query, body.len()).await;
+    let started = Instant::now();
+    let result = get_request(influxdb_token.into(), url, body)
         .header("Content-Type", "application/vnd.flux")
         .send()
         .await;
-    map_response(result).await
+    let error_kind = to_error_kind(&result);
+    let result = map_response(result).await;
+    let bytes = result.as_ref().map(String::len).unwrap_or_default();
+    record_query(&influxdb_config.address, bytes, started, outcome(error_kind, &result));
+    (result, error_kind)
+}
+
+#[cfg(feature = "arrow")]
+pub (crate) async fn stream_from_influxdb<E, F>(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    body: String,
+    mut consume: F
+) -> Result<(), E>
+where
+    E: From<InfluxDbError<Option<Error>>>,
+    F: FnMut(&[u8]) -> Result<(), E>,
+{
+    debug!("Body: {}", body);
+    let url = to_influxdb_read_url(influxdb_config);
+    let _permit = acquire(influxdb_config, RequestKind::Query, body.len()).await;
+    let started = Instant::now();
+    let result = get_request(influxdb_token.into(), url, body)
+        .header("Content-Type", "application/vnd.flux")
+        .send()
+        .await;
+    let error_kind = to_error_kind(&result);
+    let mut response = match result {
+        Ok(response) if response.status().is_success() => response,
+        result => {
+            let result = map_response(result).await;
+            record_query(&influxdb_config.address, 0, started, outcome(error_kind, &result));
+            return Err(result.err().unwrap_or_else(|| InfluxDbError::Failed(None, "request failed".to_string())).into());
+        }
+    };
+    let mut bytes = 0;
+    loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(error) => {
+                let error_kind = if error.is_timeout() { ErrorKind::Timeout } else { ErrorKind::Transport };
+                record_query(&influxdb_config.address, bytes, started, Some(error_kind));
+                return Err(InfluxDbError::Failed(Some(error), "request failed".to_string()).into());
+            }
+        };
+        bytes += chunk.len();
+        if let Err(error) = consume(&chunk) {
+            record_query(&influxdb_config.address, bytes, started, None);
+            return Err(error);
+        }
+    }
+    record_query(&influxdb_config.address, bytes, started, None);
+    Ok(())
+}
+
+pub async fn read_with_credentials(
+    credentials: &Arc<dyn CredentialProvider>,
+    influxdb_config: &InfluxdbConfig,
+    body: String
+) -> Result<String, InfluxDbError<Option<Error>>> {
+    with_credentials(credentials, influxdb_config, |influxdb_token| read_from_influxdb(influxdb_token, influxdb_config, body.clone())).await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::credentials::static_credentials::StaticCredentials;
+    use crate::test_support::fake_influxdb::FakeInfluxDb;
+    use crate::test_support::fixture_server::FixtureServer;
     use crate::test_support::http_server::setup_test_harness;
 
+    fn replay(fixture: &str) -> FixtureServer {
+        FixtureServer::replay(format!("{}/fixtures/{}", env!("CARGO_MANIFEST_DIR"), fixture))
+            .expect("Cannot load fixture")
+    }
+
     #[actix_rt::test]
     async fn read_from_influxdb_error_no_body() {
         let harness = setup_test_harness();
@@ -51,6 +172,7 @@ mod tests {
                 organisation: "organisation".to_string(),
                 bucket: "bucket".to_string(),
                 influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
             },
             "body".to_string()
         ).await;
@@ -69,6 +191,7 @@ mod tests {
                 organisation: "organisation".to_string(),
                 bucket: "bucket".to_string(),
                 influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
             },
             "body".to_string()
         ).await;
@@ -87,6 +210,7 @@ mod tests {
                 organisation: "organisation".to_string(),
                 bucket: "bucket".to_string(),
                 influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
             },
             "body".to_string()
         ).await;
@@ -105,6 +229,7 @@ mod tests {
                 organisation: "organisation".to_string(),
                 bucket: "bucket".to_string(),
                 influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
             },
             "body".to_string()
         ).await;
@@ -123,6 +248,7 @@ mod tests {
                 organisation: "organisation".to_string(),
                 bucket: "bucket".to_string(),
                 influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
             },
             "body".to_string()
         ).await;
@@ -141,6 +267,7 @@ mod tests {
                 organisation: "organisation".to_string(),
                 bucket: "bucket".to_string(),
                 influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
             },
             "body".to_string()
         ).await;
@@ -159,6 +286,7 @@ mod tests {
                 organisation: "organisation".to_string(),
                 bucket: "bucket".to_string(),
                 influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
             },
             "body".to_string()
         ).await;
@@ -177,10 +305,107 @@ mod tests {
                 organisation: "organisation".to_string(),
                 bucket: "bucket".to_string(),
                 influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
             },
             "body".to_string()
         ).await;
         assert!(result.is_ok());
         assert_eq!("test", result.unwrap().to_string());
     }
-}
\ No newline at end of file
+
+    #[actix_rt::test]
+    async fn read_from_influxdb_multi_table_fixture() {
+        let fixture = replay("query_multi_table.json");
+        let result = read_from_influxdb(
+            "token".to_string(),
+            &InfluxdbConfig {
+                address: fixture.address(),
+                organisation: "organisation".to_string(),
+                bucket: "bucket".to_string(),
+                influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
+            },
+            r#"from(bucket: "bucket") |> range(start: 2024-05-01T00:00:00Z, stop: 2024-05-01T01:00:00Z) |> filter(fn: (r) => r._field == "usage_user" or r._field == "n_cpus")"#.to_string()
+        ).await;
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(2, result.matches(",result,table,_start,_stop,_time,_value,_field,_measurement,host").count());
+        assert!(result.contains(",,1,2024-05-01T00:00:00Z,2024-05-01T01:00:00Z,2024-05-01T00:10:00Z,40.125,usage_user,cpu,server02"));
+    }
+
+    #[actix_rt::test]
+    async fn read_from_influxdb_compilation_error_fixture() {
+        let fixture = replay("query_multi_table.json");
+        let result = read_from_influxdb(
+            "token".to_string(),
+            &InfluxdbConfig {
+                address: fixture.address(),
+                organisation: "organisation".to_string(),
+                bucket: "bucket".to_string(),
+                influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
+            },
+            r#"frm(bucket: "bucket")"#.to_string()
+        ).await;
+        assert!(result.is_err());
+        assert_eq!(
+            r#"Rest call failed {"code":"invalid","message":"error @1:1-1:4: undefined identifier frm"}"#,
+            result.unwrap_err().to_string()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn write_to_influxdb_invalid_line_fixture() {
+        let fixture = replay("write_errors.json");
+        let result = write_to_influxdb(
+            "token".to_string(),
+            &InfluxdbConfig {
+                address: fixture.address(),
+                organisation: "organisation".to_string(),
+                bucket: "bucket".to_string(),
+                influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
+            },
+            "cpu,host=server01 usage_user=12.5 1714522200\ncpu,host=server01 usage_user=".to_string()
+        ).await;
+        assert!(result.is_err());
+        assert_eq!(
+            r#"Rest call failed {"code":"invalid","message":"unable to parse 'cpu,host=server01 usage_user=': missing field value"}"#,
+            result.unwrap_err().to_string()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn write_to_influxdb_missing_bucket_fixture() {
+        let fixture = replay("write_errors.json");
+        let result = write_to_influxdb(
+            "token".to_string(),
+            &InfluxdbConfig {
+                address: fixture.address(),
+                organisation: "organisation".to_string(),
+                bucket: "missing".to_string(),
+                influxdb_token_path: "influxdb_token_path".to_string(),
+                limits: None,
+            },
+            "cpu,host=server01 usage_user=12.5 1714522200".to_string()
+        ).await;
+        assert!(result.is_err());
+        assert_eq!(
+            r#"Rest call failed {"code":"not found","message":"bucket \"missing\" not found"}"#,
+            result.unwrap_err().to_string()
+        );
+    }
+    #[actix_rt::test]
+    async fn write_and_read_with_credentials() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let credentials: Arc<dyn CredentialProvider> = Arc::new(StaticCredentials::new("token"));
+        let config = fake.config("organisation", "bucket");
+        write_with_credentials(&credentials, &config, "cpu usage=1 1".to_string()).await.unwrap();
+        let result = read_with_credentials(&credentials, &config, r#"from(bucket: "bucket") |> range(start: 0)"#.to_string()).await;
+        assert!(result.unwrap().contains("usage"));
+        let wrong: Arc<dyn CredentialProvider> = Arc::new(StaticCredentials::new("wrong"));
+        let result = write_with_credentials(&wrong, &config, "cpu usage=2 2".to_string()).await;
+        assert!(result.is_err());
+        assert_eq!(4, fake.request_count());
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_api_request;
+use crate::mapper::response_mapper::{map_json_response, map_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::label::{Label, LabelMapping, LabelResource, LabelResponse, LabelledResource, Labels};
+use crate::model::secret::Secret;
+
+const PAGE_SIZE: usize = 100;
+
+pub async fn list_labels(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig
+) -> Result<Vec<Label>, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "labels");
+    let result = get_api_request(influxdb_token.into(), Method::GET, url)
+        .send()
+        .await;
+    let labels: Labels = map_json_response(result).await?;
+    Ok(labels.labels)
+}
+
+pub async fn get_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    label_id: &str
+) -> Result<Label, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("labels/{}", label_id));
+    let result = get_api_request(influxdb_token.into(), Method::GET, url)
+        .send()
+        .await;
+    let response: LabelResponse = map_json_response(result).await?;
+    Ok(response.label)
+}
+
+pub async fn create_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    label: &Label
+) -> Result<Label, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "labels");
+    let result = get_api_request(influxdb_token.into(), Method::POST, url)
+        .json(label)
+        .send()
+        .await;
+    let response: LabelResponse = map_json_response(result).await?;
+    Ok(response.label)
+}
+
+pub async fn update_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    label_id: &str,
+    label: &Label
+) -> Result<Label, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("labels/{}", label_id));
+    let result = get_api_request(influxdb_token.into(), Method::PATCH, url)
+        .json(label)
+        .send()
+        .await;
+    let response: LabelResponse = map_json_response(result).await?;
+    Ok(response.label)
+}
+
+pub async fn delete_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    label_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("labels/{}", label_id));
+    let result = get_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send()
+        .await;
+    map_response(result).await.map(|_| ())
+}
+
+pub async fn list_resource_labels(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    resource: LabelResource,
+    resource_id: &str
+) -> Result<Vec<Label>, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("{}/{}/labels", resource.path(), resource_id));
+    let result = get_api_request(influxdb_token.into(), Method::GET, url)
+        .send()
+        .await;
+    let labels: Labels = map_json_response(result).await?;
+    Ok(labels.labels)
+}
+
+pub async fn attach_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    resource: LabelResource,
+    resource_id: &str,
+    label_id: &str
+) -> Result<Label, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("{}/{}/labels", resource.path(), resource_id));
+    let result = get_api_request(influxdb_token.into(), Method::POST, url)
+        .json(&LabelMapping { label_id: label_id.to_string() })
+        .send()
+        .await;
+    let response: LabelResponse = map_json_response(result).await?;
+    Ok(response.label)
+}
+
+pub async fn detach_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    resource: LabelResource,
+    resource_id: &str,
+    label_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("{}/{}/labels/{}", resource.path(), resource_id, label_id));
+    let result = get_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send()
+        .await;
+    map_response(result).await.map(|_| ())
+}
+
+pub async fn list_resources_by_label(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    resource: LabelResource,
+    label_id: &str
+) -> Result<Vec<LabelledResource>, InfluxDbError<Option<Error>>> {
+    let influxdb_token = influxdb_token.into();
+    let url = to_influxdb_api_url(influxdb_config, resource.path());
+    let mut labelled = vec![];
+    let mut offset = 0;
+    let mut after: Option<String> = None;
+    loop {
+        let request = get_api_request(influxdb_token.clone(), Method::GET, url.clone())
+            .query(&[("org", &influxdb_config.organisation)])
+            .query(&[("limit", PAGE_SIZE)]);
+        let request = match (resource, &after) {
+            (LabelResource::Tasks, Some(after)) => request.query(&[("after", after)]),
+            (LabelResource::Tasks, None) => request,
+            _ => request.query(&[("offset", offset)]),
+        };
+        let mut page: HashMap<String, serde_json::Value> = map_json_response(request.send().await).await?;
+        let page: Vec<LabelledResource> = page
+            .remove(resource.path())
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|error| InfluxDbError::Failed(None, format!("invalid response {}", error)))?
+            .unwrap_or_default();
+        let count = page.len();
+        offset += count;
+        after = page.last().map(|resource| resource.id.clone());
+        labelled.extend(
+            page.into_iter()
+                .filter(|resource| resource.labels.iter().any(|label| label.id.as_deref() == Some(label_id)))
+        );
+        if count < PAGE_SIZE {
+            return Ok(labelled);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::label::LabelProperties;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    fn label() -> Label {
+        Label {
+            id: Some("label-id".to_string()),
+            org_id: "org-id".to_string(),
+            name: "team:storage".to_string(),
+            properties: LabelProperties {
+                color: Some("#326BBA".to_string()),
+                description: Some("storage team".to_string()),
+            },
+        }
+    }
+
+    #[actix_rt::test]
+    async fn list_and_get_labels_success() {
+        let harness = setup_test_harness();
+        let result = list_labels("token".to_string(), &test_config(harness.url("success"))).await.unwrap();
+        assert_eq!(vec![label()], result);
+        let result = get_label("token".to_string(), &test_config(harness.url("success")), "label-id").await.unwrap();
+        assert_eq!(label(), result);
+    }
+
+    #[actix_rt::test]
+    async fn list_labels_error() {
+        let harness = setup_test_harness();
+        let result = list_labels("token".to_string(), &test_config(harness.url("fails"))).await;
+        assert!(result.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn create_update_and_delete_label_success() {
+        let harness = setup_test_harness();
+        let influxdb_config = test_config(harness.url("success"));
+        let result = create_label("token".to_string(), &influxdb_config, &Label::new("org-id", "env:prod")).await.unwrap();
+        assert_eq!(Some("new-label-id".to_string()), result.id);
+        let update = Label {
+            properties: LabelProperties {
+                color: Some("#FF0000".to_string()),
+                description: None,
+            },
+            ..result
+        };
+        let result = update_label("token".to_string(), &influxdb_config, "new-label-id", &update).await.unwrap();
+        assert_eq!(update, result);
+        assert!(delete_label("token".to_string(), &influxdb_config, "new-label-id").await.is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn attach_and_detach_labels_success() {
+        let harness = setup_test_harness();
+        let influxdb_config = test_config(harness.url("success"));
+        let result = attach_label("token".to_string(), &influxdb_config, LabelResource::Buckets, "bucket-id", "label-id").await.unwrap();
+        assert_eq!(label(), result);
+        let result = list_resource_labels("token".to_string(), &influxdb_config, LabelResource::Dashboards, "dashboard-id").await.unwrap();
+        assert_eq!(vec![label()], result);
+        let result = detach_label("token".to_string(), &influxdb_config, LabelResource::Tasks, "task-id", "label-id").await;
+        assert!(result.is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn list_resources_by_label_success() {
+        let harness = setup_test_harness();
+        let result = list_resources_by_label("token".to_string(), &test_config(harness.url("success")), LabelResource::Tasks, "label-id").await.unwrap();
+        assert_eq!(vec!["downsample", "cleanup"], result.iter().map(|resource| resource.name.as_str()).collect::<Vec<_>>());
+    }
+
+    #[actix_rt::test]
+    async fn list_resources_by_label_pages_by_offset() {
+        let harness = setup_test_harness();
+        let result = list_resources_by_label("token".to_string(), &test_config(harness.url("success")), LabelResource::Dashboards, "label-id").await.unwrap();
+        assert_eq!(vec!["dashboard-0", "dashboard-100"], result.iter().map(|resource| resource.id.as_str()).collect::<Vec<_>>());
+    }
+}
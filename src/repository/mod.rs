@@ -1 +1,17 @@
-pub mod influxdb_repository;
\ No newline at end of file
+#[cfg(feature = "arrow")]
+pub mod arrow_repository;
+pub mod authorization_repository;
+pub mod bucket_repository;
+pub mod check_repository;
+pub mod dbrp_repository;
+pub mod failover_repository;
+pub mod health_repository;
+pub mod influxdb_repository;
+pub mod influxdb_v3_repository;
+pub mod label_repository;
+pub mod notification_endpoint_repository;
+pub mod notification_rule_repository;
+pub mod organisation_repository;
+pub (crate) mod request_limiter;
+pub mod session_repository;
+pub mod user_repository;
\ No newline at end of file
@@ -0,0 +1,144 @@
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_api_request;
+use crate::mapper::response_mapper::{map_json_response, map_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::notification_endpoint::{NotificationEndpoint, NotificationEndpoints};
+use crate::model::secret::Secret;
+
+const PAGE_SIZE: usize = 100;
+
+pub async fn list_notification_endpoints(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str
+) -> Result<Vec<NotificationEndpoint>, InfluxDbError<Option<Error>>> {
+    let influxdb_token = influxdb_token.into();
+    let url = to_influxdb_api_url(influxdb_config, "notificationEndpoints");
+    let mut notification_endpoints = vec![];
+    loop {
+        let result = get_api_request(influxdb_token.clone(), Method::GET, url.clone())
+            .query(&[("orgID", org_id)])
+            .query(&[("limit", PAGE_SIZE), ("offset", notification_endpoints.len())])
+            .send()
+            .await;
+        let page: NotificationEndpoints = map_json_response(result).await?;
+        let count = page.notification_endpoints.len();
+        notification_endpoints.extend(page.notification_endpoints);
+        if count < PAGE_SIZE {
+            return Ok(notification_endpoints);
+        }
+    }
+}
+
+pub async fn get_notification_endpoint(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    endpoint_id: &str
+) -> Result<NotificationEndpoint, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("notificationEndpoints/{}", endpoint_id));
+    let result = get_api_request(influxdb_token.into(), Method::GET, url)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn create_notification_endpoint(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    notification_endpoint: &NotificationEndpoint
+) -> Result<NotificationEndpoint, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "notificationEndpoints");
+    let result = get_api_request(influxdb_token.into(), Method::POST, url)
+        .json(notification_endpoint)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn update_notification_endpoint(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    endpoint_id: &str,
+    notification_endpoint: &NotificationEndpoint
+) -> Result<NotificationEndpoint, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("notificationEndpoints/{}", endpoint_id));
+    let result = get_api_request(influxdb_token.into(), Method::PUT, url)
+        .json(notification_endpoint)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn delete_notification_endpoint(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    endpoint_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("notificationEndpoints/{}", endpoint_id));
+    let result = get_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send()
+        .await;
+    map_response(result).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::notification_endpoint::NotificationEndpointKind;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    fn endpoint() -> NotificationEndpoint {
+        NotificationEndpoint {
+            id: Some("endpoint-id".to_string()),
+            status: Some("active".to_string()),
+            ..NotificationEndpoint::slack("org-id", "alerts", "https://hooks.slack.com/services/x")
+        }
+    }
+
+    #[actix_rt::test]
+    async fn list_and_get_notification_endpoints_success() {
+        let harness = setup_test_harness();
+        let result = list_notification_endpoints("token".to_string(), &test_config(harness.url("success")), "org-id").await.unwrap();
+        assert_eq!(vec![endpoint()], result);
+        let result = get_notification_endpoint("token".to_string(), &test_config(harness.url("success")), "endpoint-id").await.unwrap();
+        assert_eq!(endpoint(), result);
+    }
+
+    #[actix_rt::test]
+    async fn list_notification_endpoints_paged() {
+        let harness = setup_test_harness();
+        let result = list_notification_endpoints("token".to_string(), &test_config(harness.url("paged")), "org-id").await.unwrap();
+        assert_eq!(101, result.len());
+        assert_eq!(endpoint(), result[100]);
+    }
+
+    #[actix_rt::test]
+    async fn list_notification_endpoints_error() {
+        let harness = setup_test_harness();
+        let result = list_notification_endpoints("token".to_string(), &test_config(harness.url("fails")), "org-id").await;
+        assert!(result.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn create_update_and_delete_notification_endpoint_success() {
+        let harness = setup_test_harness();
+        let influxdb_config = test_config(harness.url("success"));
+        let pager_duty = NotificationEndpoint::pager_duty("org-id", "on-call", "routing-key");
+        let result = create_notification_endpoint("token".to_string(), &influxdb_config, &pager_duty).await.unwrap();
+        assert_eq!(Some("new-id".to_string()), result.id);
+        assert_eq!(pager_duty.kind, result.kind);
+        let update = NotificationEndpoint {
+            kind: NotificationEndpointKind::PagerDuty {
+                client_url: Some("https://example.com/incidents".to_string()),
+                routing_key: "rotated-key".into(),
+            },
+            ..result
+        };
+        let result = update_notification_endpoint("token".to_string(), &influxdb_config, "new-id", &update).await.unwrap();
+        assert_eq!(update, result);
+        assert!(delete_notification_endpoint("token".to_string(), &influxdb_config, "new-id").await.is_ok());
+    }
+}
@@ -0,0 +1,155 @@
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_api_request;
+use crate::mapper::response_mapper::{map_json_response, map_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::notification_rule::{NotificationRule, NotificationRules};
+use crate::model::secret::Secret;
+
+const PAGE_SIZE: usize = 100;
+
+pub async fn list_notification_rules(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    org_id: &str
+) -> Result<Vec<NotificationRule>, InfluxDbError<Option<Error>>> {
+    let influxdb_token = influxdb_token.into();
+    let url = to_influxdb_api_url(influxdb_config, "notificationRules");
+    let mut notification_rules = vec![];
+    loop {
+        let result = get_api_request(influxdb_token.clone(), Method::GET, url.clone())
+            .query(&[("orgID", org_id)])
+            .query(&[("limit", PAGE_SIZE), ("offset", notification_rules.len())])
+            .send()
+            .await;
+        let page: NotificationRules = map_json_response(result).await?;
+        let count = page.notification_rules.len();
+        notification_rules.extend(page.notification_rules);
+        if count < PAGE_SIZE {
+            return Ok(notification_rules);
+        }
+    }
+}
+
+pub async fn get_notification_rule(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    rule_id: &str
+) -> Result<NotificationRule, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("notificationRules/{}", rule_id));
+    let result = get_api_request(influxdb_token.into(), Method::GET, url)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn create_notification_rule(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    notification_rule: &NotificationRule
+) -> Result<NotificationRule, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "notificationRules");
+    let result = get_api_request(influxdb_token.into(), Method::POST, url)
+        .json(notification_rule)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn update_notification_rule(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    rule_id: &str,
+    notification_rule: &NotificationRule
+) -> Result<NotificationRule, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("notificationRules/{}", rule_id));
+    let result = get_api_request(influxdb_token.into(), Method::PUT, url)
+        .json(notification_rule)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn delete_notification_rule(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    rule_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("notificationRules/{}", rule_id));
+    let result = get_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send()
+        .await;
+    map_response(result).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::check::CheckStatusLevel;
+    use crate::model::notification_rule::{NotificationRuleKind, StatusRule, TagRule, TagRuleOperator};
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    fn rule() -> NotificationRule {
+        NotificationRule {
+            id: Some("rule-id".to_string()),
+            ..NotificationRule::new(
+                "org-id",
+                "critical",
+                "endpoint-id",
+                "1m",
+                vec![StatusRule::changes(CheckStatusLevel::Ok, CheckStatusLevel::Crit)],
+                NotificationRuleKind::Slack {
+                    channel: Some("#alerts".to_string()),
+                    message_template: "${ r._message }".to_string(),
+                }
+            )
+        }
+    }
+
+    #[actix_rt::test]
+    async fn list_and_get_notification_rules_success() {
+        let harness = setup_test_harness();
+        let result = list_notification_rules("token".to_string(), &test_config(harness.url("success")), "org-id").await.unwrap();
+        assert_eq!(vec![rule()], result);
+        let result = get_notification_rule("token".to_string(), &test_config(harness.url("success")), "rule-id").await.unwrap();
+        assert_eq!(rule(), result);
+    }
+
+    #[actix_rt::test]
+    async fn list_notification_rules_paged() {
+        let harness = setup_test_harness();
+        let result = list_notification_rules("token".to_string(), &test_config(harness.url("paged")), "org-id").await.unwrap();
+        assert_eq!(101, result.len());
+        assert_eq!(rule(), result[100]);
+    }
+
+    #[actix_rt::test]
+    async fn list_notification_rules_error() {
+        let harness = setup_test_harness();
+        let result = list_notification_rules("token".to_string(), &test_config(harness.url("fails")), "org-id").await;
+        assert!(result.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn create_update_and_delete_notification_rule_success() {
+        let harness = setup_test_harness();
+        let influxdb_config = test_config(harness.url("success"));
+        let http = NotificationRule::new("org-id", "hook", "endpoint-id", "5m", vec![StatusRule::is(CheckStatusLevel::Warn)], NotificationRuleKind::Http {});
+        let result = create_notification_rule("token".to_string(), &influxdb_config, &http).await.unwrap();
+        assert_eq!(Some("new-id".to_string()), result.id);
+        assert_eq!(http.status_rules, result.status_rules);
+        let update = NotificationRule {
+            tag_rules: vec![TagRule {
+                key: "env".to_string(),
+                value: "prod".to_string(),
+                operator: TagRuleOperator::Equal,
+            }],
+            ..result
+        };
+        let result = update_notification_rule("token".to_string(), &influxdb_config, "new-id", &update).await.unwrap();
+        assert_eq!(update, result);
+        assert!(delete_notification_rule("token".to_string(), &influxdb_config, "new-id").await.is_ok());
+    }
+}
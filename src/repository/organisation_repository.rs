@@ -0,0 +1,58 @@
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::get_api_request;
+use crate::mapper::response_mapper::map_json_response;
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::organisation::Organisations;
+use crate::model::secret::Secret;
+
+pub async fn find_organisation_id(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig
+) -> Result<String, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "orgs");
+    let result = get_api_request(influxdb_token.into(), Method::GET, url)
+        .query(&[("org", &influxdb_config.organisation)])
+        .send()
+        .await;
+    let organisations: Organisations = map_json_response(result).await?;
+    organisations
+        .orgs
+        .into_iter()
+        .find(|organisation| organisation.name == influxdb_config.organisation)
+        .map(|organisation| organisation.id)
+        .ok_or_else(|| InfluxDbError::Failed(None, format!("organisation {} not found", influxdb_config.organisation)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    #[actix_rt::test]
+    async fn find_organisation_id_success() {
+        let harness = setup_test_harness();
+        let result = find_organisation_id("token".to_string(), &test_config(harness.url("success"))).await;
+        assert_eq!("org-id", result.unwrap());
+    }
+
+    #[actix_rt::test]
+    async fn find_organisation_id_encodes_org() {
+        let harness = setup_test_harness();
+        let config = InfluxdbConfig {
+            organisation: "acme & co".to_string(),
+            ..test_config(harness.url("success"))
+        };
+        let result = find_organisation_id("token".to_string(), &config).await;
+        assert_eq!("org-id", result.unwrap());
+    }
+
+    #[actix_rt::test]
+    async fn find_organisation_id_missing() {
+        let harness = setup_test_harness();
+        let result = find_organisation_id("token".to_string(), &test_config(harness.url("missing"))).await;
+        assert_eq!("Rest call failed organisation organisation not found", result.unwrap_err().to_string());
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+#[cfg(feature = "blocking")]
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "blocking")]
+use std::task::{Context, Poll, Wake, Waker};
+#[cfg(feature = "blocking")]
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::request_limits::RequestLimits;
+
+static LIMITERS: Mutex<Option<HashMap<String, Arc<RequestLimiter>>>> = Mutex::new(None);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub (crate) enum RequestKind {
+    Write,
+    Query,
+}
+
+pub (crate) struct RequestPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+struct RequestLimiter {
+    writes: Option<Arc<Semaphore>>,
+    queries: Option<Arc<Semaphore>>,
+    requests: Option<Mutex<TokenBucket>>,
+    bytes: Option<Mutex<TokenBucket>>,
+}
+
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+pub (crate) async fn acquire(influxdb_config: &InfluxdbConfig, kind: RequestKind, bytes: usize) -> RequestPermit {
+    let limiter = match limiter(influxdb_config) {
+        Some(limiter) => limiter,
+        None => return RequestPermit { _permit: None },
+    };
+    let permit = match limiter.semaphore(kind) {
+        Some(semaphore) => semaphore.acquire_owned().await.ok(),
+        None => None,
+    };
+    let delay = limiter.reserve(bytes, Instant::now());
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+    RequestPermit { _permit: permit }
+}
+
+#[cfg(feature = "blocking")]
+pub (crate) fn acquire_blocking(influxdb_config: &InfluxdbConfig, kind: RequestKind, bytes: usize) -> RequestPermit {
+    let limiter = match limiter(influxdb_config) {
+        Some(limiter) => limiter,
+        None => return RequestPermit { _permit: None },
+    };
+    let permit = limiter
+        .semaphore(kind)
+        .and_then(|semaphore| block_on(semaphore.acquire_owned()).ok());
+    let delay = limiter.reserve(bytes, Instant::now());
+    if !delay.is_zero() {
+        std::thread::sleep(delay);
+    }
+    RequestPermit { _permit: permit }
+}
+
+#[cfg(feature = "blocking")]
+struct ThreadWaker(Thread);
+
+#[cfg(feature = "blocking")]
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+#[cfg(feature = "blocking")]
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+fn limiter(influxdb_config: &InfluxdbConfig) -> Option<Arc<RequestLimiter>> {
+    let limits = influxdb_config.limits.as_ref()?;
+    let key = format!("{}|{:?}", influxdb_config.address, limits);
+    let mut limiters = LIMITERS.lock().unwrap();
+    let limiters = limiters.get_or_insert_with(HashMap::new);
+    if let Some(limiter) = limiters.get(&key) {
+        return Some(limiter.clone());
+    }
+    let now = Instant::now();
+    limiters.retain(|_, limiter| !limiter.is_idle(now));
+    let limiter = Arc::new(RequestLimiter::new(limits));
+    limiters.insert(key, limiter.clone());
+    Some(limiter)
+}
+
+impl RequestLimiter {
+    fn new(limits: &RequestLimits) -> Self {
+        let now = Instant::now();
+        RequestLimiter {
+            writes: limits.max_in_flight_writes.map(|permits| Arc::new(Semaphore::new(permits.max(1)))),
+            queries: limits.max_in_flight_queries.map(|permits| Arc::new(Semaphore::new(permits.max(1)))),
+            requests: limits
+                .requests_per_second
+                .filter(|rate| *rate > 0.0)
+                .map(|rate| Mutex::new(TokenBucket::new(rate, limits.request_burst, now))),
+            bytes: limits
+                .bytes_per_second
+                .filter(|rate| *rate > 0.0)
+                .map(|rate| Mutex::new(TokenBucket::new(rate, limits.byte_burst, now))),
+        }
+    }
+
+    fn semaphore(&self, kind: RequestKind) -> Option<Arc<Semaphore>> {
+        match kind {
+            RequestKind::Write => self.writes.clone(),
+            RequestKind::Query => self.queries.clone(),
+        }
+    }
+
+    fn is_idle(self: &Arc<Self>, now: Instant) -> bool {
+        let released = |semaphore: &Option<Arc<Semaphore>>| semaphore.as_ref().map(|semaphore| Arc::strong_count(semaphore) == 1).unwrap_or(true);
+        let refilled = |bucket: &Option<Mutex<TokenBucket>>| bucket.as_ref().map(|bucket| bucket.lock().unwrap().is_full(now)).unwrap_or(true);
+        Arc::strong_count(self) == 1
+            && released(&self.writes)
+            && released(&self.queries)
+            && refilled(&self.requests)
+            && refilled(&self.bytes)
+    }
+
+    fn reserve(&self, bytes: usize, now: Instant) -> Duration {
+        let requests = self
+            .requests
+            .as_ref()
+            .map(|bucket| bucket.lock().unwrap().reserve(1.0, now))
+            .unwrap_or_default();
+        let bytes = self
+            .bytes
+            .as_ref()
+            .map(|bucket| bucket.lock().unwrap().reserve(bytes as f64, now))
+            .unwrap_or_default();
+        requests.max(bytes)
+    }
+}
+
+impl TokenBucket {
+    fn new(rate: f64, burst: Option<f64>, now: Instant) -> Self {
+        let capacity = burst.unwrap_or(rate).max(1.0);
+        TokenBucket {
+            rate,
+            capacity,
+            tokens: capacity,
+            updated: now,
+        }
+    }
+
+    fn is_full(&self, now: Instant) -> bool {
+        self.tokens + now.saturating_duration_since(self.updated).as_secs_f64() * self.rate >= self.capacity
+    }
+
+    fn reserve(&mut self, amount: f64, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.updated = now;
+        self.tokens -= amount;
+        match self.tokens >= 0.0 {
+            true => Duration::ZERO,
+            false => Duration::from_secs_f64(-self.tokens / self.rate),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::influxdb_repository::write_to_influxdb;
+    use crate::test_support::fake_influxdb::{FakeFailure, FakeInfluxDb};
+
+    fn limited(fake: &FakeInfluxDb, limits: RequestLimits) -> InfluxdbConfig {
+        InfluxdbConfig {
+            limits: Some(limits),
+            ..fake.config("organisation", "bucket")
+        }
+    }
+
+    #[test]
+    fn token_bucket_waits_when_empty() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(10.0, Some(2.0), now);
+        assert_eq!(Duration::ZERO, bucket.reserve(1.0, now));
+        assert_eq!(Duration::ZERO, bucket.reserve(1.0, now));
+        assert_eq!(Duration::from_millis(100), bucket.reserve(1.0, now));
+        assert_eq!(Duration::ZERO, bucket.reserve(1.0, now + Duration::from_millis(300)));
+    }
+
+    #[test]
+    fn token_bucket_allows_oversized_reservations() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(100.0, None, now);
+        assert_eq!(Duration::from_secs(1), bucket.reserve(200.0, now));
+        assert_eq!(Duration::from_millis(1500), bucket.reserve(50.0, now));
+    }
+
+    #[test]
+    fn token_bucket_refills() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(10.0, Some(2.0), now);
+        bucket.reserve(2.0, now);
+        assert!(!bucket.is_full(now + Duration::from_millis(100)));
+        assert!(bucket.is_full(now + Duration::from_millis(200)));
+    }
+
+    #[test]
+    fn evicts_idle_limiters() {
+        let config = |address: &str| InfluxdbConfig {
+            limits: Some(RequestLimits {
+                max_in_flight_writes: Some(1),
+                requests_per_second: Some(1000.0),
+                ..RequestLimits::default()
+            }),
+            ..crate::test_support::test_config::test_config(address.to_string())
+        };
+        let contains = |address: &str| {
+            LIMITERS
+                .lock()
+                .unwrap()
+                .iter()
+                .flat_map(HashMap::keys)
+                .any(|key| key.starts_with(&format!("{}|", address)))
+        };
+        let busy = limiter(&config("http://evict-busy")).unwrap();
+        let permit = busy.semaphore(RequestKind::Write).unwrap().try_acquire_owned().unwrap();
+        drop(busy);
+        drop(limiter(&config("http://evict-idle")));
+        std::thread::sleep(Duration::from_millis(10));
+        drop(limiter(&config("http://evict-other")));
+        assert!(contains("http://evict-busy"));
+        assert!(!contains("http://evict-idle"));
+        drop(permit);
+        drop(limiter(&config("http://evict-last")));
+        assert!(!contains("http://evict-busy"));
+    }
+
+    #[actix_rt::test]
+    async fn limits_in_flight_writes() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        fake.inject_failure(FakeFailure::SlowResponse(Duration::from_millis(200)));
+        fake.inject_failure(FakeFailure::SlowResponse(Duration::from_millis(200)));
+        let config = limited(&fake, RequestLimits {
+            max_in_flight_writes: Some(1),
+            ..RequestLimits::default()
+        });
+        let started = Instant::now();
+        let (first, second) = tokio::join!(
+            write_to_influxdb("token".to_string(), &config, "cpu usage=1 1".to_string()),
+            write_to_influxdb("token".to_string(), &config, "cpu usage=2 2".to_string())
+        );
+        assert!(first.is_ok() && second.is_ok());
+        assert!(started.elapsed() >= Duration::from_millis(400));
+        assert_eq!(2, fake.point_count("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn limits_request_rate() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let config = limited(&fake, RequestLimits {
+            requests_per_second: Some(10.0),
+            request_burst: Some(1.0),
+            ..RequestLimits::default()
+        });
+        let started = Instant::now();
+        for value in 0..3 {
+            write_to_influxdb("token".to_string(), &config, format!("cpu usage={} 1", value)).await.unwrap();
+        }
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert_eq!(3, fake.point_count("bucket"));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn acquire_blocking_waits_for_permit() {
+        let config = InfluxdbConfig {
+            limits: Some(RequestLimits {
+                max_in_flight_writes: Some(1),
+                ..RequestLimits::default()
+            }),
+            ..crate::test_support::test_config::test_config("http://acquire-blocking".to_string())
+        };
+        let permit = acquire_blocking(&config, RequestKind::Write, 0);
+        let started = Instant::now();
+        let waiting = {
+            let config = config.clone();
+            std::thread::spawn(move || {
+                let _permit = acquire_blocking(&config, RequestKind::Write, 0);
+                started.elapsed()
+            })
+        };
+        std::thread::sleep(Duration::from_millis(100));
+        drop(permit);
+        assert!(waiting.join().unwrap() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,281 @@
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+use log::{debug, warn};
+use reqwest::{Error, Method, Response, StatusCode};
+use tokio::runtime::{Builder, Handle};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::{get_basic_request, get_session_request};
+use crate::mapper::response_mapper::{map_response, outcome, to_error_kind, to_session_cookie};
+use crate::mapper::url_mapper::{to_influxdb_api_url, to_influxdb_read_url, to_influxdb_write_url};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+use crate::repository::request_limiter::{acquire, RequestKind};
+use crate::telemetry::client_stats::{record_query, record_write, WriteSize};
+
+pub struct InfluxDbSession {
+    influxdb_config: InfluxdbConfig,
+    username: String,
+    password: Secret,
+    session: Mutex<Option<Secret>>,
+    authenticating: tokio::sync::Mutex<()>,
+}
+
+impl InfluxDbSession {
+    pub async fn sign_in(
+        influxdb_config: &InfluxdbConfig,
+        username: &str,
+        password: impl Into<Secret>
+    ) -> Result<Self, InfluxDbError<Option<Error>>> {
+        let session = InfluxDbSession {
+            influxdb_config: influxdb_config.clone(),
+            username: username.to_string(),
+            password: password.into(),
+            session: Mutex::new(None),
+            authenticating: tokio::sync::Mutex::new(()),
+        };
+        session.authenticate().await?;
+        Ok(session)
+    }
+
+    pub async fn write_to_influxdb(&self, body: String) -> Result<String, InfluxDbError<Option<Error>>> {
+        let url = to_influxdb_write_url(&self.influxdb_config);
+        let _permit = acquire(&self.influxdb_config, RequestKind::Write, body.len()).await;
+        let size = WriteSize::of(&body);
+        let started = Instant::now();
+        let result = self.send(Method::POST, &url, Some(&body), None).await?;
+        let error_kind = to_error_kind(&result);
+        let result = map_response(result).await;
+        record_write(&self.influxdb_config.address, size, started, outcome(error_kind, &result));
+        result
+    }
+
+    pub async fn read_from_influxdb(&self, body: String) -> Result<String, InfluxDbError<Option<Error>>> {
+        let url = to_influxdb_read_url(&self.influxdb_config);
+        let _permit = acquire(&self.influxdb_config, RequestKind::Query, body.len()).await;
+        let started = Instant::now();
+        let result = self.send(Method::POST, &url, Some(&body), Some("application/vnd.flux")).await?;
+        let error_kind = to_error_kind(&result);
+        let result = map_response(result).await;
+        let bytes = result.as_ref().map(String::len).unwrap_or_default();
+        record_query(&self.influxdb_config.address, bytes, started, outcome(error_kind, &result));
+        result
+    }
+
+    pub async fn api_request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&serde_json::Value>
+    ) -> Result<String, InfluxDbError<Option<Error>>> {
+        let url = to_influxdb_api_url(&self.influxdb_config, path);
+        let body = body.map(serde_json::Value::to_string);
+        let result = self.send(method, &url, body.as_deref(), Some("application/json")).await?;
+        map_response(result).await
+    }
+
+    pub async fn sign_out(self) -> Result<(), InfluxDbError<Option<Error>>> {
+        let session = self.session.lock().unwrap().take();
+        match session {
+            Some(session) => send_sign_out(session, to_influxdb_api_url(&self.influxdb_config, "signout")).await,
+            None => Ok(()),
+        }
+    }
+
+    pub async fn close(self) -> Result<(), InfluxDbError<Option<Error>>> {
+        self.sign_out().await
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&str>,
+        content_type: Option<&str>
+    ) -> Result<Result<Response, Error>, InfluxDbError<Option<Error>>> {
+        let session = self.session.lock().unwrap().clone();
+        let session = match session {
+            Some(session) => session,
+            None => self.reauthenticate(None).await?,
+        };
+        let result = self.request(&session, method.clone(), url, body, content_type).send().await;
+        if !matches!(&result, Ok(response) if response.status() == StatusCode::UNAUTHORIZED) {
+            return Ok(result);
+        }
+        debug!("Session for {} expired, signing in again", self.username);
+        let session = self.reauthenticate(Some(&session)).await?;
+        Ok(self.request(&session, method, url, body, content_type).send().await)
+    }
+
+    fn request(
+        &self,
+        session: &Secret,
+        method: Method,
+        url: &str,
+        body: Option<&str>,
+        content_type: Option<&str>
+    ) -> reqwest::RequestBuilder {
+        let request = get_session_request(session, method, url.to_string());
+        match (body, content_type) {
+            (Some(body), Some(content_type)) => request.header("Content-Type", content_type).body(body.to_string()),
+            (Some(body), None) => request.body(body.to_string()),
+            (None, _) => request,
+        }
+    }
+
+    async fn reauthenticate(&self, expired: Option<&Secret>) -> Result<Secret, InfluxDbError<Option<Error>>> {
+        let _authenticating = self.authenticating.lock().await;
+        let current = self.session.lock().unwrap().clone();
+        match current {
+            Some(current) if Some(&current) != expired => Ok(current),
+            _ => self.authenticate().await,
+        }
+    }
+
+    async fn authenticate(&self) -> Result<Secret, InfluxDbError<Option<Error>>> {
+        let url = to_influxdb_api_url(&self.influxdb_config, "signin");
+        let result = get_basic_request(&self.username, &self.password, Method::POST, url)
+            .send()
+            .await;
+        let cookie = result.as_ref().ok().and_then(to_session_cookie);
+        map_response(result).await?;
+        let session = Secret::from(
+            cookie.ok_or_else(|| InfluxDbError::Failed(None, "signin response has no session cookie".to_string()))?
+        );
+        *self.session.lock().unwrap() = Some(session.clone());
+        Ok(session)
+    }
+}
+
+impl Drop for InfluxDbSession {
+    fn drop(&mut self) {
+        let session = match self.session.get_mut().unwrap().take() {
+            Some(session) => session,
+            None => return,
+        };
+        let url = to_influxdb_api_url(&self.influxdb_config, "signout");
+        match Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(sign_out_in_background(session, url));
+            }
+            Err(_) => {
+                thread::spawn(move || match Builder::new_current_thread().enable_all().build() {
+                    Ok(runtime) => runtime.block_on(sign_out_in_background(session, url)),
+                    Err(error) => warn!("Cannot sign out of InfluxDB session: {}", error),
+                });
+            }
+        }
+    }
+}
+
+async fn sign_out_in_background(session: Secret, url: String) {
+    if let Err(error) = send_sign_out(session, url).await {
+        warn!("Cannot sign out of InfluxDB session: {}", error);
+    }
+}
+
+async fn send_sign_out(session: Secret, url: String) -> Result<(), InfluxDbError<Option<Error>>> {
+    let result = get_session_request(&session, Method::POST, url)
+        .send()
+        .await;
+    map_response(result).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::fake_influxdb::FakeInfluxDb;
+
+    fn fake() -> FakeInfluxDb {
+        let fake = FakeInfluxDb::start(&["token"]);
+        fake.add_user("user", "password");
+        fake
+    }
+
+    #[actix_rt::test]
+    async fn writes_and_reads_with_session() {
+        let fake = fake();
+        let session = InfluxDbSession::sign_in(&fake.config("organisation", "bucket"), "user", "password").await.unwrap();
+        assert_eq!(1, fake.session_count());
+        session.write_to_influxdb("cpu usage=1 1".to_string()).await.unwrap();
+        let result = session.read_from_influxdb(r#"from(bucket: "bucket") |> range(start: 0)"#.to_string()).await.unwrap();
+        assert!(result.contains(",1970-01-01T00:00:01Z,1,usage,cpu\r\n"));
+    }
+
+    #[actix_rt::test]
+    async fn signs_in_again_when_session_expires() {
+        let fake = fake();
+        let session = InfluxDbSession::sign_in(&fake.config("organisation", "bucket"), "user", "password").await.unwrap();
+        fake.expire_sessions();
+        session.write_to_influxdb("cpu usage=1 1".to_string()).await.unwrap();
+        assert_eq!(1, fake.point_count("bucket"));
+        assert_eq!(1, fake.session_count());
+        assert_eq!(4, fake.request_count());
+    }
+
+    #[actix_rt::test]
+    async fn rejects_wrong_password() {
+        let fake = fake();
+        let result = InfluxDbSession::sign_in(&fake.config("organisation", "bucket"), "user", "wrong").await;
+        assert_eq!(
+            r#"Rest call failed {"code":"unauthorized","message":"Unauthorized"}"#,
+            result.err().unwrap().to_string()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn signs_in_once_for_concurrent_expired_requests() {
+        let fake = fake();
+        let session = InfluxDbSession::sign_in(&fake.config("organisation", "bucket"), "user", "password").await.unwrap();
+        fake.expire_sessions();
+        let (first, second) = tokio::join!(
+            session.write_to_influxdb("cpu usage=1 1".to_string()),
+            session.write_to_influxdb("cpu usage=2 2".to_string())
+        );
+        first.unwrap();
+        second.unwrap();
+        assert_eq!(2, fake.point_count("bucket"));
+        assert_eq!(1, fake.session_count());
+        assert_eq!(6, fake.request_count());
+    }
+
+    #[actix_rt::test]
+    async fn calls_management_api_with_session() {
+        let fake = fake();
+        let session = InfluxDbSession::sign_in(&fake.config("organisation", "bucket"), "user", "password").await.unwrap();
+        session.write_to_influxdb("cpu usage=1 1".to_string()).await.unwrap();
+        let result = session.api_request(Method::GET, "buckets", None).await.unwrap();
+        assert_eq!(r#"{"buckets":[{"name":"bucket"}]}"#, result);
+    }
+
+    #[actix_rt::test]
+    async fn signs_out_explicitly() {
+        let fake = fake();
+        let session = InfluxDbSession::sign_in(&fake.config("organisation", "bucket"), "user", "password").await.unwrap();
+        session.sign_out().await.unwrap();
+        assert_eq!(0, fake.session_count());
+    }
+
+    #[actix_rt::test]
+    async fn signs_out_on_close() {
+        let fake = fake();
+        let session = InfluxDbSession::sign_in(&fake.config("organisation", "bucket"), "user", "password").await.unwrap();
+        session.close().await.unwrap();
+        assert_eq!(0, fake.session_count());
+    }
+
+    #[actix_rt::test]
+    async fn signs_out_in_background_on_drop() {
+        let fake = fake();
+        let session = InfluxDbSession::sign_in(&fake.config("organisation", "bucket"), "user", "password").await.unwrap();
+        drop(session);
+        for _ in 0..100 {
+            if fake.session_count() == 0 {
+                break;
+            }
+            actix_rt::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(0, fake.session_count());
+        assert_eq!(2, fake.request_count());
+    }
+}
@@ -0,0 +1,221 @@
+use reqwest::{Error, Method};
+use crate::error::influxdb_error::InfluxDbError;
+use crate::mapper::request_mapper::{get_api_request, get_basic_request};
+use crate::mapper::response_mapper::{map_json_response, map_response};
+use crate::mapper::url_mapper::to_influxdb_api_url;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+use crate::model::user::{PasswordRequest, User, Users};
+
+const PAGE_SIZE: usize = 100;
+
+pub async fn list_users(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig
+) -> Result<Vec<User>, InfluxDbError<Option<Error>>> {
+    let influxdb_token = influxdb_token.into();
+    let url = to_influxdb_api_url(influxdb_config, "users");
+    let mut users = vec![];
+    loop {
+        let result = get_api_request(influxdb_token.clone(), Method::GET, url.clone())
+            .query(&[("limit", PAGE_SIZE), ("offset", users.len())])
+            .send()
+            .await;
+        let page: Users = map_json_response(result).await?;
+        let count = page.users.len();
+        users.extend(page.users);
+        if count < PAGE_SIZE {
+            return Ok(users);
+        }
+    }
+}
+
+pub async fn find_user(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    name: &str
+) -> Result<Option<User>, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "users");
+    let result = get_api_request(influxdb_token.into(), Method::GET, url)
+        .query(&[("name", name)])
+        .send()
+        .await;
+    let users: Users = map_json_response(result).await?;
+    Ok(users.users.into_iter().find(|user| user.name == name))
+}
+
+pub async fn get_user(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    user_id: &str
+) -> Result<User, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("users/{}", user_id));
+    let result = get_api_request(influxdb_token.into(), Method::GET, url)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn current_user(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig
+) -> Result<User, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "me");
+    let result = get_api_request(influxdb_token.into(), Method::GET, url)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn create_user(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    user: &User
+) -> Result<User, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "users");
+    let result = get_api_request(influxdb_token.into(), Method::POST, url)
+        .json(user)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn update_user(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    user_id: &str,
+    user: &User
+) -> Result<User, InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("users/{}", user_id));
+    let result = get_api_request(influxdb_token.into(), Method::PATCH, url)
+        .json(user)
+        .send()
+        .await;
+    map_json_response(result).await
+}
+
+pub async fn delete_user(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    user_id: &str
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("users/{}", user_id));
+    let result = get_api_request(influxdb_token.into(), Method::DELETE, url)
+        .send()
+        .await;
+    map_response(result).await.map(|_| ())
+}
+
+pub async fn set_password(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    user_id: &str,
+    password: impl Into<Secret>
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, &format!("users/{}/password", user_id));
+    let result = get_api_request(influxdb_token.into(), Method::POST, url)
+        .json(&PasswordRequest { password: password.into() })
+        .send()
+        .await;
+    map_response(result).await.map(|_| ())
+}
+
+pub async fn change_password(
+    influxdb_config: &InfluxdbConfig,
+    username: &str,
+    old_password: impl Into<Secret>,
+    new_password: impl Into<Secret>
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    let url = to_influxdb_api_url(influxdb_config, "me/password");
+    let result = get_basic_request(username, &old_password.into(), Method::PUT, url)
+        .json(&PasswordRequest { password: new_password.into() })
+        .send()
+        .await;
+    map_response(result).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::http_server::setup_test_harness;
+    use crate::test_support::test_config::test_config;
+
+    fn alice() -> User {
+        User {
+            id: Some("user-id".to_string()),
+            name: "alice".to_string(),
+            status: Some("active".to_string()),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn list_users_success() {
+        let harness = setup_test_harness();
+        let result = list_users("token".to_string(), &test_config(harness.url("success"))).await.unwrap();
+        assert_eq!(vec![alice()], result);
+    }
+
+    #[actix_rt::test]
+    async fn list_users_paged() {
+        let harness = setup_test_harness();
+        let result = list_users("token".to_string(), &test_config(harness.url("paged"))).await.unwrap();
+        assert_eq!(101, result.len());
+        assert_eq!("user-0", result[0].name);
+        assert_eq!("user-100", result[100].name);
+    }
+
+    #[actix_rt::test]
+    async fn list_users_error() {
+        let harness = setup_test_harness();
+        let result = list_users("token".to_string(), &test_config(harness.url("fails"))).await;
+        assert!(result.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn find_user_by_name() {
+        let harness = setup_test_harness();
+        let result = find_user("token".to_string(), &test_config(harness.url("success")), "alice").await.unwrap();
+        assert_eq!(Some(alice()), result);
+        let result = find_user("token".to_string(), &test_config(harness.url("success")), "bob").await.unwrap();
+        assert_eq!(None, result);
+    }
+
+    #[actix_rt::test]
+    async fn get_and_current_user_success() {
+        let harness = setup_test_harness();
+        let result = get_user("token".to_string(), &test_config(harness.url("success")), "user-id").await.unwrap();
+        assert_eq!(alice(), result);
+        let result = current_user("token".to_string(), &test_config(harness.url("success"))).await.unwrap();
+        assert_eq!(alice(), result);
+    }
+
+    #[actix_rt::test]
+    async fn create_update_and_delete_user_success() {
+        let harness = setup_test_harness();
+        let influxdb_config = test_config(harness.url("success"));
+        let result = create_user("token".to_string(), &influxdb_config, &User::new("bob")).await.unwrap();
+        assert_eq!(Some("new-user-id".to_string()), result.id);
+        let user = User { status: Some("inactive".to_string()), ..result };
+        let result = update_user("token".to_string(), &influxdb_config, "new-user-id", &user).await.unwrap();
+        assert_eq!(user, result);
+        assert!(delete_user("token".to_string(), &influxdb_config, "new-user-id").await.is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn set_password_success() {
+        let harness = setup_test_harness();
+        let result = set_password("token".to_string(), &test_config(harness.url("success")), "user-id", "new-password").await;
+        assert!(result.is_ok());
+        let result = set_password("token".to_string(), &test_config(harness.url("success")), "user-id", "").await;
+        assert!(result.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn change_password_uses_current_credentials() {
+        let harness = setup_test_harness();
+        let result = change_password(&test_config(harness.url("success")), "alice", "old", "new-password").await;
+        assert!(result.is_ok());
+        let result = change_password(&test_config(harness.url("success")), "alice", "wrong", "new-password").await;
+        assert!(result.is_err());
+    }
+}
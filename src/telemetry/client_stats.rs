@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+use crate::model::client_stats::{ClientEvent, ClientStats, ErrorKind};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub (crate) struct WriteSize {
+    points: u64,
+    bytes: u64,
+}
+
+type StatsHook = Arc<dyn Fn(&ClientEvent) + Send + Sync>;
+
+static STATS: Mutex<Option<HashMap<String, ClientStats>>> = Mutex::new(None);
+static HOOK: RwLock<Option<StatsHook>> = RwLock::new(None);
+
+pub fn stats() -> ClientStats {
+    let stats = STATS.lock().unwrap();
+    let mut result = ClientStats::default();
+    for address_stats in stats.iter().flat_map(HashMap::values) {
+        result.merge(address_stats);
+    }
+    result
+}
+
+pub fn address_stats(address: &str) -> Option<ClientStats> {
+    STATS.lock().unwrap().as_ref()?.get(address).cloned()
+}
+
+pub fn reset_stats() {
+    *STATS.lock().unwrap() = None;
+}
+
+pub fn set_stats_hook(hook: impl Fn(&ClientEvent) + Send + Sync + 'static) {
+    *HOOK.write().unwrap() = Some(Arc::new(hook));
+}
+
+pub fn clear_stats_hook() {
+    *HOOK.write().unwrap() = None;
+}
+
+pub (crate) fn record_write(address: &str, size: WriteSize, started: Instant, error: Option<ErrorKind>) {
+    record(ClientEvent::Write {
+        address: address.to_string(),
+        points: size.points,
+        bytes: size.bytes,
+        latency_ms: elapsed_ms(started),
+        error,
+    });
+}
+
+pub (crate) fn record_query(address: &str, bytes: usize, started: Instant, error: Option<ErrorKind>) {
+    record(ClientEvent::Query {
+        address: address.to_string(),
+        bytes: bytes as u64,
+        latency_ms: elapsed_ms(started),
+        error,
+    });
+}
+
+pub (crate) fn record_retry(address: &str, attempt: u32) {
+    record(ClientEvent::Retry {
+        address: address.to_string(),
+        attempt,
+    });
+}
+
+fn record(event: ClientEvent) {
+    {
+        let mut stats = STATS.lock().unwrap();
+        let address = match &event {
+            ClientEvent::Write { address, .. } | ClientEvent::Query { address, .. } | ClientEvent::Retry { address, .. } => address,
+        };
+        let address_stats = stats.get_or_insert_with(HashMap::new).entry(address.clone()).or_default();
+        apply(address_stats, &event);
+    }
+    let hook = HOOK.read().unwrap().clone();
+    if let Some(hook) = hook {
+        hook(&event);
+    }
+}
+
+fn apply(stats: &mut ClientStats, event: &ClientEvent) {
+    match event {
+        ClientEvent::Write { points, bytes, latency_ms, error, .. } => {
+            stats.write_latency.record(*latency_ms);
+            match error {
+                None => {
+                    stats.written_points += points;
+                    stats.written_bytes += bytes;
+                    stats.written_batches += 1;
+                }
+                Some(kind) => {
+                    stats.failed_points += points;
+                    *stats.write_failures.entry(*kind).or_default() += 1;
+                }
+            }
+        }
+        ClientEvent::Query { bytes, latency_ms, error, .. } => {
+            stats.queries += 1;
+            stats.query_bytes += bytes;
+            stats.query_latency.record(*latency_ms);
+            if let Some(kind) = error {
+                *stats.query_failures.entry(*kind).or_default() += 1;
+            }
+        }
+        ClientEvent::Retry { .. } => stats.retries += 1,
+    }
+}
+
+impl WriteSize {
+    pub (crate) fn of(body: &str) -> Self {
+        WriteSize {
+            points: body.lines().filter(|line| !line.trim().is_empty() && !line.starts_with('#')).count() as u64,
+            bytes: body.len() as u64,
+        }
+    }
+}
+
+fn elapsed_ms(started: Instant) -> f64 {
+    started.elapsed().as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use super::*;
+    use crate::repository::influxdb_repository::{read_from_influxdb, write_to_influxdb};
+    use crate::test_support::fake_influxdb::{FakeFailure, FakeInfluxDb};
+
+    #[actix_rt::test]
+    async fn records_writes_and_queries() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let config = fake.config("organisation", "bucket");
+        let before = address_stats(&fake.address()).unwrap_or_default();
+        write_to_influxdb("token".to_string(), &config, "cpu value=1 1\ncpu value=2 2".to_string()).await.unwrap();
+        let body = read_from_influxdb("token".to_string(), &config, r#"from(bucket: "bucket") |> range(start: 0)"#.to_string()).await.unwrap();
+        let result = address_stats(&fake.address()).unwrap();
+        assert_eq!(2, result.written_points - before.written_points);
+        assert_eq!(27, result.written_bytes - before.written_bytes);
+        assert_eq!(1, result.written_batches - before.written_batches);
+        assert_eq!(1, result.write_latency.count - before.write_latency.count);
+        assert_eq!(1, result.queries - before.queries);
+        assert_eq!(body.len() as u64, result.query_bytes - before.query_bytes);
+        assert_eq!(1, result.query_latency.count - before.query_latency.count);
+        assert!(stats().written_points >= 2);
+    }
+
+    #[actix_rt::test]
+    async fn records_failures_by_kind() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let config = fake.config("organisation", "bucket");
+        let before = address_stats(&fake.address()).unwrap_or_default();
+        fake.inject_failure(FakeFailure::ServiceUnavailable);
+        assert!(write_to_influxdb("token".to_string(), &config, "cpu value=1 1".to_string()).await.is_err());
+        assert!(write_to_influxdb("wrong".to_string(), &config, "cpu value=1 1".to_string()).await.is_err());
+        let result = address_stats(&fake.address()).unwrap();
+        let failures = |stats: &ClientStats, kind: ErrorKind| stats.write_failures.get(&kind).copied().unwrap_or_default();
+        assert_eq!(0, result.written_points - before.written_points);
+        assert_eq!(2, result.failed_points - before.failed_points);
+        assert_eq!(1, failures(&result, ErrorKind::ServerError) - failures(&before, ErrorKind::ServerError));
+        assert_eq!(1, failures(&result, ErrorKind::Unauthorized) - failures(&before, ErrorKind::Unauthorized));
+    }
+
+    #[actix_rt::test]
+    async fn calls_hook() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let address = fake.address();
+        let points = Arc::new(AtomicU64::new(0));
+        let counted = points.clone();
+        set_stats_hook(move |event| {
+            if let ClientEvent::Write { address: event_address, points, .. } = event {
+                if *event_address == address {
+                    counted.fetch_add(*points, Ordering::SeqCst);
+                }
+            }
+        });
+        write_to_influxdb("token".to_string(), &fake.config("organisation", "bucket"), "cpu value=1 1".to_string()).await.unwrap();
+        clear_stats_hook();
+        assert_eq!(1, points.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,179 @@
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+use crate::mapper::point_mapper::to_line_protocol;
+use crate::model::batch_writer_config::BatchWriterConfig;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::log_backend_config::LogBackendConfig;
+use crate::model::point::Point;
+use crate::model::precision::Precision;
+use crate::model::secret::Secret;
+use crate::telemetry::target_filter::{escape_line_breaks, is_ignored, now_nanos};
+use crate::writer::batch_writer::BatchWriter;
+
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct InfluxDbLogger {
+    measurement: String,
+    level: LevelFilter,
+    writer: Arc<BatchWriter>,
+    dropped: AtomicU64,
+}
+
+impl InfluxDbLogger {
+    pub fn new(influxdb_token: impl Into<Secret>, influxdb_config: InfluxdbConfig, config: LogBackendConfig) -> Self {
+        let writer = BatchWriter::start(
+            influxdb_token,
+            influxdb_config,
+            BatchWriterConfig {
+                precision: Precision::Nanosecond,
+                ..config.batch_config
+            }
+        );
+        InfluxDbLogger {
+            measurement: config.measurement,
+            level: LevelFilter::from_str(&config.level).unwrap_or(LevelFilter::Info),
+            writer: Arc::new(writer),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    pub fn init(self) -> Result<Arc<BatchWriter>, SetLoggerError> {
+        let writer = self.writer();
+        let level = self.level;
+        log::set_logger(Box::leak(Box::new(self)))?;
+        log::set_max_level(level);
+        Ok(writer)
+    }
+
+    pub fn writer(&self) -> Arc<BatchWriter> {
+        self.writer.clone()
+    }
+
+    pub fn dropped_records(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn to_point(&self, record: &Record) -> Point {
+        let mut point = Point::new(&self.measurement)
+            .tag("level", record.level().as_str())
+            .tag("target", record.target())
+            .field("message", escape_line_breaks(&record.args().to_string()))
+            .timestamp(now_nanos());
+        if let Some(module) = record.module_path() {
+            point = point.tag("module", module);
+        }
+        if let Some(file) = record.file() {
+            point = point.field("file", file);
+        }
+        if let Some(line) = record.line() {
+            point = point.field("line", line as i64);
+        }
+        point
+    }
+}
+
+impl Log for InfluxDbLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level && !is_ignored(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let written = to_line_protocol(&self.to_point(record)).map(|line| self.writer.write(&line));
+        if !matches!(written, Ok(Ok(_))) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn flush(&self) {
+        if self.writer.flush_blocking(FLUSH_TIMEOUT).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use log::Level;
+    use super::*;
+    use crate::mapper::line_protocol_parser::parse_line_protocol;
+    use crate::model::point::FieldValue;
+    use crate::test_support::fake_influxdb::FakeInfluxDb;
+
+    fn logger(fake: &FakeInfluxDb, level: &str) -> InfluxDbLogger {
+        InfluxDbLogger::new("token".to_string(), fake.config("organisation", "bucket"), LogBackendConfig {
+            level: level.to_string(),
+            batch_config: BatchWriterConfig {
+                flush_interval_ms: 60_000,
+                ..BatchWriterConfig::default()
+            },
+            ..LogBackendConfig::default()
+        })
+    }
+
+    fn log(logger: &InfluxDbLogger, level: Level, target: &str, message: &str) {
+        logger.log(
+            &Record::builder()
+                .level(level)
+                .target(target)
+                .module_path(Some("my_service::handler"))
+                .file(Some("src/handler.rs"))
+                .line(Some(12))
+                .args(format_args!("{}", message))
+                .build()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn writes_log_records() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let logger = logger(&fake, "info");
+        log(&logger, Level::Warn, "my_service", "disk \"almost\" full");
+        logger.writer().flush().await.unwrap();
+        let result = parse_line_protocol(&fake.lines("bucket").join("\n")).unwrap();
+        assert_eq!(1, result.len());
+        assert_eq!("logs", result[0].measurement);
+        assert_eq!(Some(&"WARN".to_string()), result[0].tags.get("level"));
+        assert_eq!(Some(&"my_service".to_string()), result[0].tags.get("target"));
+        assert_eq!(Some(&"my_service::handler".to_string()), result[0].tags.get("module"));
+        assert_eq!(Some(&FieldValue::String("disk \"almost\" full".to_string())), result[0].fields.get("message"));
+        assert_eq!(Some(&FieldValue::String("src/handler.rs".to_string())), result[0].fields.get("file"));
+        assert_eq!(Some(&FieldValue::Integer(12)), result[0].fields.get("line"));
+    }
+
+    #[actix_rt::test]
+    async fn escapes_multi_line_messages() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let logger = logger(&fake, "info");
+        log(&logger, Level::Error, "my_service", "request failed\nCaused by:\r\n    timeout");
+        log(&logger, Level::Info, "my_service", "unrelated");
+        logger.flush();
+        let lines = fake.lines("bucket");
+        assert_eq!(2, lines.len());
+        let result = parse_line_protocol(&lines.join("\n")).unwrap();
+        assert_eq!(
+            Some(&FieldValue::String(r"request failed\nCaused by:\n    timeout".to_string())),
+            result[0].fields.get("message")
+        );
+        assert_eq!(Some(&FieldValue::String("unrelated".to_string())), result[1].fields.get("message"));
+        assert_eq!(0, logger.dropped_records());
+    }
+
+    #[actix_rt::test]
+    async fn filters_levels_and_own_targets() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let logger = logger(&fake, "warn");
+        log(&logger, Level::Info, "my_service", "too verbose");
+        log(&logger, Level::Error, "influxdb_client::writer::batch_writer", "feedback");
+        log(&logger, Level::Error, "reqwest::connect", "feedback");
+        log(&logger, Level::Error, "my_service", "kept");
+        logger.writer().flush().await.unwrap();
+        assert_eq!(1, fake.point_count("bucket"));
+        assert!(fake.lines("bucket")[0].contains("kept"));
+    }
+}
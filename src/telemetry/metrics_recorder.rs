@@ -0,0 +1,397 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use log::warn;
+use metrics::{Counter, Gauge, GaugeFn, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use crate::mapper::point_mapper::to_line_protocol;
+use crate::model::batch_writer_config::BatchWriterConfig;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::metrics_exporter_config::MetricsExporterConfig;
+use crate::model::point::Point;
+use crate::model::precision::Precision;
+use crate::model::secret::Secret;
+use crate::telemetry::target_filter::now_nanos;
+use crate::writer::batch_writer::BatchWriter;
+
+pub struct InfluxDbRecorder {
+    registry: Arc<Registry>,
+    writer: Arc<BatchWriter>,
+    _stop: Sender<()>,
+}
+
+struct Registry {
+    global_tags: BTreeMap<String, String>,
+    quantiles: Vec<f64>,
+    histogram_buckets: Vec<f64>,
+    counters: Mutex<BTreeMap<Key, Arc<AtomicU64>>>,
+    gauges: Mutex<BTreeMap<Key, Arc<GaugeValue>>>,
+    histograms: Mutex<BTreeMap<Key, Arc<HistogramValue>>>,
+}
+
+struct GaugeValue(AtomicU64);
+
+// Each push reports the samples recorded since the previous push and then resets the summary.
+struct HistogramValue {
+    bucket_bounds: Vec<f64>,
+    summary: Mutex<HistogramSummary>,
+}
+
+struct HistogramSummary {
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+    bucket_counts: Vec<u64>,
+}
+
+impl InfluxDbRecorder {
+    pub fn new(influxdb_token: impl Into<Secret>, influxdb_config: InfluxdbConfig, config: MetricsExporterConfig) -> Self {
+        let writer = Arc::new(BatchWriter::start(
+            influxdb_token,
+            influxdb_config,
+            BatchWriterConfig {
+                precision: Precision::Nanosecond,
+                ..config.batch_config
+            }
+        ));
+        let registry = Arc::new(Registry {
+            global_tags: config.global_tags,
+            quantiles: config.quantiles,
+            histogram_buckets: sorted_buckets(config.histogram_buckets),
+            counters: Mutex::new(BTreeMap::new()),
+            gauges: Mutex::new(BTreeMap::new()),
+            histograms: Mutex::new(BTreeMap::new()),
+        });
+        let (stop, stopped) = mpsc::channel::<()>();
+        let interval = Duration::from_millis(config.push_interval_ms.max(1));
+        let push_registry = registry.clone();
+        let push_writer = writer.clone();
+        thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                push(&push_registry, &push_writer);
+            }
+        });
+        InfluxDbRecorder {
+            registry,
+            writer,
+            _stop: stop,
+        }
+    }
+
+    pub fn install(self) -> Result<Arc<BatchWriter>, InfluxDbRecorder> {
+        let writer = self.writer();
+        metrics::set_global_recorder(self).map_err(|error| error.into_inner())?;
+        Ok(writer)
+    }
+
+    pub fn writer(&self) -> Arc<BatchWriter> {
+        self.writer.clone()
+    }
+
+    pub fn push(&self) {
+        push(&self.registry, &self.writer);
+    }
+}
+
+impl Recorder for InfluxDbRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let mut counters = self.registry.counters.lock().unwrap();
+        Counter::from_arc(counters.entry(key.clone()).or_insert_with(|| Arc::new(AtomicU64::new(0))).clone())
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        let mut gauges = self.registry.gauges.lock().unwrap();
+        Gauge::from_arc(
+            gauges
+                .entry(key.clone())
+                .or_insert_with(|| Arc::new(GaugeValue(AtomicU64::new(0_f64.to_bits()))))
+                .clone()
+        )
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        let mut histograms = self.registry.histograms.lock().unwrap();
+        Histogram::from_arc(
+            histograms
+                .entry(key.clone())
+                .or_insert_with(|| Arc::new(HistogramValue::new(&self.registry.histogram_buckets)))
+                .clone()
+        )
+    }
+}
+
+impl GaugeValue {
+    fn update(&self, operation: impl Fn(f64) -> f64) {
+        let _ = self.0.fetch_update(Ordering::AcqRel, Ordering::Acquire, |bits| {
+            Some(operation(f64::from_bits(bits)).to_bits())
+        });
+    }
+}
+
+impl GaugeFn for GaugeValue {
+    fn increment(&self, value: f64) {
+        self.update(|current| current + value);
+    }
+
+    fn decrement(&self, value: f64) {
+        self.update(|current| current - value);
+    }
+
+    fn set(&self, value: f64) {
+        self.0.store(value.to_bits(), Ordering::Release);
+    }
+}
+
+impl HistogramValue {
+    fn new(bucket_bounds: &[f64]) -> Self {
+        HistogramValue {
+            bucket_bounds: bucket_bounds.to_vec(),
+            summary: Mutex::new(HistogramSummary::new(bucket_bounds.len())),
+        }
+    }
+
+    fn take(&self) -> HistogramSummary {
+        let mut summary = self.summary.lock().unwrap();
+        let empty = HistogramSummary::new(self.bucket_bounds.len());
+        std::mem::replace(&mut *summary, empty)
+    }
+}
+
+impl HistogramFn for HistogramValue {
+    fn record(&self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        let bucket = self
+            .bucket_bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(self.bucket_bounds.len());
+        let mut summary = self.summary.lock().unwrap();
+        summary.min = if summary.count == 0 { value } else { summary.min.min(value) };
+        summary.max = if summary.count == 0 { value } else { summary.max.max(value) };
+        summary.count += 1;
+        summary.sum += value;
+        if let Some(count) = summary.bucket_counts.get_mut(bucket) {
+            *count += 1;
+        }
+    }
+}
+
+impl HistogramSummary {
+    fn new(buckets: usize) -> Self {
+        HistogramSummary {
+            count: 0,
+            sum: 0.0,
+            min: 0.0,
+            max: 0.0,
+            bucket_counts: vec![0; buckets + 1],
+        }
+    }
+
+    fn quantile(&self, bucket_bounds: &[f64], quantile: f64) -> f64 {
+        let rank = ((quantile.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, count) in self.bucket_counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return bucket_bounds.get(bucket).copied().unwrap_or(self.max).clamp(self.min, self.max);
+            }
+        }
+        self.max
+    }
+}
+
+fn push(registry: &Registry, writer: &BatchWriter) {
+    let lines = snapshot(registry, now_nanos())
+        .iter()
+        .filter_map(|point| to_line_protocol(point).ok())
+        .collect::<Vec<String>>();
+    if lines.is_empty() {
+        return;
+    }
+    if let Err(error) = writer.write(&lines.join("\n")) {
+        warn!("Dropping {} metrics: {}", lines.len(), error);
+    }
+}
+
+fn snapshot(registry: &Registry, timestamp: i64) -> Vec<Point> {
+    let mut points = vec![];
+    for (key, counter) in registry.counters.lock().unwrap().iter() {
+        let value = counter.load(Ordering::Acquire);
+        points.push(point(registry, key, timestamp).field("value", value.min(i64::MAX as u64) as i64));
+    }
+    for (key, gauge) in registry.gauges.lock().unwrap().iter() {
+        points.push(point(registry, key, timestamp).field("value", f64::from_bits(gauge.0.load(Ordering::Acquire))));
+    }
+    for (key, histogram) in registry.histograms.lock().unwrap().iter() {
+        let summary = histogram.take();
+        if summary.count == 0 {
+            continue;
+        }
+        let mut point = point(registry, key, timestamp)
+            .field("count", summary.count.min(i64::MAX as u64) as i64)
+            .field("sum", summary.sum)
+            .field("min", summary.min)
+            .field("max", summary.max);
+        for quantile in &registry.quantiles {
+            point = point.field(&quantile_name(*quantile), summary.quantile(&histogram.bucket_bounds, *quantile));
+        }
+        points.push(point);
+    }
+    points
+}
+
+fn point(registry: &Registry, key: &Key, timestamp: i64) -> Point {
+    let mut point = Point::new(key.name()).timestamp(timestamp);
+    for (name, value) in &registry.global_tags {
+        point = point.tag(name, value);
+    }
+    for label in key.labels() {
+        point = point.tag(label.key(), label.value());
+    }
+    point
+}
+
+fn quantile_name(quantile: f64) -> String {
+    let percentile = format!("{:.3}", quantile * 100.0);
+    format!("p{}", percentile.trim_end_matches('0').trim_end_matches('.').replace('.', ""))
+}
+
+fn sorted_buckets(mut buckets: Vec<f64>) -> Vec<f64> {
+    buckets.retain(|bound| bound.is_finite());
+    buckets.sort_by(f64::total_cmp);
+    buckets.dedup();
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapper::line_protocol_parser::parse_line_protocol;
+    use crate::model::point::FieldValue;
+    use crate::test_support::fake_influxdb::FakeInfluxDb;
+
+    fn recorder(fake: &FakeInfluxDb) -> InfluxDbRecorder {
+        InfluxDbRecorder::new("token".to_string(), fake.config("organisation", "bucket"), MetricsExporterConfig {
+            push_interval_ms: 60_000,
+            global_tags: BTreeMap::from([("service".to_string(), "api".to_string())]),
+            histogram_buckets: (1..=10).map(|bound| bound as f64 * 10.0).collect(),
+            batch_config: BatchWriterConfig {
+                flush_interval_ms: 60_000,
+                ..BatchWriterConfig::default()
+            },
+            ..MetricsExporterConfig::default()
+        })
+    }
+
+    fn points(fake: &FakeInfluxDb) -> BTreeMap<String, Point> {
+        parse_line_protocol(&fake.lines("bucket").join("\n"))
+            .unwrap()
+            .into_iter()
+            .map(|point| (point.measurement.clone(), point))
+            .collect()
+    }
+
+    #[actix_rt::test]
+    async fn pushes_counters_and_gauges() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let recorder = recorder(&fake);
+        metrics::with_local_recorder(&recorder, || {
+            metrics::counter!("requests", "route" => "/users").increment(2);
+            metrics::counter!("requests", "route" => "/users").increment(3);
+            metrics::gauge!("connections").set(7.0);
+            metrics::gauge!("connections").decrement(2.0);
+        });
+        recorder.push();
+        recorder.writer().flush().await.unwrap();
+        let result = points(&fake);
+        assert_eq!(Some(&FieldValue::Integer(5)), result["requests"].fields.get("value"));
+        assert_eq!(Some(&"/users".to_string()), result["requests"].tags.get("route"));
+        assert_eq!(Some(&"api".to_string()), result["requests"].tags.get("service"));
+        assert_eq!(Some(&FieldValue::Float(5.0)), result["connections"].fields.get("value"));
+    }
+
+    #[actix_rt::test]
+    async fn pushes_histogram_summaries() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let recorder = recorder(&fake);
+        metrics::with_local_recorder(&recorder, || {
+            for value in 1..=100 {
+                metrics::histogram!("latency").record(value as f64);
+            }
+        });
+        recorder.push();
+        recorder.push();
+        recorder.writer().flush().await.unwrap();
+        assert_eq!(1, fake.point_count("bucket"));
+        let fields = &points(&fake)["latency"].fields;
+        assert_eq!(Some(&FieldValue::Integer(100)), fields.get("count"));
+        assert_eq!(Some(&FieldValue::Float(5050.0)), fields.get("sum"));
+        assert_eq!(Some(&FieldValue::Float(1.0)), fields.get("min"));
+        assert_eq!(Some(&FieldValue::Float(100.0)), fields.get("max"));
+        assert_eq!(Some(&FieldValue::Float(50.0)), fields.get("p50"));
+        assert_eq!(Some(&FieldValue::Float(90.0)), fields.get("p90"));
+        assert_eq!(Some(&FieldValue::Float(100.0)), fields.get("p99"));
+    }
+
+    #[test]
+    fn summarises_histograms_in_fixed_buckets() {
+        let histogram = HistogramValue::new(&[1.0, 10.0]);
+        for value in [0.5, 2.0, 3.0, 40.0, 60.0, f64::NAN] {
+            histogram.record(value);
+        }
+        let result = histogram.take();
+        assert_eq!(5, result.count);
+        assert_eq!(105.5, result.sum);
+        assert_eq!(0.5, result.min);
+        assert_eq!(60.0, result.max);
+        assert_eq!(vec![1, 2, 2], result.bucket_counts);
+        assert_eq!(1.0, result.quantile(&histogram.bucket_bounds, 0.0));
+        assert_eq!(10.0, result.quantile(&histogram.bucket_bounds, 0.5));
+        assert_eq!(60.0, result.quantile(&histogram.bucket_bounds, 0.99));
+        assert_eq!(0, histogram.take().count);
+    }
+
+    #[test]
+    fn sorts_histogram_buckets() {
+        assert_eq!(vec![1.0, 5.0], sorted_buckets(vec![5.0, f64::INFINITY, 1.0, 5.0, f64::NAN]));
+    }
+
+    #[actix_rt::test]
+    async fn pushes_on_interval() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let recorder = InfluxDbRecorder::new("token".to_string(), fake.config("organisation", "bucket"), MetricsExporterConfig {
+            push_interval_ms: 10,
+            batch_config: BatchWriterConfig {
+                flush_interval_ms: 10,
+                ..BatchWriterConfig::default()
+            },
+            ..MetricsExporterConfig::default()
+        });
+        metrics::with_local_recorder(&recorder, || metrics::counter!("jobs").increment(1));
+        for _ in 0..200 {
+            if fake.point_count("bucket") > 0 {
+                break;
+            }
+            actix_rt::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(fake.point_count("bucket") > 0);
+    }
+
+    #[test]
+    fn names_quantiles() {
+        assert_eq!("p50", quantile_name(0.5));
+        assert_eq!("p99", quantile_name(0.99));
+        assert_eq!("p999", quantile_name(0.999));
+    }
+}
@@ -0,0 +1,9 @@
+pub mod client_stats;
+#[cfg(feature = "log-backend")]
+pub mod log_backend;
+#[cfg(feature = "metrics")]
+pub mod metrics_recorder;
+pub mod request_metrics;
+mod target_filter;
+#[cfg(feature = "tracing")]
+pub mod tracing_layer;
\ No newline at end of file
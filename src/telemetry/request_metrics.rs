@@ -0,0 +1,214 @@
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::StatusCode;
+use actix_web::Error;
+use log::warn;
+use crate::mapper::point_mapper::to_line_protocol;
+use crate::model::batch_writer_config::BatchWriterConfig;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::point::Point;
+use crate::model::precision::Precision;
+use crate::model::request_metrics_config::RequestMetricsConfig;
+use crate::model::secret::Secret;
+use crate::telemetry::target_filter::now_nanos;
+use crate::writer::batch_writer::BatchWriter;
+
+#[derive(Clone)]
+pub struct RequestMetrics {
+    config: Arc<RequestMetricsConfig>,
+    writer: Arc<BatchWriter>,
+}
+
+pub struct RequestMetricsMiddleware<S> {
+    service: S,
+    config: Arc<RequestMetricsConfig>,
+    writer: Arc<BatchWriter>,
+}
+
+impl RequestMetrics {
+    pub fn new(influxdb_token: impl Into<Secret>, influxdb_config: InfluxdbConfig, config: RequestMetricsConfig) -> Self {
+        let writer = BatchWriter::start(
+            influxdb_token,
+            influxdb_config,
+            BatchWriterConfig {
+                precision: Precision::Nanosecond,
+                ..config.batch_config.clone()
+            }
+        );
+        RequestMetrics::with_writer(Arc::new(writer), config)
+    }
+
+    pub fn with_writer(writer: Arc<BatchWriter>, config: RequestMetricsConfig) -> Self {
+        RequestMetrics {
+            config: Arc::new(config),
+            writer,
+        }
+    }
+
+    pub fn writer(&self) -> Arc<BatchWriter> {
+        self.writer.clone()
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestMetricsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestMetricsMiddleware {
+            service,
+            config: self.config.clone(),
+            writer: self.writer.clone(),
+        }))
+    }
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        if self.config.is_excluded(request.path()) {
+            return Box::pin(self.service.call(request));
+        }
+        let started = Instant::now();
+        let timestamp = now_nanos();
+        let method = request.method().to_string();
+        let config = self.config.clone();
+        let writer = self.writer.clone();
+        let response = self.service.call(request);
+        Box::pin(async move {
+            let result = response.await;
+            let (route, status) = match &result {
+                Ok(response) => (
+                    response.request().match_pattern().unwrap_or_else(|| "unmatched".to_string()),
+                    response.status(),
+                ),
+                Err(error) => ("unmatched".to_string(), error.as_response_error().status_code()),
+            };
+            let point = to_point(&config, &method, &route, status, started, timestamp);
+            let written = to_line_protocol(&point)
+                .map_err(|error| error.to_string())
+                .and_then(|line| writer.write(&line).map_err(|error| error.to_string()));
+            if let Err(error) = written {
+                warn!("Dropping request metric: {}", error);
+            }
+            result
+        })
+    }
+}
+
+fn to_point(
+    config: &RequestMetricsConfig,
+    method: &str,
+    route: &str,
+    status: StatusCode,
+    started: Instant,
+    timestamp: i64
+) -> Point {
+    let mut point = Point::new(&config.measurement);
+    for (key, value) in &config.tags {
+        point = point.tag(key, value);
+    }
+    point
+        .tag("method", method)
+        .tag("route", route)
+        .tag("status_class", &format!("{}xx", status.as_u16() / 100))
+        .field("status", status.as_u16() as i64)
+        .field("latency_ms", started.elapsed().as_secs_f64() * 1000.0)
+        .timestamp(timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use actix_web::{test, web, App, HttpResponse, HttpServer};
+    use super::*;
+    use crate::mapper::line_protocol_parser::parse_line_protocol;
+    use crate::model::point::FieldValue;
+    use crate::test_support::fake_influxdb::FakeInfluxDb;
+
+    fn metrics(fake: &FakeInfluxDb) -> RequestMetrics {
+        RequestMetrics::new("token".to_string(), fake.config("organisation", "bucket"), RequestMetricsConfig {
+            excluded_paths: vec!["/health".to_string()],
+            tags: BTreeMap::from([("service".to_string(), "api".to_string())]),
+            batch_config: BatchWriterConfig {
+                flush_interval_ms: 60_000,
+                ..BatchWriterConfig::default()
+            },
+            ..RequestMetricsConfig::default()
+        })
+    }
+
+    #[actix_rt::test]
+    async fn records_requests() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let metrics = metrics(&fake);
+        let writer = metrics.writer();
+        let app = test::init_service(
+            App::new()
+                .wrap(metrics)
+                .route("/users/{id}", web::get().to(|| async { HttpResponse::Ok().finish() }))
+                .route("/health", web::get().to(|| async { HttpResponse::Ok().finish() }))
+        ).await;
+        test::call_service(&app, test::TestRequest::get().uri("/users/42").to_request()).await;
+        test::call_service(&app, test::TestRequest::get().uri("/health").to_request()).await;
+        test::call_service(&app, test::TestRequest::post().uri("/missing").to_request()).await;
+        writer.flush().await.unwrap();
+        let result = parse_line_protocol(&fake.lines("bucket").join("\n")).unwrap();
+        assert_eq!(2, result.len());
+        assert_eq!("http_requests", result[0].measurement);
+        assert_eq!(Some(&"GET".to_string()), result[0].tags.get("method"));
+        assert_eq!(Some(&"/users/{id}".to_string()), result[0].tags.get("route"));
+        assert_eq!(Some(&"2xx".to_string()), result[0].tags.get("status_class"));
+        assert_eq!(Some(&"api".to_string()), result[0].tags.get("service"));
+        assert_eq!(Some(&FieldValue::Integer(200)), result[0].fields.get("status"));
+        assert!(matches!(result[0].fields.get("latency_ms"), Some(FieldValue::Float(_))));
+        assert_eq!(Some(&"unmatched".to_string()), result[1].tags.get("route"));
+        assert_eq!(Some(&"4xx".to_string()), result[1].tags.get("status_class"));
+    }
+
+    #[actix_rt::test]
+    async fn records_requests_from_http_server() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let metrics = metrics(&fake);
+        let writer = metrics.writer();
+        let server = HttpServer::new(move || {
+            App::new()
+                .wrap(metrics.clone())
+                .route("/users/{id}", web::get().to(|| async { HttpResponse::Ok().finish() }))
+        })
+            .workers(1)
+            .bind(("127.0.0.1", 0))
+            .unwrap();
+        let address = server.addrs()[0];
+        let server = server.run();
+        let handle = server.handle();
+        actix_rt::spawn(server);
+        reqwest::get(format!("http://{}/users/42", address)).await.unwrap();
+        handle.stop(true).await;
+        writer.flush().await.unwrap();
+        let result = parse_line_protocol(&fake.lines("bucket").join("\n")).unwrap();
+        assert_eq!(1, result.len());
+        assert_eq!(Some(&"/users/{id}".to_string()), result[0].tags.get("route"));
+    }
+}
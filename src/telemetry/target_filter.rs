@@ -0,0 +1,41 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(any(feature = "tracing", feature = "log-backend"))]
+const IGNORED_TARGETS: [&str; 5] = ["influxdb_client", "reqwest", "hyper", "h2", "tokio"];
+
+#[cfg(any(feature = "tracing", feature = "log-backend"))]
+pub (crate) fn is_ignored(target: &str) -> bool {
+    IGNORED_TARGETS
+        .iter()
+        .any(|ignored| target == *ignored || target.strip_prefix(ignored).map(|rest| rest.starts_with("::")).unwrap_or(false))
+}
+
+#[cfg(any(feature = "tracing", feature = "log-backend"))]
+pub (crate) fn escape_line_breaks(message: &str) -> String {
+    message.replace("\r\n", "\\n").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+pub (crate) fn now_nanos() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as i64)
+        .unwrap_or(0)
+}
+
+#[cfg(all(test, any(feature = "tracing", feature = "log-backend")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_own_targets() {
+        assert!(is_ignored("influxdb_client::writer::batch_writer"));
+        assert!(is_ignored("hyper"));
+        assert!(!is_ignored("hyperion"));
+        assert!(!is_ignored("my_service"));
+    }
+
+    #[test]
+    fn escapes_line_breaks() {
+        assert_eq!("first\\nsecond\\nthird\\rfourth", escape_line_breaks("first\r\nsecond\nthird\rfourth"));
+    }
+}
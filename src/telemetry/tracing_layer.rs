@@ -0,0 +1,315 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+use crate::mapper::point_mapper::to_line_protocol;
+use crate::model::batch_writer_config::BatchWriterConfig;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::point::{FieldValue, Point};
+use crate::model::precision::Precision;
+use crate::model::secret::Secret;
+use crate::model::tracing_layer_config::TracingLayerConfig;
+use crate::telemetry::target_filter::{escape_line_breaks, is_ignored, now_nanos};
+use crate::writer::batch_writer::BatchWriter;
+
+pub struct TracingLayer {
+    config: TracingLayerConfig,
+    event_level: Level,
+    writer: Arc<BatchWriter>,
+    sampled: AtomicU64,
+    dropped: AtomicU64,
+}
+
+struct SpanTiming {
+    started: Instant,
+    timestamp: i64,
+    point: Point,
+}
+
+struct PointVisitor<'a> {
+    config: &'a TracingLayerConfig,
+    point: &'a mut Point,
+}
+
+impl TracingLayer {
+    pub fn new(influxdb_token: impl Into<Secret>, influxdb_config: InfluxdbConfig, config: TracingLayerConfig) -> Self {
+        let writer = BatchWriter::start(
+            influxdb_token,
+            influxdb_config,
+            BatchWriterConfig {
+                precision: Precision::Nanosecond,
+                ..config.batch_config.clone()
+            }
+        );
+        TracingLayer {
+            event_level: Level::from_str(&config.event_level).unwrap_or(Level::INFO),
+            config,
+            writer: Arc::new(writer),
+            sampled: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    pub fn writer(&self) -> Arc<BatchWriter> {
+        self.writer.clone()
+    }
+
+    pub fn dropped_records(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn sample(&self) -> bool {
+        let rate = self.config.sample_rate;
+        if rate >= 1.0 {
+            return true;
+        }
+        if rate <= 0.0 {
+            return false;
+        }
+        let count = self.sampled.fetch_add(1, Ordering::Relaxed) as f64;
+        ((count + 1.0) * rate).floor() > (count * rate).floor()
+    }
+
+    fn write(&self, point: &Point) {
+        let written = match to_line_protocol(point) {
+            Ok(line) => self.writer.write(&line).is_ok(),
+            Err(_) => false,
+        };
+        if !written {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn measurement(&self, name: &str) -> String {
+        self.config.measurements.get(name).cloned().unwrap_or_else(|| name.to_string())
+    }
+}
+
+impl<S> Layer<S> for TracingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attributes: &Attributes<'_>, id: &Id, context: Context<'_, S>) {
+        let metadata = attributes.metadata();
+        if is_ignored(metadata.target()) || !self.sample() {
+            return;
+        }
+        let mut timing = SpanTiming {
+            started: Instant::now(),
+            timestamp: now_nanos(),
+            point: Point::new(&self.measurement(metadata.name())),
+        };
+        attributes.record(&mut PointVisitor { config: &self.config, point: &mut timing.point });
+        if let Some(span) = context.span(id) {
+            span.extensions_mut().insert(timing);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, context: Context<'_, S>) {
+        if let Some(span) = context.span(id) {
+            if let Some(timing) = span.extensions_mut().get_mut::<SpanTiming>() {
+                values.record(&mut PointVisitor { config: &self.config, point: &mut timing.point });
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, context: Context<'_, S>) {
+        let metadata = event.metadata();
+        let measurement = match &self.config.event_measurement {
+            Some(measurement) => measurement,
+            None => return,
+        };
+        if *metadata.level() > self.event_level || is_ignored(metadata.target()) || !self.sample() {
+            return;
+        }
+        let mut point = Point::new(measurement)
+            .tag("level", &metadata.level().to_string())
+            .tag("target", metadata.target())
+            .timestamp(now_nanos());
+        if let Some(span) = context.event_span(event) {
+            point = point.tag("span", span.name());
+        }
+        event.record(&mut PointVisitor { config: &self.config, point: &mut point });
+        self.write(&point);
+    }
+
+    fn on_close(&self, id: Id, context: Context<'_, S>) {
+        let timing = match context.span(&id).and_then(|span| span.extensions_mut().remove::<SpanTiming>()) {
+            Some(timing) => timing,
+            None => return,
+        };
+        let point = timing
+            .point
+            .field("duration_ms", timing.started.elapsed().as_secs_f64() * 1000.0)
+            .timestamp(timing.timestamp);
+        self.write(&point);
+    }
+}
+
+impl PointVisitor<'_> {
+    fn record(&mut self, field: &Field, value: FieldValue, text: impl FnOnce() -> String) {
+        let name = field.name();
+        if self.config.tag_fields.iter().any(|tag| tag == name) {
+            self.point.tags.insert(name.to_string(), escape_line_breaks(&text()));
+        } else if name == "message" || self.config.value_fields.iter().any(|value| value == name) {
+            let value = match value {
+                FieldValue::String(value) => FieldValue::String(escape_line_breaks(&value)),
+                value => value,
+            };
+            self.point.fields.insert(name.to_string(), value);
+        }
+    }
+}
+
+impl Visit for PointVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record(field, FieldValue::Float(value), || value.to_string());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record(field, FieldValue::Integer(value), || value.to_string());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.record(field, FieldValue::UInteger(value), || value.to_string());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record(field, FieldValue::Boolean(value), || value.to_string());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, FieldValue::String(value.to_string()), || value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let value = format!("{:?}", value);
+        self.record(field, FieldValue::String(value.clone()), || value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use tracing_subscriber::layer::SubscriberExt;
+    use super::*;
+    use crate::mapper::line_protocol_parser::parse_line_protocol;
+    use crate::test_support::fake_influxdb::FakeInfluxDb;
+
+    fn config() -> TracingLayerConfig {
+        TracingLayerConfig {
+            tag_fields: vec!["route".to_string()],
+            value_fields: vec!["rows".to_string()],
+            batch_config: BatchWriterConfig {
+                flush_interval_ms: 60_000,
+                ..BatchWriterConfig::default()
+            },
+            ..TracingLayerConfig::default()
+        }
+    }
+
+    fn points(lines: Vec<String>) -> Vec<Point> {
+        parse_line_protocol(&lines.join("\n")).unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn records_span_durations() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let layer = TracingLayer::new("token".to_string(), fake.config("organisation", "bucket"), TracingLayerConfig {
+            measurements: BTreeMap::from([("handle_request".to_string(), "requests".to_string())]),
+            ..config()
+        });
+        let writer = layer.writer();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!(target: "app", "handle_request", route = "/users", rows = tracing::field::Empty, ignored = 1);
+            span.in_scope(|| {
+                span.record("rows", 42_i64);
+            });
+        });
+        writer.flush().await.unwrap();
+        let result = points(fake.lines("bucket"));
+        assert_eq!(1, result.len());
+        assert_eq!("requests", result[0].measurement);
+        assert_eq!(Some(&"/users".to_string()), result[0].tags.get("route"));
+        assert_eq!(Some(&FieldValue::Integer(42)), result[0].fields.get("rows"));
+        assert!(matches!(result[0].fields.get("duration_ms"), Some(FieldValue::Float(_))));
+        assert!(!result[0].fields.contains_key("ignored"));
+    }
+
+    #[actix_rt::test]
+    async fn records_selected_events() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let layer = TracingLayer::new("token".to_string(), fake.config("organisation", "bucket"), TracingLayerConfig {
+            event_measurement: Some("events".to_string()),
+            event_level: "warn".to_string(),
+            ..config()
+        });
+        let writer = layer.writer();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(target: "app", "not recorded");
+            tracing::warn!(target: "reqwest::connect", "ignored target");
+            tracing::warn!(target: "app", rows = 3_i64, "slow query");
+        });
+        writer.flush().await.unwrap();
+        let result = points(fake.lines("bucket"));
+        assert_eq!(1, result.len());
+        assert_eq!("events", result[0].measurement);
+        assert_eq!(Some(&"WARN".to_string()), result[0].tags.get("level"));
+        assert_eq!(Some(&FieldValue::String("slow query".to_string())), result[0].fields.get("message"));
+        assert_eq!(Some(&FieldValue::Integer(3)), result[0].fields.get("rows"));
+    }
+
+    #[actix_rt::test]
+    async fn escapes_multi_line_events() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let layer = TracingLayer::new("token".to_string(), fake.config("organisation", "bucket"), TracingLayerConfig {
+            event_measurement: Some("events".to_string()),
+            ..config()
+        });
+        let writer = layer.writer();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(target: "app", route = "/a\n/b", "first\nsecond");
+        });
+        writer.flush().await.unwrap();
+        let result = points(fake.lines("bucket"));
+        assert_eq!(1, result.len());
+        assert_eq!(Some(&"/a\\n/b".to_string()), result[0].tags.get("route"));
+        assert_eq!(Some(&FieldValue::String("first\\nsecond".to_string())), result[0].fields.get("message"));
+    }
+
+    #[actix_rt::test]
+    async fn counts_unwritable_points_as_dropped() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let layer = TracingLayer::new("token".to_string(), fake.config("organisation", "bucket"), config());
+        layer.write(&Point::new("work"));
+        assert_eq!(1, layer.dropped_records());
+        assert_eq!(0, fake.point_count("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn samples_spans() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let layer = TracingLayer::new("token".to_string(), fake.config("organisation", "bucket"), TracingLayerConfig {
+            sample_rate: 0.25,
+            ..config()
+        });
+        let writer = layer.writer();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..8 {
+                tracing::info_span!(target: "app", "work").in_scope(|| {});
+            }
+        });
+        writer.flush().await.unwrap();
+        assert_eq!(2, fake.point_count("bucket"));
+    }
+}
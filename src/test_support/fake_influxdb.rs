@@ -0,0 +1,728 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use actix_test::TestServer;
+use actix_web::{web, App, HttpRequest, HttpResponse};
+use log::info;
+use serde_json::json;
+use crate::mapper::line_protocol_parser::parse_line_protocol;
+use crate::mapper::time_mapper::{from_rfc3339, to_rfc3339};
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::point::{FieldValue, Point};
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum FakeFailure {
+    TooManyRequests { retry_after_seconds: u64 },
+    ServiceUnavailable,
+    PartialWrite { accepted_lines: usize },
+    SlowResponse(Duration),
+    MalformedCsv,
+}
+
+pub struct FakeInfluxDb {
+    server: TestServer,
+    state: Arc<Mutex<FakeState>>,
+}
+
+#[derive(Default)]
+struct FakeState {
+    tokens: Vec<String>,
+    users: HashMap<String, String>,
+    sessions: Vec<String>,
+    next_session: u64,
+    buckets: HashMap<String, Vec<Point>>,
+    lines: HashMap<String, Vec<String>>,
+    failures: VecDeque<FakeFailure>,
+    request_count: usize,
+}
+
+type SharedState = Arc<Mutex<FakeState>>;
+type SeriesKey = (String, BTreeMap<String, String>, String);
+
+impl FakeInfluxDb {
+    pub fn start(tokens: &[&str]) -> Self {
+        let state = Arc::new(Mutex::new(FakeState {
+            tokens: tokens.iter().map(|token| token.to_string()).collect(),
+            ..FakeState::default()
+        }));
+        let app_state = state.clone();
+        let server = actix_test::start(move || {
+            App::new()
+                .app_data(web::Data::new(app_state.clone()))
+                .app_data(web::PayloadConfig::new(32 * 1024 * 1024))
+                .route("/api/v2/write", web::post().to(write))
+                .route("/api/v2/query", web::post().to(query))
+                .route("/api/v2/buckets", web::get().to(buckets))
+                .route("/api/v2/signin", web::post().to(sign_in))
+                .route("/api/v2/signout", web::post().to(sign_out))
+                .route("/health", web::get().to(health))
+                .route("/ping", web::get().to(ping))
+        });
+        FakeInfluxDb { server, state }
+    }
+
+    pub fn address(&self) -> String {
+        format!("http://{}", self.server.addr())
+    }
+
+    pub fn config(&self, organisation: &str, bucket: &str) -> InfluxdbConfig {
+        InfluxdbConfig {
+            address: self.address(),
+            organisation: organisation.to_string(),
+            bucket: bucket.to_string(),
+            influxdb_token_path: "".to_string(),
+            limits: None,
+        }
+    }
+
+    pub fn add_user(&self, username: &str, password: &str) {
+        self.state.lock().unwrap().users.insert(username.to_string(), password.to_string());
+    }
+
+    pub fn expire_sessions(&self) {
+        self.state.lock().unwrap().sessions.clear();
+    }
+
+    pub fn session_count(&self) -> usize {
+        self.state.lock().unwrap().sessions.len()
+    }
+
+    pub fn inject_failure(&self, failure: FakeFailure) {
+        self.state.lock().unwrap().failures.push_back(failure);
+    }
+
+    pub fn lines(&self, bucket: &str) -> Vec<String> {
+        self.state.lock().unwrap().lines.get(bucket).cloned().unwrap_or_default()
+    }
+
+    pub fn points(&self, bucket: &str) -> Vec<Point> {
+        self.state.lock().unwrap().buckets.get(bucket).cloned().unwrap_or_default()
+    }
+
+    pub fn point_count(&self, bucket: &str) -> usize {
+        self.lines(bucket).len()
+    }
+
+    pub fn request_count(&self) -> usize {
+        self.state.lock().unwrap().request_count
+    }
+}
+
+async fn write(
+    request: HttpRequest,
+    parameters: web::Query<HashMap<String, String>>,
+    body: String,
+    state: web::Data<SharedState>,
+) -> HttpResponse {
+    info!("POST /api/v2/write");
+    let failure = match begin_request(&request, &state).await {
+        Ok(failure) => failure,
+        Err(response) => return response,
+    };
+    let bucket = match parameters.get("bucket") {
+        Some(bucket) if !bucket.is_empty() => bucket.clone(),
+        _ => return error_response(HttpResponse::BadRequest(), "invalid", "bucket is required"),
+    };
+    let multiplier = match precision_multiplier(parameters.get("precision").map(String::as_str)) {
+        Some(multiplier) => multiplier,
+        None => return error_response(HttpResponse::BadRequest(), "invalid", "invalid precision"),
+    };
+    let lines: Vec<&str> = body
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    let mut parsed = vec![];
+    for line in &lines {
+        let point = match parse_line_protocol(line) {
+            Ok(mut points) => {
+                let mut point = points.remove(0);
+                point.timestamp = match point.timestamp {
+                    Some(timestamp) => timestamp.checked_mul(multiplier),
+                    None => Some(now_nanos()),
+                };
+                point.timestamp.map(|_| point).ok_or_else(|| "timestamp out of range".to_string())
+            }
+            Err(error) => Err(error.reason),
+        };
+        match point {
+            Ok(point) => parsed.push((line.to_string(), point)),
+            Err(reason) => return error_response(
+                HttpResponse::BadRequest(),
+                "invalid",
+                &format!("unable to parse '{}': {}", line, reason),
+            ),
+        }
+    }
+    let accepted = match failure {
+        Some(FakeFailure::PartialWrite { accepted_lines }) => accepted_lines.min(parsed.len()),
+        _ => parsed.len(),
+    };
+    let rejected = parsed.len() - accepted;
+    let mut state = state.lock().unwrap();
+    for (line, point) in parsed.into_iter().take(accepted) {
+        state.lines.entry(bucket.clone()).or_default().push(line);
+        state.buckets.entry(bucket.clone()).or_default().push(point);
+    }
+    if rejected > 0 {
+        return error_response(
+            HttpResponse::BadRequest(),
+            "invalid",
+            &format!("partial write has occurred, {} points rejected", rejected),
+        );
+    }
+    HttpResponse::NoContent().finish()
+}
+
+async fn query(
+    request: HttpRequest,
+    body: String,
+    state: web::Data<SharedState>,
+) -> HttpResponse {
+    info!("POST /api/v2/query");
+    let failure = match begin_request(&request, &state).await {
+        Ok(failure) => failure,
+        Err(response) => return response,
+    };
+    let is_json = request
+        .headers()
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("application/json"))
+        .unwrap_or(false);
+    let flux = if is_json {
+        match serde_json::from_str::<serde_json::Value>(&body) {
+            Ok(value) => value["query"].as_str().unwrap_or_default().to_string(),
+            Err(error) => return error_response(HttpResponse::BadRequest(), "invalid", &error.to_string()),
+        }
+    } else {
+        body
+    };
+    let query = match parse_flux(&flux, now_nanos()) {
+        Ok(query) => query,
+        Err(reason) => return error_response(HttpResponse::BadRequest(), "invalid", &reason),
+    };
+    let state = state.lock().unwrap();
+    let points = match state.buckets.get(&query.bucket) {
+        Some(points) => points,
+        None => return error_response(
+            HttpResponse::NotFound(),
+            "not found",
+            &format!("could not find bucket \"{}\"", query.bucket),
+        ),
+    };
+    let mut body = to_annotated_csv(&query, points);
+    if let Some(FakeFailure::MalformedCsv) = failure {
+        body.push_str("\r\n#datatype,string,long,long\r\n,result,table,_value\r\n,,0,x\r\n");
+    }
+    HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .body(body)
+}
+
+async fn buckets(request: HttpRequest, state: web::Data<SharedState>) -> HttpResponse {
+    info!("GET /api/v2/buckets");
+    if let Err(response) = begin_request(&request, &state).await {
+        return response;
+    }
+    let state = state.lock().unwrap();
+    let mut names: Vec<&String> = state.buckets.keys().collect();
+    names.sort();
+    HttpResponse::Ok().json(json!({"buckets": names.iter().map(|name| json!({"name": name})).collect::<Vec<_>>()}))
+}
+
+async fn sign_in(request: HttpRequest, state: web::Data<SharedState>) -> HttpResponse {
+    info!("POST /api/v2/signin");
+    let mut state = state.lock().unwrap();
+    state.request_count += 1;
+    let credentials = request
+        .headers()
+        .get("Authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Basic "))
+        .unwrap_or_default();
+    let known = state
+        .users
+        .iter()
+        .any(|(username, password)| base64_encode(format!("{}:{}", username, password).as_bytes()) == credentials);
+    if !known {
+        return error_response(HttpResponse::Unauthorized(), "unauthorized", "Unauthorized");
+    }
+    state.next_session += 1;
+    let session = format!("session-{}", state.next_session);
+    state.sessions.push(session.clone());
+    HttpResponse::NoContent()
+        .insert_header(("Set-Cookie", format!("influxdb-oss-session={}; Path=/api/; HttpOnly", session)))
+        .finish()
+}
+
+async fn sign_out(request: HttpRequest, state: web::Data<SharedState>) -> HttpResponse {
+    info!("POST /api/v2/signout");
+    let mut state = state.lock().unwrap();
+    state.request_count += 1;
+    match session(&request) {
+        Some(session) if state.sessions.contains(&session) => {
+            state.sessions.retain(|known| *known != session);
+            HttpResponse::NoContent().finish()
+        }
+        _ => error_response(HttpResponse::Unauthorized(), "unauthorized", "unauthorized access"),
+    }
+}
+
+async fn health() -> HttpResponse {
+    HttpResponse::Ok().json(json!({"name": "influxdb", "message": "ready for queries and writes", "status": "pass"}))
+}
+
+async fn ping() -> HttpResponse {
+    HttpResponse::NoContent().finish()
+}
+
+async fn begin_request(request: &HttpRequest, state: &web::Data<SharedState>) -> Result<Option<FakeFailure>, HttpResponse> {
+    let failure = {
+        let mut state = state.lock().unwrap();
+        state.request_count += 1;
+        let token = request
+            .headers()
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Token "));
+        let session = session(request);
+        let authorized = token.map(|token| state.tokens.iter().any(|known| known == token)).unwrap_or(false)
+            || session.map(|session| state.sessions.contains(&session)).unwrap_or(false);
+        if !authorized {
+            return Err(error_response(HttpResponse::Unauthorized(), "unauthorized", "unauthorized access"));
+        }
+        state.failures.pop_front()
+    };
+    match failure {
+        Some(FakeFailure::TooManyRequests { retry_after_seconds }) => {
+            let mut response = HttpResponse::TooManyRequests();
+            response.insert_header(("Retry-After", retry_after_seconds.to_string()));
+            Err(error_response(response, "too many requests", "rate limit exceeded"))
+        }
+        Some(FakeFailure::ServiceUnavailable) => {
+            Err(error_response(HttpResponse::ServiceUnavailable(), "unavailable", "service unavailable"))
+        }
+        Some(FakeFailure::SlowResponse(delay)) => {
+            actix_rt::time::sleep(delay).await;
+            Ok(None)
+        }
+        failure => Ok(failure),
+    }
+}
+
+fn session(request: &HttpRequest) -> Option<String> {
+    request
+        .headers()
+        .get("Cookie")
+        .and_then(|value| value.to_str().ok())?
+        .split(';')
+        .filter_map(|cookie| cookie.trim().strip_prefix("influxdb-oss-session="))
+        .map(str::to_string)
+        .next()
+}
+
+fn base64_encode(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::new();
+    for chunk in input.chunks(3) {
+        let bytes = [chunk[0], chunk.get(1).copied().unwrap_or(0), chunk.get(2).copied().unwrap_or(0)];
+        let triple = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for index in 0..4 {
+            match index <= chunk.len() {
+                true => output.push(ALPHABET[(triple >> (18 - 6 * index) & 0x3f) as usize] as char),
+                false => output.push('='),
+            }
+        }
+    }
+    output
+}
+
+fn error_response(mut response: actix_web::HttpResponseBuilder, code: &str, message: &str) -> HttpResponse {
+    response.json(json!({"code": code, "message": message}))
+}
+
+fn now_nanos() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_nanos() as i64).unwrap_or(0)
+}
+
+fn precision_multiplier(precision: Option<&str>) -> Option<i64> {
+    match precision.unwrap_or("ns") {
+        "ns" => Some(1),
+        "us" => Some(1_000),
+        "ms" => Some(1_000_000),
+        "s" => Some(1_000_000_000),
+        _ => None,
+    }
+}
+
+#[derive(PartialEq, Debug)]
+struct FluxQuery {
+    bucket: String,
+    start: i64,
+    stop: i64,
+    filters: Vec<(String, String)>,
+}
+
+fn parse_flux(flux: &str, now: i64) -> Result<FluxQuery, String> {
+    let bucket = argument(flux, "from(", "bucket")
+        .map(|bucket| bucket.trim_matches('"').to_string())
+        .ok_or_else(|| "expected from(bucket: \"...\")".to_string())?;
+    let start = argument(flux, "range(", "start")
+        .ok_or_else(|| "expected range(start: ...)".to_string())
+        .and_then(|start| parse_time(&start, now))?;
+    let stop = match argument(flux, "range(", "stop") {
+        Some(stop) => parse_time(&stop, now)?,
+        None => now,
+    };
+    let mut filters = vec![];
+    let mut remaining = flux;
+    while let Some(index) = remaining.find("filter(") {
+        remaining = &remaining[index + "filter(".len()..];
+        let predicate = remaining
+            .split_once("=>")
+            .map(|(_, predicate)| predicate)
+            .ok_or_else(|| "expected filter(fn: (r) => ...)".to_string())?;
+        let end = predicate.find("|>").unwrap_or(predicate.len());
+        let predicate = predicate[..end].trim().trim_end_matches(')').trim();
+        if predicate.contains(" or ") {
+            return Err("only conjunctions of equality predicates are supported".to_string());
+        }
+        for clause in predicate.split(" and ") {
+            filters.push(parse_predicate(clause.trim().trim_matches(|c| c == '(' || c == ')'))?);
+        }
+    }
+    Ok(FluxQuery { bucket, start, stop, filters })
+}
+
+fn argument(flux: &str, call: &str, name: &str) -> Option<String> {
+    let arguments = &flux[flux.find(call)? + call.len()..];
+    let arguments = &arguments[..arguments.find(')')?];
+    arguments
+        .split(',')
+        .filter_map(|argument| argument.split_once(':'))
+        .find(|(key, _)| key.trim() == name)
+        .map(|(_, value)| value.trim().to_string())
+}
+
+fn parse_time(value: &str, now: i64) -> Result<i64, String> {
+    if value == "now()" {
+        return Ok(now);
+    }
+    if let Some(timestamp) = from_rfc3339(value) {
+        return Ok(timestamp);
+    }
+    if let Ok(seconds) = value.parse::<i64>() {
+        return Ok(seconds * 1_000_000_000);
+    }
+    let (sign, duration) = match value.strip_prefix('-') {
+        Some(duration) => (-1, duration),
+        None => (1, value),
+    };
+    parse_duration(duration)
+        .map(|nanos| now + sign * nanos)
+        .ok_or_else(|| format!("unsupported time '{}'", value))
+}
+
+fn parse_duration(value: &str) -> Option<i64> {
+    let mut total = 0_i64;
+    let mut remaining = value;
+    while !remaining.is_empty() {
+        let digits = remaining.bytes().take_while(u8::is_ascii_digit).count();
+        let amount: i64 = remaining[..digits].parse().ok()?;
+        remaining = &remaining[digits..];
+        let unit_length = remaining.bytes().take_while(|byte| byte.is_ascii_alphabetic()).count();
+        let unit = match &remaining[..unit_length] {
+            "ns" => 1,
+            "us" => 1_000,
+            "ms" => 1_000_000,
+            "s" => 1_000_000_000,
+            "m" => 60_000_000_000,
+            "h" => 3_600_000_000_000,
+            "d" => 86_400_000_000_000,
+            "w" => 604_800_000_000_000,
+            _ => return None,
+        };
+        remaining = &remaining[unit_length..];
+        total = total.checked_add(amount.checked_mul(unit)?)?;
+    }
+    if value.is_empty() { None } else { Some(total) }
+}
+
+fn parse_predicate(clause: &str) -> Result<(String, String), String> {
+    let (column, value) = clause
+        .split_once("==")
+        .ok_or_else(|| format!("unsupported predicate '{}'", clause))?;
+    let column = column.trim();
+    let column = column
+        .strip_prefix("r[\"")
+        .and_then(|column| column.strip_suffix("\"]"))
+        .or_else(|| column.strip_prefix("r."))
+        .ok_or_else(|| format!("unsupported predicate '{}'", clause))?;
+    let value = value.trim();
+    if value.len() < 2 || !value.starts_with('"') || !value.ends_with('"') {
+        return Err(format!("unsupported predicate '{}'", clause));
+    }
+    Ok((column.to_string(), value[1..value.len() - 1].to_string()))
+}
+
+fn column_value<'a>(point: &'a Point, field: &'a str, column: &str) -> Option<&'a str> {
+    match column {
+        "_measurement" => Some(&point.measurement),
+        "_field" => Some(field),
+        tag => point.tags.get(tag).map(String::as_str),
+    }
+}
+
+fn to_annotated_csv(query: &FluxQuery, points: &[Point]) -> String {
+    let mut series: BTreeMap<SeriesKey, Vec<(i64, &FieldValue)>> = BTreeMap::new();
+    for point in points {
+        let timestamp = point.timestamp.unwrap_or_default();
+        if timestamp < query.start || timestamp >= query.stop {
+            continue;
+        }
+        for (field, value) in &point.fields {
+            let matches = query
+                .filters
+                .iter()
+                .all(|(column, expected)| column_value(point, field, column) == Some(expected.as_str()));
+            if matches {
+                series
+                    .entry((point.measurement.clone(), point.tags.clone(), field.clone()))
+                    .or_default()
+                    .push((timestamp, value));
+            }
+        }
+    }
+    let start = to_rfc3339(query.start);
+    let stop = to_rfc3339(query.stop);
+    let mut csv = String::new();
+    for (table, ((measurement, tags, field), mut rows)) in series.into_iter().enumerate() {
+        rows.sort_by_key(|(timestamp, _)| *timestamp);
+        let tag_count = tags.len();
+        csv.push_str(&format!(
+            "#datatype,string,long,dateTime:RFC3339,dateTime:RFC3339,dateTime:RFC3339,{},string,string{}\r\n",
+            datatype(rows[0].1),
+            ",string".repeat(tag_count)
+        ));
+        csv.push_str(&format!("#group,false,false,true,true,false,false,true,true{}\r\n", ",true".repeat(tag_count)));
+        csv.push_str(&format!("#default,_result,,,,,,,{}\r\n", ",".repeat(tag_count)));
+        let mut header = ",result,table,_start,_stop,_time,_value,_field,_measurement".to_string();
+        for key in tags.keys() {
+            header.push(',');
+            header.push_str(&csv_escape(key));
+        }
+        csv.push_str(&header);
+        csv.push_str("\r\n");
+        for (timestamp, value) in rows {
+            let mut record = format!(
+                ",,{},{},{},{},{},{},{}",
+                table,
+                start,
+                stop,
+                to_rfc3339(timestamp),
+                csv_escape(&format_value(value)),
+                csv_escape(&field),
+                csv_escape(&measurement)
+            );
+            for value in tags.values() {
+                record.push(',');
+                record.push_str(&csv_escape(value));
+            }
+            csv.push_str(&record);
+            csv.push_str("\r\n");
+        }
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+fn datatype(value: &FieldValue) -> &'static str {
+    match value {
+        FieldValue::Float(_) => "double",
+        FieldValue::Integer(_) => "long",
+        FieldValue::UInteger(_) => "unsignedLong",
+        FieldValue::String(_) => "string",
+        FieldValue::Boolean(_) => "boolean",
+    }
+}
+
+fn format_value(value: &FieldValue) -> String {
+    match value {
+        FieldValue::Float(value) => value.to_string(),
+        FieldValue::Integer(value) => value.to_string(),
+        FieldValue::UInteger(value) => value.to_string(),
+        FieldValue::String(value) => value.clone(),
+        FieldValue::Boolean(value) => value.to_string(),
+    }
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::influxdb_repository::{read_from_influxdb, write_to_influxdb};
+
+    #[actix_rt::test]
+    async fn write_then_query() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let config = fake.config("organisation", "bucket");
+        let result = write_to_influxdb(
+            "token".to_string(),
+            &config,
+            "cpu,host=a usage=1.5 10\ncpu,host=b usage=2.5 20\nmem,host=a used=3i 30".to_string()
+        ).await;
+        assert!(result.is_ok());
+        assert_eq!(3, fake.point_count("bucket"));
+        let result = read_from_influxdb(
+            "token".to_string(),
+            &config,
+            r#"from(bucket: "bucket") |> range(start: 0) |> filter(fn: (r) => r._measurement == "cpu" and r["host"] == "a")"#.to_string()
+        ).await;
+        assert!(result.is_ok());
+        assert_eq!(
+            "#datatype,string,long,dateTime:RFC3339,dateTime:RFC3339,dateTime:RFC3339,double,string,string,string\r\n\
+             #group,false,false,true,true,false,false,true,true,true\r\n\
+             #default,_result,,,,,,,,\r\n\
+             ,result,table,_start,_stop,_time,_value,_field,_measurement,host\r\n",
+            result.as_ref().unwrap().split(",,0,").next().unwrap()
+        );
+        assert!(result.unwrap().contains(",1970-01-01T00:00:10Z,1.5,usage,cpu,a\r\n"));
+    }
+
+    #[actix_rt::test]
+    async fn write_rejects_unknown_token() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let result = write_to_influxdb(
+            "other".to_string(),
+            &fake.config("organisation", "bucket"),
+            "cpu usage=1.5".to_string()
+        ).await;
+        assert!(result.is_err());
+        assert_eq!(
+            r#"Rest call failed {"code":"unauthorized","message":"unauthorized access"}"#,
+            result.unwrap_err().to_string()
+        );
+        assert_eq!(0, fake.point_count("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn write_rejects_invalid_line_protocol() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let result = write_to_influxdb(
+            "token".to_string(),
+            &fake.config("organisation", "bucket"),
+            "cpu usage=".to_string()
+        ).await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("unable to parse 'cpu usage='"));
+    }
+
+    #[actix_rt::test]
+    async fn injected_failures_are_served_in_order() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let config = fake.config("organisation", "bucket");
+        fake.inject_failure(FakeFailure::TooManyRequests { retry_after_seconds: 1 });
+        fake.inject_failure(FakeFailure::ServiceUnavailable);
+        let first = write_to_influxdb("token".to_string(), &config, "cpu usage=1".to_string()).await;
+        let second = write_to_influxdb("token".to_string(), &config, "cpu usage=1".to_string()).await;
+        let third = write_to_influxdb("token".to_string(), &config, "cpu usage=1".to_string()).await;
+        assert!(first.unwrap_err().to_string().contains("too many requests"));
+        assert!(second.unwrap_err().to_string().contains("service unavailable"));
+        assert!(third.is_ok());
+        assert_eq!(1, fake.point_count("bucket"));
+        assert_eq!(3, fake.request_count());
+    }
+
+    #[actix_rt::test]
+    async fn partial_write_keeps_accepted_lines() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        fake.inject_failure(FakeFailure::PartialWrite { accepted_lines: 1 });
+        let result = write_to_influxdb(
+            "token".to_string(),
+            &fake.config("organisation", "bucket"),
+            "cpu usage=1\ncpu usage=2".to_string()
+        ).await;
+        assert!(result.unwrap_err().to_string().contains("partial write has occurred, 1 points rejected"));
+        assert_eq!(vec!["cpu usage=1".to_string()], fake.lines("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn slow_response_times_out() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        fake.inject_failure(FakeFailure::SlowResponse(Duration::from_secs(6)));
+        let result = write_to_influxdb(
+            "token".to_string(),
+            &fake.config("organisation", "bucket"),
+            "cpu usage=1".to_string()
+        ).await;
+        assert!(result.is_err());
+        assert_eq!("Rest call failed request failed", result.unwrap_err().to_string());
+    }
+
+    #[actix_rt::test]
+    async fn query_unknown_bucket() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let result = read_from_influxdb(
+            "token".to_string(),
+            &fake.config("organisation", "missing"),
+            r#"from(bucket: "missing") |> range(start: -1h)"#.to_string()
+        ).await;
+        assert!(result.unwrap_err().to_string().contains("could not find bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn write_normalises_precision() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let result = write_to_influxdb(
+            "token".to_string(),
+            &fake.config("organisation", "bucket"),
+            "cpu,host=a usage=1.5 10".to_string()
+        ).await;
+        assert!(result.is_ok());
+        assert_eq!(
+            vec![Point::new("cpu").tag("host", "a").field("usage", 1.5).timestamp(10_000_000_000)],
+            fake.points("bucket")
+        );
+    }
+
+    #[test]
+    fn base64_encodes_with_padding() {
+        assert_eq!("dXNlcjpwYXNz", base64_encode(b"user:pass"));
+        assert_eq!("YQ==", base64_encode(b"a"));
+        assert_eq!("YWI=", base64_encode(b"ab"));
+    }
+
+    #[test]
+    fn parse_flux_relative_range() {
+        let result = parse_flux(
+            r#"from(bucket: "b") |> range(start: -1h30m, stop: -30m) |> filter(fn: (r) => r._field == "usage")"#,
+            10_000_000_000_000
+        ).unwrap();
+        assert_eq!(
+            FluxQuery {
+                bucket: "b".to_string(),
+                start: 10_000_000_000_000 - 5_400_000_000_000,
+                stop: 10_000_000_000_000 - 1_800_000_000_000,
+                filters: vec![("_field".to_string(), "usage".to_string())],
+            },
+            result
+        );
+    }
+
+    #[test]
+    fn parse_flux_rejects_disjunction() {
+        let result = parse_flux(
+            r#"from(bucket: "b") |> range(start: 0) |> filter(fn: (r) => r.host == "a" or r.host == "b")"#,
+            0
+        );
+        assert!(result.is_err());
+    }
+}
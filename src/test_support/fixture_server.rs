@@ -0,0 +1,375 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use actix_test::TestServer;
+use actix_web::http::StatusCode;
+use actix_web::{web, App, HttpRequest, HttpResponse};
+use log::{error, info};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+
+const REDACTED: &str = "REDACTED";
+const SKIPPED_HEADERS: [&str; 5] = ["connection", "content-length", "date", "host", "transfer-encoding"];
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: String,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct Fixture {
+    pub interactions: Vec<Interaction>,
+}
+
+pub struct FixtureServer {
+    server: TestServer,
+    state: Arc<Mutex<FixtureState>>,
+    path: PathBuf,
+}
+
+struct FixtureState {
+    upstream: Option<String>,
+    interactions: Vec<Interaction>,
+    replayed: Vec<bool>,
+}
+
+type SharedFixtureState = Arc<Mutex<FixtureState>>;
+
+impl FixtureServer {
+    pub fn record(path: impl AsRef<Path>, upstream: &str) -> Self {
+        Self::start(path.as_ref(), Some(upstream.trim_end_matches('/').to_string()), Fixture::default())
+    }
+
+    pub fn replay(path: impl AsRef<Path>) -> io::Result<Self> {
+        let fixture: Fixture = serde_json::from_str(&fs::read_to_string(path.as_ref())?)?;
+        Ok(Self::start(path.as_ref(), None, fixture))
+    }
+
+    pub fn from_env(path: impl AsRef<Path>) -> io::Result<Self> {
+        match std::env::var("INFLUXDB_FIXTURE_MODE").as_deref() {
+            Ok("record") => {
+                let upstream = std::env::var("INFLUXDB_FIXTURE_UPSTREAM")
+                    .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "INFLUXDB_FIXTURE_UPSTREAM is not set"))?;
+                Ok(Self::record(path, &upstream))
+            }
+            _ => Self::replay(path),
+        }
+    }
+
+    fn start(path: &Path, upstream: Option<String>, fixture: Fixture) -> Self {
+        let state = Arc::new(Mutex::new(FixtureState {
+            upstream,
+            replayed: vec![false; fixture.interactions.len()],
+            interactions: fixture.interactions,
+        }));
+        let app_state = state.clone();
+        let server = actix_test::start(move || {
+            App::new()
+                .app_data(web::Data::new(app_state.clone()))
+                .app_data(web::PayloadConfig::new(32 * 1024 * 1024))
+                .default_service(web::to(handle))
+        });
+        FixtureServer { server, state, path: path.to_path_buf() }
+    }
+
+    pub fn address(&self) -> String {
+        format!("http://{}", self.server.addr())
+    }
+
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.state.lock().unwrap().interactions.clone()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let fixture = Fixture { interactions: self.interactions() };
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&fixture)?)
+    }
+}
+
+impl Drop for FixtureServer {
+    fn drop(&mut self) {
+        let recording = self.state.lock().map(|state| state.upstream.is_some()).unwrap_or(false);
+        if recording {
+            if let Err(error) = self.save() {
+                error!("Cannot save fixture {:?}: {}", self.path, error);
+            }
+        }
+    }
+}
+
+async fn handle(request: HttpRequest, body: web::Bytes, state: web::Data<SharedFixtureState>) -> HttpResponse {
+    info!("{} {}", request.method(), request.path());
+    let recorded = to_recorded_request(&request, &body);
+    let upstream = state.lock().unwrap().upstream.clone();
+    match upstream {
+        Some(upstream) => record(&request, body, recorded, &upstream, &state).await,
+        None => replay(&recorded, &state),
+    }
+}
+
+async fn record(
+    request: &HttpRequest,
+    body: web::Bytes,
+    recorded: RecordedRequest,
+    upstream: &str,
+    state: &web::Data<SharedFixtureState>,
+) -> HttpResponse {
+    let url = format!("{}{}", upstream, request.uri().path_and_query().map(|value| value.as_str()).unwrap_or("/"));
+    let method = reqwest::Method::from_bytes(request.method().as_str().as_bytes()).unwrap_or(reqwest::Method::GET);
+    let mut forwarded = reqwest::Client::new().request(method, &url).body(body.to_vec());
+    for (name, value) in request.headers() {
+        if !SKIPPED_HEADERS.contains(&name.as_str()) {
+            forwarded = forwarded.header(name.as_str(), value.as_bytes());
+        }
+    }
+    let response = match forwarded.send().await {
+        Ok(response) => response,
+        Err(error) => {
+            error!("Cannot forward to {}: {:#?}", url, error);
+            return HttpResponse::BadGateway().body(error.to_string());
+        }
+    };
+    let status = response.status().as_u16();
+    let headers = response
+        .headers()
+        .iter()
+        .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+        .map(|(name, value)| (name.as_str().to_string(), String::from_utf8_lossy(value.as_bytes()).to_string()))
+        .collect::<BTreeMap<String, String>>();
+    let body = response.text().await.unwrap_or_default();
+    let interaction = Interaction {
+        request: recorded,
+        response: RecordedResponse {
+            status,
+            headers: headers.iter().map(|(name, value)| (name.clone(), redact_header(name, value))).collect(),
+            body: redact_body(&body),
+        },
+    };
+    let reply = to_http_response(&RecordedResponse { status, headers, body });
+    let mut state = state.lock().unwrap();
+    state.interactions.push(interaction);
+    state.replayed.push(true);
+    reply
+}
+
+fn replay(recorded: &RecordedRequest, state: &web::Data<SharedFixtureState>) -> HttpResponse {
+    let mut state = state.lock().unwrap();
+    let matches: Vec<usize> = state
+        .interactions
+        .iter()
+        .enumerate()
+        .filter(|(_, interaction)| is_match(&interaction.request, recorded))
+        .map(|(index, _)| index)
+        .collect();
+    let index = matches
+        .iter()
+        .find(|index| !state.replayed[**index])
+        .or(matches.last())
+        .copied();
+    match index {
+        Some(index) => {
+            state.replayed[index] = true;
+            to_http_response(&state.interactions[index].response)
+        }
+        None => HttpResponse::NotFound().body(format!(
+            "no recorded interaction for {} {}?{}",
+            recorded.method, recorded.path, recorded.query
+        )),
+    }
+}
+
+fn is_match(expected: &RecordedRequest, actual: &RecordedRequest) -> bool {
+    expected.method == actual.method
+        && expected.path == actual.path
+        && expected.query == actual.query
+        && expected.body == actual.body
+}
+
+fn to_recorded_request(request: &HttpRequest, body: &web::Bytes) -> RecordedRequest {
+    let headers = request
+        .headers()
+        .iter()
+        .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+        .map(|(name, value)| (name.as_str().to_string(), redact_header(name.as_str(), &String::from_utf8_lossy(value.as_bytes()))))
+        .collect();
+    RecordedRequest {
+        method: request.method().to_string(),
+        path: request.path().to_string(),
+        query: request.query_string().to_string(),
+        headers,
+        body: redact_body(&String::from_utf8_lossy(body)),
+    }
+}
+
+fn to_http_response(recorded: &RecordedResponse) -> HttpResponse {
+    let status = StatusCode::from_u16(recorded.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut response = HttpResponse::build(status);
+    for (name, value) in &recorded.headers {
+        response.insert_header((name.as_str(), value.as_str()));
+    }
+    response.body(recorded.body.clone())
+}
+
+fn redact_header(name: &str, value: &str) -> String {
+    match name {
+        "authorization" => redact_credentials(value),
+        "cookie" => value.split(';').map(redact_cookie).collect::<Vec<String>>().join("; "),
+        "set-cookie" => match value.split_once(';') {
+            Some((cookie, attributes)) => format!("{};{}", redact_cookie(cookie), attributes),
+            None => redact_cookie(value),
+        },
+        _ => value.to_string(),
+    }
+}
+
+fn redact_credentials(value: &str) -> String {
+    match value.split_once(' ') {
+        Some((scheme, _)) => format!("{} {}", scheme, REDACTED),
+        None => REDACTED.to_string(),
+    }
+}
+
+fn redact_cookie(cookie: &str) -> String {
+    match cookie.trim().split_once('=') {
+        Some((name, _)) => format!("{}={}", name, REDACTED),
+        None => REDACTED.to_string(),
+    }
+}
+
+fn redact_body(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut value) if value.is_object() || value.is_array() => {
+            redact_value(&mut value);
+            value.to_string()
+        }
+        _ => body.to_string(),
+    }
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if key == "token" || key == "password" {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(value);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::influxdb_repository::write_to_influxdb;
+    use crate::repository::session_repository::InfluxDbSession;
+    use crate::test_support::fake_influxdb::FakeInfluxDb;
+    use crate::test_support::test_config::test_config;
+
+    fn fixture_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("influxdb-client-{}-{}.json", name, std::process::id()))
+    }
+
+    #[actix_rt::test]
+    async fn record_then_replay() {
+        let fake = FakeInfluxDb::start(&["secret-token"]);
+        let path = fixture_path("record_then_replay");
+        {
+            let recorder = FixtureServer::record(&path, &fake.address());
+            let config = test_config(recorder.address());
+            let result = write_to_influxdb("secret-token".to_string(), &config, "cpu usage=1".to_string()).await;
+            assert!(result.is_ok());
+            let result = write_to_influxdb("wrong-token".to_string(), &config, "cpu usage=2".to_string()).await;
+            assert!(result.is_err());
+        }
+        let saved = fs::read_to_string(&path).expect("Cannot read fixture");
+        assert!(!saved.contains("secret-token"));
+        assert!(saved.contains("Token REDACTED"));
+        drop(fake);
+
+        let replayer = FixtureServer::replay(&path).expect("Cannot replay");
+        let mut config = test_config(replayer.address());
+        let result = write_to_influxdb("any".to_string(), &config, "cpu usage=1".to_string()).await;
+        assert!(result.is_ok());
+        let result = write_to_influxdb("any".to_string(), &config, "cpu usage=2".to_string()).await;
+        assert_eq!(
+            r#"Rest call failed {"code":"unauthorized","message":"unauthorized access"}"#,
+            result.unwrap_err().to_string()
+        );
+        config.bucket = "other".to_string();
+        let result = write_to_influxdb("any".to_string(), &config, "cpu usage=1".to_string()).await;
+        assert!(result.unwrap_err().to_string().starts_with("Rest call failed no recorded interaction for POST /api/v2/write"));
+        fs::remove_file(&path).expect("Cannot remove fixture");
+    }
+
+    #[test]
+    fn redact_body_replaces_tokens() {
+        let result = redact_body(r#"{"authorizations":[{"id":"1","token":"abc"}],"password":"p"}"#);
+        assert_eq!(r#"{"authorizations":[{"id":"1","token":"REDACTED"}],"password":"REDACTED"}"#, result);
+    }
+
+    #[test]
+    fn redact_body_keeps_plain_text() {
+        assert_eq!("#datatype,string\r\n", redact_body("#datatype,string\r\n"));
+    }
+
+    #[test]
+    fn redact_header_keeps_scheme() {
+        assert_eq!("Token REDACTED", redact_header("authorization", "Token abc"));
+        assert_eq!("REDACTED", redact_header("authorization", "abc"));
+        assert_eq!("text/plain", redact_header("content-type", "text/plain"));
+    }
+
+    #[test]
+    fn redact_header_keeps_cookie_names() {
+        assert_eq!("session=REDACTED; theme=REDACTED", redact_header("cookie", "session=abc; theme=dark"));
+        assert_eq!(
+            "influxdb-oss-session=REDACTED; Path=/api/; HttpOnly",
+            redact_header("set-cookie", "influxdb-oss-session=abc; Path=/api/; HttpOnly")
+        );
+        assert_eq!("session=REDACTED", redact_header("set-cookie", "session=abc"));
+    }
+
+    #[actix_rt::test]
+    async fn record_redacts_session_cookies() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        fake.add_user("user", "password");
+        let path = fixture_path("record_redacts_session_cookies");
+        {
+            let recorder = FixtureServer::record(&path, &fake.address());
+            let session = InfluxDbSession::sign_in(&test_config(recorder.address()), "user", "password").await.unwrap();
+            session.write_to_influxdb("cpu usage=1".to_string()).await.unwrap();
+        }
+        let saved = fs::read_to_string(&path).expect("Cannot read fixture");
+        assert!(!saved.contains("session-1"));
+        assert!(saved.contains("influxdb-oss-session=REDACTED; Path=/api/; HttpOnly"));
+        assert_eq!(1, fake.point_count("bucket"));
+        fs::remove_file(&path).expect("Cannot remove fixture");
+    }
+}
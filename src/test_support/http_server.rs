@@ -1,4 +1,6 @@
-use actix_web::{Responder, HttpResponse, post, App};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use actix_web::{Responder, HttpRequest, HttpResponse, delete, get, patch, post, put, App, web};
 use actix_cors::Cors;
 use actix_test::TestServer;
 use log::{info};
@@ -57,6 +59,445 @@ pub async fn fake_write_influxdb_success() -> impl Responder {
     HttpResponse::Ok().body("test")
 }
 
+#[post("/success/api/v3/write_lp")]
+pub async fn fake_write_v3_influxdb_success() -> impl Responder {
+    info!("POST /");
+    HttpResponse::Ok().body("test")
+}
+
+#[post("/fails-body-response/api/v3/write_lp")]
+pub async fn fake_write_v3_influxdb_fails_with_body() -> impl Responder {
+    info!("POST /");
+    HttpResponse::InternalServerError().body("Some terrible error")
+}
+
+#[post("/success/api/v3/query_sql")]
+pub async fn fake_query_sql_influxdb_success(body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("POST /");
+    fake_v3_query_response(&body)
+}
+
+#[post("/success/api/v3/query_influxql")]
+pub async fn fake_query_influxql_influxdb_success(body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("POST /");
+    fake_v3_query_response(&body)
+}
+
+#[post("/fails/api/v3/query_sql")]
+pub async fn fake_query_sql_influxdb_fails() -> impl Responder {
+    info!("POST /");
+    HttpResponse::InternalServerError()
+}
+
+#[get("/success/api/v2/orgs")]
+pub async fn fake_organisations_success(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    info!("GET /");
+    let name = query.get("org").cloned().unwrap_or_else(|| "organisation".to_string());
+    HttpResponse::Ok().json(serde_json::json!({"orgs": [{"id": "org-id", "name": name}]}))
+}
+
+#[get("/missing/api/v2/orgs")]
+pub async fn fake_organisations_missing() -> impl Responder {
+    info!("GET /");
+    HttpResponse::Ok().body(r#"{"orgs":[]}"#)
+}
+
+#[get("/success/api/v2/buckets")]
+pub async fn fake_buckets_success() -> impl Responder {
+    info!("GET /");
+    HttpResponse::Ok().body(r#"{"buckets":[{"id":"bucket-id","orgID":"org-id","name":"bucket","retentionRules":[{"type":"expire","everySeconds":3600}]}]}"#)
+}
+
+#[get("/paged/api/v2/buckets")]
+pub async fn fake_buckets_paged(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    info!("GET /");
+    let count = match (query.get("limit").map(String::as_str), query.get("offset").map(String::as_str)) {
+        (Some("100"), Some("0")) => 100,
+        (Some("100"), Some("100")) => 1,
+        _ => return HttpResponse::BadRequest().body(r#"{"code":"invalid","message":"unexpected page"}"#),
+    };
+    let buckets: Vec<String> = (0..count)
+        .map(|index| format!(r#"{{"id":"bucket-{}","orgID":"org-id","name":"bucket","retentionRules":[]}}"#, index))
+        .collect();
+    HttpResponse::Ok().body(format!(r#"{{"buckets":[{}]}}"#, buckets.join(",")))
+}
+
+#[get("/paged/api/v2/{collection:checks|notificationEndpoints|notificationRules}")]
+pub async fn fake_monitoring_paged(collection: web::Path<String>, query: web::Query<HashMap<String, String>>) -> impl Responder {
+    info!("GET /");
+    let resource = match collection.as_str() {
+        "checks" => FAKE_CHECK,
+        "notificationEndpoints" => FAKE_NOTIFICATION_ENDPOINT,
+        _ => FAKE_NOTIFICATION_RULE,
+    };
+    fake_monitoring_list(&query, &collection, resource, 100)
+}
+
+#[post("/success/api/v2/buckets")]
+pub async fn fake_create_bucket_success(body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("POST /");
+    let mut bucket = body.into_inner();
+    bucket["id"] = serde_json::Value::String("new-bucket-id".to_string());
+    HttpResponse::Created().json(bucket)
+}
+
+#[delete("/success/api/v2/buckets/{id}")]
+pub async fn fake_delete_bucket_success(id: web::Path<String>) -> impl Responder {
+    info!("DELETE /");
+    DELETED_BUCKETS.lock().unwrap().push(id.into_inner());
+    HttpResponse::NoContent().finish()
+}
+
+#[get("/success/api/v2/authorizations")]
+pub async fn fake_authorizations_success() -> impl Responder {
+    info!("GET /");
+    HttpResponse::Ok().body(r#"{"authorizations":[{"id":"auth-id","orgID":"org-id","description":"writer","status":"active","token":"secret","permissions":[{"action":"write","resource":{"type":"buckets","id":"bucket-id","orgID":"org-id"}}]}]}"#)
+}
+
+#[post("/success/api/v2/authorizations")]
+pub async fn fake_create_authorization_success(body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("POST /");
+    let mut authorization = body.into_inner();
+    authorization["id"] = serde_json::Value::String("new-auth-id".to_string());
+    authorization["token"] = serde_json::Value::String("new-token".to_string());
+    HttpResponse::Created().json(authorization)
+}
+
+#[delete("/success/api/v2/authorizations/{id}")]
+pub async fn fake_delete_authorization_success() -> impl Responder {
+    info!("DELETE /");
+    HttpResponse::NoContent().finish()
+}
+
+#[get("/success/api/v2/users")]
+pub async fn fake_users_success(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    info!("GET /");
+    let users = match query.get("name").map(String::as_str) {
+        None | Some("alice") => format!("[{}]", FAKE_USER),
+        Some(_) => "[]".to_string(),
+    };
+    HttpResponse::Ok().body(format!(r#"{{"users":{}}}"#, users))
+}
+
+#[get("/paged/api/v2/users")]
+pub async fn fake_users_paged(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    info!("GET /");
+    let range = match (query.get("limit").map(String::as_str), query.get("offset").map(String::as_str)) {
+        (Some("100"), Some("0")) => 0..100,
+        (Some("100"), Some("100")) => 100..101,
+        _ => return HttpResponse::BadRequest().body(r#"{"code":"invalid","message":"unexpected page"}"#),
+    };
+    let users: Vec<String> = range
+        .map(|index| format!(r#"{{"id":"user-{}","name":"user-{}","status":"active"}}"#, index, index))
+        .collect();
+    HttpResponse::Ok().body(format!(r#"{{"users":[{}]}}"#, users.join(",")))
+}
+
+#[get("/success/api/v2/users/{id}")]
+pub async fn fake_user_success() -> impl Responder {
+    info!("GET /");
+    HttpResponse::Ok().body(FAKE_USER)
+}
+
+#[get("/success/api/v2/me")]
+pub async fn fake_me_success() -> impl Responder {
+    info!("GET /");
+    HttpResponse::Ok().body(FAKE_USER)
+}
+
+#[post("/success/api/v2/users")]
+pub async fn fake_create_user_success(body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("POST /");
+    let mut user = body.into_inner();
+    user["id"] = serde_json::Value::String("new-user-id".to_string());
+    user["status"] = serde_json::Value::String("active".to_string());
+    HttpResponse::Created().json(user)
+}
+
+#[patch("/success/api/v2/users/{id}")]
+pub async fn fake_update_user_success(id: web::Path<String>, body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("PATCH /");
+    let mut user = body.into_inner();
+    user["id"] = serde_json::Value::String(id.into_inner());
+    HttpResponse::Ok().json(user)
+}
+
+#[delete("/success/api/v2/users/{id}")]
+pub async fn fake_delete_user_success() -> impl Responder {
+    info!("DELETE /");
+    HttpResponse::NoContent().finish()
+}
+
+#[post("/success/api/v2/users/{id}/password")]
+pub async fn fake_set_password_success(body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("POST /");
+    match body["password"].as_str() {
+        Some(password) if !password.is_empty() => HttpResponse::NoContent().finish(),
+        _ => HttpResponse::BadRequest().body(r#"{"code":"invalid","message":"passwords must be at least 8 characters long"}"#),
+    }
+}
+
+#[put("/success/api/v2/me/password")]
+pub async fn fake_change_password_success(request: HttpRequest, body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("PUT /");
+    let authorization = request.headers().get("Authorization").and_then(|value| value.to_str().ok());
+    match (authorization, body["password"].as_str()) {
+        (Some("Basic YWxpY2U6b2xk"), Some(_)) => HttpResponse::NoContent().finish(),
+        _ => HttpResponse::Unauthorized().body(r#"{"code":"unauthorized","message":"your username or password is incorrect"}"#),
+    }
+}
+
+#[get("/success/api/v2/labels")]
+pub async fn fake_labels_success() -> impl Responder {
+    info!("GET /");
+    HttpResponse::Ok().body(format!(r#"{{"labels":[{}]}}"#, FAKE_LABEL))
+}
+
+#[get("/success/api/v2/labels/{id}")]
+pub async fn fake_label_success() -> impl Responder {
+    info!("GET /");
+    HttpResponse::Ok().body(format!(r#"{{"label":{}}}"#, FAKE_LABEL))
+}
+
+#[post("/success/api/v2/labels")]
+pub async fn fake_create_label_success(body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("POST /");
+    let mut label = body.into_inner();
+    label["id"] = serde_json::Value::String("new-label-id".to_string());
+    HttpResponse::Created().json(serde_json::json!({"label": label}))
+}
+
+#[patch("/success/api/v2/labels/{id}")]
+pub async fn fake_update_label_success(id: web::Path<String>, body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("PATCH /");
+    let mut label = body.into_inner();
+    label["id"] = serde_json::Value::String(id.into_inner());
+    HttpResponse::Ok().json(serde_json::json!({"label": label}))
+}
+
+#[delete("/success/api/v2/labels/{id}")]
+pub async fn fake_delete_label_success() -> impl Responder {
+    info!("DELETE /");
+    HttpResponse::NoContent().finish()
+}
+
+#[get("/success/api/v2/{resource}/{id}/labels")]
+pub async fn fake_resource_labels_success() -> impl Responder {
+    info!("GET /");
+    HttpResponse::Ok().body(format!(r#"{{"labels":[{}]}}"#, FAKE_LABEL))
+}
+
+#[post("/success/api/v2/{resource}/{id}/labels")]
+pub async fn fake_attach_label_success(body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("POST /");
+    match body["labelID"].as_str() {
+        Some("label-id") => HttpResponse::Created().body(format!(r#"{{"label":{}}}"#, FAKE_LABEL)),
+        _ => HttpResponse::NotFound().body(r#"{"code":"not found","message":"label not found"}"#),
+    }
+}
+
+#[delete("/success/api/v2/{resource}/{id}/labels/{label_id}")]
+pub async fn fake_detach_label_success() -> impl Responder {
+    info!("DELETE /");
+    HttpResponse::NoContent().finish()
+}
+
+#[get("/success/api/v2/tasks")]
+pub async fn fake_tasks_success(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    info!("GET /");
+    let tasks = match query.get("after").map(String::as_str) {
+        None => fake_labelled_page("task", 100, |index| match index {
+            0 => Some(("task-id".to_string(), "downsample".to_string())),
+            _ => None,
+        }),
+        Some("task-99") => vec![fake_labelled_resource("other-id", "cleanup", true)],
+        Some(_) => return HttpResponse::BadRequest().body(r#"{"code":"invalid","message":"unknown after"}"#),
+    };
+    HttpResponse::Ok().body(format!(r#"{{"tasks":[{}]}}"#, tasks.join(",")))
+}
+
+#[get("/success/api/v2/dashboards")]
+pub async fn fake_dashboards_success(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    info!("GET /");
+    let dashboards = match query.get("offset").map(String::as_str) {
+        Some("0") => fake_labelled_page("dashboard", 100, |index| match index {
+            0 => Some(("dashboard-0".to_string(), "dashboard-0".to_string())),
+            _ => None,
+        }),
+        Some("100") => vec![fake_labelled_resource("dashboard-100", "dashboard-100", true)],
+        _ => return HttpResponse::BadRequest().body(r#"{"code":"invalid","message":"unknown offset"}"#),
+    };
+    HttpResponse::Ok().body(format!(r#"{{"dashboards":[{}]}}"#, dashboards.join(",")))
+}
+
+#[get("/success/api/v2/checks")]
+pub async fn fake_checks_success(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    info!("GET /");
+    fake_monitoring_list(&query, "checks", FAKE_CHECK, 1)
+}
+
+#[get("/success/api/v2/checks/{id}")]
+pub async fn fake_check_success() -> impl Responder {
+    info!("GET /");
+    HttpResponse::Ok().body(FAKE_CHECK)
+}
+
+#[get("/success/api/v2/notificationEndpoints")]
+pub async fn fake_notification_endpoints_success(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    info!("GET /");
+    fake_monitoring_list(&query, "notificationEndpoints", FAKE_NOTIFICATION_ENDPOINT, 1)
+}
+
+#[get("/success/api/v2/notificationEndpoints/{id}")]
+pub async fn fake_notification_endpoint_success() -> impl Responder {
+    info!("GET /");
+    HttpResponse::Ok().body(FAKE_NOTIFICATION_ENDPOINT)
+}
+
+#[get("/success/api/v2/notificationRules")]
+pub async fn fake_notification_rules_success(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    info!("GET /");
+    fake_monitoring_list(&query, "notificationRules", FAKE_NOTIFICATION_RULE, 1)
+}
+
+#[get("/success/api/v2/notificationRules/{id}")]
+pub async fn fake_notification_rule_success() -> impl Responder {
+    info!("GET /");
+    HttpResponse::Ok().body(FAKE_NOTIFICATION_RULE)
+}
+
+#[post("/success/api/v2/{collection:checks|notificationEndpoints|notificationRules}")]
+pub async fn fake_create_monitoring_success(body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("POST /");
+    let mut resource = body.into_inner();
+    resource["id"] = serde_json::Value::String("new-id".to_string());
+    resource["status"] = serde_json::Value::String("active".to_string());
+    HttpResponse::Created().json(resource)
+}
+
+#[put("/success/api/v2/{collection:checks|notificationEndpoints|notificationRules}/{id}")]
+pub async fn fake_update_monitoring_success(path: web::Path<(String, String)>, body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("PUT /");
+    let (_, id) = path.into_inner();
+    let mut resource = body.into_inner();
+    resource["id"] = serde_json::Value::String(id);
+    HttpResponse::Ok().json(resource)
+}
+
+#[delete("/success/api/v2/{collection:checks|notificationEndpoints|notificationRules}/{id}")]
+pub async fn fake_delete_monitoring_success() -> impl Responder {
+    info!("DELETE /");
+    HttpResponse::NoContent().finish()
+}
+
+#[get("/success/api/v2/dbrps")]
+pub async fn fake_dbrps_success(query: web::Query<HashMap<String, String>>) -> impl Responder {
+    info!("GET /");
+    match (query.get("orgID").map(String::as_str), query.get("db").map(String::as_str)) {
+        (Some("org-id"), None | Some("telegraf")) => HttpResponse::Ok().body(format!(r#"{{"content":[{}]}}"#, FAKE_DBRP)),
+        (Some("org-id"), Some(_)) => HttpResponse::Ok().body(r#"{"content":[]}"#),
+        _ => HttpResponse::BadRequest().body(r#"{"code":"invalid","message":"either orgID or org must be provided"}"#),
+    }
+}
+
+#[get("/success/api/v2/dbrps/{id}")]
+pub async fn fake_dbrp_success() -> impl Responder {
+    info!("GET /");
+    HttpResponse::Ok().body(format!(r#"{{"content":{}}}"#, FAKE_DBRP))
+}
+
+#[post("/success/api/v2/dbrps")]
+pub async fn fake_create_dbrp_success(body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("POST /");
+    if body["database"] == "conflict" {
+        return HttpResponse::Conflict().body(r#"{"code":"conflict","message":"database and retention policy already mapped"}"#);
+    }
+    let mut dbrp = body.into_inner();
+    dbrp["id"] = serde_json::Value::String("new-dbrp-id".to_string());
+    HttpResponse::Created().json(dbrp)
+}
+
+#[patch("/success/api/v2/dbrps/{id}")]
+pub async fn fake_update_dbrp_success(body: web::Json<serde_json::Value>) -> impl Responder {
+    info!("PATCH /");
+    let mut dbrp: serde_json::Value = serde_json::from_str(FAKE_DBRP).unwrap();
+    if let Some(update) = body.as_object() {
+        for (key, value) in update {
+            dbrp[key] = value.clone();
+        }
+    }
+    HttpResponse::Ok().json(serde_json::json!({"content": dbrp}))
+}
+
+#[delete("/success/api/v2/dbrps/{id}")]
+pub async fn fake_delete_dbrp_success() -> impl Responder {
+    info!("DELETE /");
+    HttpResponse::NoContent().finish()
+}
+
+#[get("/success/ping")]
+pub async fn fake_ping_success() -> impl Responder {
+    info!("GET /");
+    HttpResponse::NoContent().finish()
+}
+
+#[get("/success/health")]
+pub async fn fake_health_success() -> impl Responder {
+    info!("GET /");
+    HttpResponse::Ok().body(r#"{"name":"influxdb","status":"pass"}"#)
+}
+
+static DELETED_BUCKETS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+const FAKE_USER: &str = r#"{"id":"user-id","name":"alice","status":"active"}"#;
+
+const FAKE_LABEL: &str = r##"{"id":"label-id","orgID":"org-id","name":"team:storage","properties":{"color":"#326BBA","description":"storage team"}}"##;
+
+const FAKE_CHECK: &str = r#"{"id":"check-id","orgID":"org-id","name":"cpu","type":"threshold","status":"active","query":{"text":"from(bucket: \"telegraf\")","editMode":"advanced"},"every":"1m","thresholds":[{"type":"greater","value":90.0,"level":"CRIT","allValues":false}]}"#;
+
+const FAKE_NOTIFICATION_ENDPOINT: &str = r#"{"id":"endpoint-id","orgID":"org-id","name":"alerts","type":"slack","status":"active","url":"https://hooks.slack.com/services/x"}"#;
+
+const FAKE_NOTIFICATION_RULE: &str = r##"{"id":"rule-id","orgID":"org-id","name":"critical","type":"slack","status":"active","endpointID":"endpoint-id","every":"1m","statusRules":[{"currentLevel":"CRIT","previousLevel":"OK"}],"tagRules":[],"channel":"#alerts","messageTemplate":"${ r._message }"}"##;
+
+const FAKE_DBRP: &str = r#"{"id":"dbrp-id","orgID":"org-id","bucketID":"bucket-id","database":"telegraf","retention_policy":"autogen","default":true,"virtual":false}"#;
+
+fn fake_monitoring_list(query: &HashMap<String, String>, collection: &str, resource: &str, first_page: usize) -> HttpResponse {
+    if query.get("orgID").map(String::as_str) != Some("org-id") {
+        return HttpResponse::BadRequest().body(r#"{"code":"invalid","message":"orgID is required"}"#);
+    }
+    let count = match (query.get("limit").map(String::as_str), query.get("offset").map(String::as_str)) {
+        (Some("100"), Some("0")) => first_page,
+        (Some("100"), Some("100")) if first_page == 100 => 1,
+        _ => return HttpResponse::BadRequest().body(r#"{"code":"invalid","message":"unexpected page"}"#),
+    };
+    HttpResponse::Ok().body(format!(r#"{{"{}":[{}]}}"#, collection, vec![resource; count].join(",")))
+}
+
+fn fake_labelled_page(prefix: &str, size: usize, labelled: impl Fn(usize) -> Option<(String, String)>) -> Vec<String> {
+    (0..size)
+        .map(|index| match labelled(index) {
+            Some((id, name)) => fake_labelled_resource(&id, &name, true),
+            None => fake_labelled_resource(&format!("{}-{}", prefix, index), &format!("{}-{}", prefix, index), false),
+        })
+        .collect()
+}
+
+fn fake_labelled_resource(id: &str, name: &str, labelled: bool) -> String {
+    let labels = if labelled { FAKE_LABEL } else { "" };
+    format!(r#"{{"id":"{}","name":"{}","labels":[{}]}}"#, id, name, labels)
+}
+
+fn fake_v3_query_response(body: &serde_json::Value) -> HttpResponse {
+    if body["format"] == "parquet" {
+        return HttpResponse::Ok().body("PAR1");
+    }
+    HttpResponse::Ok().body(r#"[{"host":"a","usage":1.5}]"#)
+}
+
+pub fn deleted_buckets() -> Vec<String> {
+    DELETED_BUCKETS.lock().unwrap().clone()
+}
+
 #[allow(dead_code)]
 pub fn setup_test_harness() -> TestServer {
     actix_test::start(|| {
@@ -71,5 +512,55 @@ pub fn setup_test_harness() -> TestServer {
             .service(fake_write_influxdb_fails)
             .service(fake_write_influxdb_fails_with_body)
             .service(fake_write_influxdb_success)
+            .service(fake_write_v3_influxdb_success)
+            .service(fake_write_v3_influxdb_fails_with_body)
+            .service(fake_query_sql_influxdb_success)
+            .service(fake_query_influxql_influxdb_success)
+            .service(fake_query_sql_influxdb_fails)
+            .service(fake_organisations_success)
+            .service(fake_organisations_missing)
+            .service(fake_buckets_success)
+            .service(fake_buckets_paged)
+            .service(fake_monitoring_paged)
+            .service(fake_create_bucket_success)
+            .service(fake_delete_bucket_success)
+            .service(fake_authorizations_success)
+            .service(fake_create_authorization_success)
+            .service(fake_delete_authorization_success)
+            .service(fake_users_success)
+            .service(fake_users_paged)
+            .service(fake_user_success)
+            .service(fake_me_success)
+            .service(fake_create_user_success)
+            .service(fake_update_user_success)
+            .service(fake_delete_user_success)
+            .service(fake_set_password_success)
+            .service(fake_change_password_success)
+            .service(fake_labels_success)
+            .service(fake_label_success)
+            .service(fake_create_label_success)
+            .service(fake_update_label_success)
+            .service(fake_delete_label_success)
+            .service(fake_resource_labels_success)
+            .service(fake_attach_label_success)
+            .service(fake_detach_label_success)
+            .service(fake_tasks_success)
+            .service(fake_dashboards_success)
+            .service(fake_checks_success)
+            .service(fake_check_success)
+            .service(fake_notification_endpoints_success)
+            .service(fake_notification_endpoint_success)
+            .service(fake_notification_rules_success)
+            .service(fake_notification_rule_success)
+            .service(fake_create_monitoring_success)
+            .service(fake_update_monitoring_success)
+            .service(fake_delete_monitoring_success)
+            .service(fake_dbrps_success)
+            .service(fake_dbrp_success)
+            .service(fake_create_dbrp_success)
+            .service(fake_update_dbrp_success)
+            .service(fake_delete_dbrp_success)
+            .service(fake_ping_success)
+            .service(fake_health_success)
     })
 }
@@ -1 +1,6 @@
-pub(crate) mod http_server;
\ No newline at end of file
+#[cfg(test)]
+pub(crate) mod http_server;
+pub mod fake_influxdb;
+pub mod fixture_server;
+#[cfg(test)]
+pub(crate) mod test_config;
\ No newline at end of file
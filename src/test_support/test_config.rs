@@ -0,0 +1,11 @@
+use crate::model::influxdb_config::InfluxdbConfig;
+
+pub(crate) fn test_config(address: String) -> InfluxdbConfig {
+    InfluxdbConfig {
+        address,
+        organisation: "organisation".to_string(),
+        bucket: "bucket".to_string(),
+        influxdb_token_path: "influxdb_token_path".to_string(),
+        limits: None,
+    }
+}
@@ -0,0 +1,553 @@
+use std::collections::VecDeque;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use log::{debug, error, warn};
+use reqwest::Error;
+use tokio::runtime::Builder;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{sleep, timeout, timeout_at, Instant};
+use crate::credentials::credential_provider::CredentialProvider;
+use crate::credentials::credential_request::load_credentials;
+use crate::credentials::static_credentials::StaticCredentials;
+use crate::error::influxdb_error::InfluxDbError;
+use crate::model::batch_writer_config::BatchWriterConfig;
+use crate::model::batch_writer_stats::BatchWriterStats;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::secret::Secret;
+use crate::mapper::response_mapper::WriteFailure;
+use crate::repository::influxdb_repository::send_write;
+use crate::telemetry::client_stats::record_retry;
+
+enum Command {
+    Write(String),
+    Flush(oneshot::Sender<Result<(), String>>),
+    FlushBlocking(std::sync::mpsc::Sender<Result<(), String>>),
+}
+
+pub struct BatchWriter {
+    sender: Option<mpsc::Sender<Command>>,
+    stats: Arc<Mutex<BatchWriterStats>>,
+}
+
+struct Worker {
+    credentials: Arc<dyn CredentialProvider>,
+    influxdb_config: InfluxdbConfig,
+    config: BatchWriterConfig,
+    buffer: VecDeque<String>,
+    stats: Arc<Mutex<BatchWriterStats>>,
+}
+
+impl BatchWriter {
+    pub fn start(
+        influxdb_token: impl Into<Secret>,
+        influxdb_config: InfluxdbConfig,
+        config: BatchWriterConfig
+    ) -> Self {
+        BatchWriter::start_with_credentials(Arc::new(StaticCredentials::new(influxdb_token)), influxdb_config, config)
+    }
+
+    pub fn start_with_credentials(
+        credentials: Arc<dyn CredentialProvider>,
+        influxdb_config: InfluxdbConfig,
+        config: BatchWriterConfig
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel(config.queue_capacity.max(1));
+        let stats = Arc::new(Mutex::new(BatchWriterStats::default()));
+        let worker = Worker {
+            credentials,
+            influxdb_config,
+            config,
+            buffer: VecDeque::new(),
+            stats: stats.clone(),
+        };
+        thread::spawn(move || {
+            let runtime = Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Cannot build batch writer runtime");
+            runtime.block_on(worker.run(receiver));
+        });
+        BatchWriter {
+            sender: Some(sender),
+            stats,
+        }
+    }
+
+    pub fn write(&self, body: &str) -> Result<(), InfluxDbError<Option<Error>>> {
+        let sender = self.sender.as_ref().expect("Batch writer sender missing");
+        match sender.try_send(Command::Write(body.to_string())) {
+            Ok(_) => Ok(()),
+            Err(TrySendError::Full(_)) => Err(InfluxDbError::Failed(None, "batch writer buffer is full".to_string())),
+            Err(TrySendError::Closed(_)) => Err(InfluxDbError::Failed(None, "batch writer is closed".to_string())),
+        }
+    }
+
+    pub async fn flush(&self) -> Result<(), InfluxDbError<Option<Error>>> {
+        let receiver = self.request_flush().await?;
+        await_flush(receiver).await
+    }
+
+    pub async fn shutdown(mut self, limit: Duration) -> Result<(), InfluxDbError<Option<Error>>> {
+        let result = match timeout(limit, self.flush()).await {
+            Ok(result) => result,
+            Err(_) => Err(InfluxDbError::Failed(None, "batch writer shutdown timed out".to_string())),
+        };
+        self.sender.take();
+        result
+    }
+
+    pub fn flush_blocking(&self, timeout: Duration) -> Result<(), InfluxDbError<Option<Error>>> {
+        let sender = self.sender.as_ref().expect("Batch writer sender missing");
+        let (reply, receiver) = std::sync::mpsc::channel();
+        match sender.try_send(Command::FlushBlocking(reply)) {
+            Ok(_) => {}
+            Err(TrySendError::Full(_)) => return Err(InfluxDbError::Failed(None, "batch writer buffer is full".to_string())),
+            Err(TrySendError::Closed(_)) => return Err(InfluxDbError::Failed(None, "batch writer is closed".to_string())),
+        }
+        match receiver.recv_timeout(timeout) {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(message)) => Err(InfluxDbError::Failed(None, message)),
+            Err(RecvTimeoutError::Disconnected) => Err(InfluxDbError::Failed(None, "batch writer is closed".to_string())),
+            Err(RecvTimeoutError::Timeout) => Err(InfluxDbError::Failed(None, "batch writer flush timed out".to_string())),
+        }
+    }
+
+    pub fn stats(&self) -> BatchWriterStats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    pub (crate) async fn request_flush(
+        &self
+    ) -> Result<oneshot::Receiver<Result<(), String>>, InfluxDbError<Option<Error>>> {
+        let sender = self.sender.as_ref().expect("Batch writer sender missing");
+        let (reply, receiver) = oneshot::channel();
+        sender
+            .send(Command::Flush(reply))
+            .await
+            .map_err(|_| InfluxDbError::Failed(None, "batch writer is closed".to_string()))?;
+        Ok(receiver)
+    }
+}
+
+pub (crate) async fn await_flush(
+    receiver: oneshot::Receiver<Result<(), String>>
+) -> Result<(), InfluxDbError<Option<Error>>> {
+    match receiver.await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(message)) => Err(InfluxDbError::Failed(None, message)),
+        Err(_) => Err(InfluxDbError::Failed(None, "batch writer is closed".to_string())),
+    }
+}
+
+impl Drop for BatchWriter {
+    fn drop(&mut self) {
+        self.sender.take();
+    }
+}
+
+impl Worker {
+    async fn run(mut self, mut receiver: mpsc::Receiver<Command>) {
+        let interval = Duration::from_millis(self.config.flush_interval_ms.max(1));
+        let mut deadline = Instant::now() + interval;
+        loop {
+            match timeout_at(deadline, receiver.recv()).await {
+                Ok(Some(Command::Write(body))) => {
+                    self.buffer_lines(&body);
+                    if self.buffer.len() >= self.config.batch_size.max(1) {
+                        let _ = self.send_full_batches().await;
+                    }
+                }
+                Ok(Some(Command::Flush(reply))) => {
+                    let _ = reply.send(self.send_pending().await);
+                    deadline = Instant::now() + interval;
+                }
+                Ok(Some(Command::FlushBlocking(reply))) => {
+                    let _ = reply.send(self.send_pending().await);
+                    deadline = Instant::now() + interval;
+                }
+                Ok(None) => {
+                    if let Err(error) = self.send_pending().await {
+                        error!("Batch writer closed with {} unsent lines: {}", self.buffer.len(), error);
+                    }
+                    return;
+                }
+                Err(_) => {
+                    let _ = self.send_pending().await;
+                    deadline = Instant::now() + interval;
+                }
+            }
+        }
+    }
+
+    fn buffer_lines(&mut self, body: &str) {
+        for line in body.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            self.buffer.push_back(line.to_string());
+        }
+        let overflow = self.buffer.len().saturating_sub(self.config.max_buffered_lines);
+        if overflow > 0 {
+            self.buffer.drain(..overflow);
+            warn!("Batch writer buffer full, dropped {} lines", overflow);
+        }
+        let mut stats = self.stats.lock().unwrap();
+        stats.dropped_lines += overflow as u64;
+        stats.pending_lines = self.buffer.len();
+    }
+
+    async fn send_full_batches(&mut self) -> Result<(), String> {
+        while self.buffer.len() >= self.config.batch_size.max(1) {
+            self.send_batch().await.map_err(|(message, _)| message)?;
+        }
+        Ok(())
+    }
+
+    async fn send_pending(&mut self) -> Result<(), String> {
+        let mut result = Ok(());
+        while !self.buffer.is_empty() {
+            match self.send_batch().await {
+                Ok(_) => {}
+                Err((message, true)) => return Err(message),
+                Err((message, false)) => result = Err(message),
+            }
+        }
+        result
+    }
+
+    async fn send_batch(&mut self) -> Result<(), (String, bool)> {
+        let size = self.buffer.len().min(self.config.batch_size.max(1));
+        let lines = self.buffer.iter().take(size).cloned().collect::<Vec<String>>();
+        let mut ranges = vec![(0, size)];
+        let mut resolved = 0;
+        let mut written = 0;
+        let mut result = Ok(());
+        while let Some((start, end)) = ranges.pop() {
+            match self.send_lines(&lines[start..end]).await {
+                Ok(_) => written += end - start,
+                Err((_, WriteFailure::InvalidData)) if end - start > 1 => {
+                    let middle = start + (end - start) / 2;
+                    ranges.push((middle, end));
+                    ranges.push((start, middle));
+                    continue;
+                }
+                Err((message, WriteFailure::InvalidData)) => {
+                    error!("Dropping line rejected by InfluxDB: {}", message);
+                    self.stats.lock().unwrap().dropped_lines += 1;
+                    result = Err((message, false));
+                }
+                Err((message, _)) => {
+                    warn!("Batch write failed, keeping {} lines buffered: {}", self.buffer.len() - resolved, message);
+                    result = Err((message, true));
+                    break;
+                }
+            }
+            resolved = end;
+        }
+        self.buffer.drain(..resolved);
+        let mut stats = self.stats.lock().unwrap();
+        if written > 0 {
+            debug!("Wrote batch of {} lines", written);
+            stats.written_lines += written as u64;
+            stats.written_batches += 1;
+        }
+        if let Err((message, _)) = &result {
+            stats.failed_batches += 1;
+            stats.last_error = Some(message.clone());
+        }
+        stats.pending_lines = self.buffer.len();
+        result
+    }
+
+    async fn send_lines(&self, lines: &[String]) -> Result<(), (String, WriteFailure)> {
+        let body = lines.join("\n");
+        let mut attempt = 0;
+        let mut reauthenticated = false;
+        loop {
+            let influxdb_token = load_credentials(&self.credentials).await.map_err(|error| {
+                warn!("Batch writer cannot load credentials: {}", error);
+                (error.to_string(), WriteFailure::Rejected)
+            })?;
+            let (result, failure, _) = send_write(influxdb_token, &self.influxdb_config, self.config.precision, body.clone()).await;
+            let error = match result {
+                Ok(_) => return Ok(()),
+                Err(error) => error,
+            };
+            match failure.unwrap_or(WriteFailure::Retryable(None)) {
+                WriteFailure::Retryable(retry_after) if attempt < self.config.max_retries => {
+                    let backoff = Duration::from_millis(self.config.retry_interval_ms.saturating_mul(1 << attempt.min(16)));
+                    let delay = retry_after.map(|retry_after| retry_after.max(backoff)).unwrap_or(backoff);
+                    warn!("Batch write failed, retrying in {}ms: {}", delay.as_millis(), error);
+                    self.stats.lock().unwrap().retries += 1;
+                    record_retry(&self.influxdb_config.address, attempt + 1);
+                    sleep(delay).await;
+                    attempt += 1;
+                }
+                WriteFailure::Unauthorized if !reauthenticated => {
+                    warn!("Batch write unauthorized, retrying with fresh credentials: {}", error);
+                    self.credentials.invalidate();
+                    reauthenticated = true;
+                }
+                failure => return Err((error.to_string(), failure)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::credential_error::CredentialError;
+    use crate::model::client_stats::{ClientStats, ErrorKind};
+    use crate::model::precision::Precision;
+    use crate::telemetry::client_stats::address_stats;
+    use crate::test_support::fake_influxdb::{FakeFailure, FakeInfluxDb};
+
+    fn config() -> BatchWriterConfig {
+        BatchWriterConfig {
+            batch_size: 2,
+            flush_interval_ms: 60_000,
+            queue_capacity: 100,
+            max_buffered_lines: 100,
+            max_retries: 2,
+            retry_interval_ms: 1,
+            precision: Precision::Second,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn writes_batches() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let writer = BatchWriter::start("token".to_string(), fake.config("organisation", "bucket"), config());
+        writer.write("cpu usage=1 1\ncpu usage=2 2").unwrap();
+        writer.write("cpu usage=3 3").unwrap();
+        writer.flush().await.unwrap();
+        assert_eq!(
+            vec!["cpu usage=1 1".to_string(), "cpu usage=2 2".to_string(), "cpu usage=3 3".to_string()],
+            fake.lines("bucket")
+        );
+        assert_eq!(2, fake.request_count());
+        let stats = writer.stats();
+        assert_eq!(3, stats.written_lines);
+        assert_eq!(2, stats.written_batches);
+        assert_eq!(0, stats.pending_lines);
+    }
+
+    #[actix_rt::test]
+    async fn retries_retryable_failures() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        fake.inject_failure(FakeFailure::ServiceUnavailable);
+        fake.inject_failure(FakeFailure::TooManyRequests { retry_after_seconds: 0 });
+        let before = address_stats(&fake.address()).unwrap_or_default();
+        let writer = BatchWriter::start("token".to_string(), fake.config("organisation", "bucket"), config());
+        writer.write("cpu usage=1 1").unwrap();
+        writer.flush().await.unwrap();
+        assert_eq!(vec!["cpu usage=1 1".to_string()], fake.lines("bucket"));
+        assert_eq!(2, writer.stats().retries);
+        let after = address_stats(&fake.address()).unwrap();
+        let rate_limited = |stats: &ClientStats| stats.write_failures.get(&ErrorKind::RateLimited).copied().unwrap_or_default();
+        assert_eq!(2, after.retries - before.retries);
+        assert_eq!(1, rate_limited(&after) - rate_limited(&before));
+        assert_eq!(1, after.written_points - before.written_points);
+    }
+
+    #[actix_rt::test]
+    async fn keeps_lines_when_retries_exhausted() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        for _ in 0..3 {
+            fake.inject_failure(FakeFailure::ServiceUnavailable);
+        }
+        let writer = BatchWriter::start("token".to_string(), fake.config("organisation", "bucket"), config());
+        writer.write("cpu usage=1 1").unwrap();
+        assert!(writer.flush().await.is_err());
+        let stats = writer.stats();
+        assert_eq!(1, stats.failed_batches);
+        assert_eq!(1, stats.pending_lines);
+        assert!(stats.last_error.is_some());
+        writer.flush().await.unwrap();
+        assert_eq!(vec!["cpu usage=1 1".to_string()], fake.lines("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn keeps_batches_rejected_as_unauthorized() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let writer = BatchWriter::start("wrong".to_string(), fake.config("organisation", "bucket"), config());
+        writer.write("cpu usage=1 1").unwrap();
+        assert!(writer.flush().await.unwrap_err().to_string().contains("unauthorized access"));
+        let stats = writer.stats();
+        assert_eq!(0, stats.dropped_lines);
+        assert_eq!(1, stats.pending_lines);
+        assert_eq!(2, fake.request_count());
+    }
+
+    #[actix_rt::test]
+    async fn drops_only_invalid_lines() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let writer = BatchWriter::start(
+            "token".to_string(),
+            fake.config("organisation", "bucket"),
+            BatchWriterConfig { batch_size: 10, ..config() }
+        );
+        writer.write("cpu usage=1 1\ncpu usage=2 2\ncpu usage= 3\ncpu usage=4 4").unwrap();
+        assert!(writer.flush().await.unwrap_err().to_string().contains("unable to parse 'cpu usage= 3'"));
+        assert_eq!(
+            vec!["cpu usage=1 1".to_string(), "cpu usage=2 2".to_string(), "cpu usage=4 4".to_string()],
+            fake.lines("bucket")
+        );
+        let stats = writer.stats();
+        assert_eq!(3, stats.written_lines);
+        assert_eq!(1, stats.dropped_lines);
+        assert_eq!(0, stats.pending_lines);
+    }
+
+    #[actix_rt::test]
+    async fn honours_retry_after() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        fake.inject_failure(FakeFailure::TooManyRequests { retry_after_seconds: 1 });
+        let writer = BatchWriter::start("token".to_string(), fake.config("organisation", "bucket"), config());
+        let started = std::time::Instant::now();
+        writer.write("cpu usage=1 1").unwrap();
+        writer.flush().await.unwrap();
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(1, fake.point_count("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn drops_oldest_lines_when_buffer_full() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let writer = BatchWriter::start(
+            "token".to_string(),
+            fake.config("organisation", "bucket"),
+            BatchWriterConfig { batch_size: 10, max_buffered_lines: 2, ..config() }
+        );
+        writer.write("cpu usage=1 1\ncpu usage=2 2\ncpu usage=3 3").unwrap();
+        writer.flush().await.unwrap();
+        assert_eq!(vec!["cpu usage=2 2".to_string(), "cpu usage=3 3".to_string()], fake.lines("bucket"));
+        assert_eq!(1, writer.stats().dropped_lines);
+    }
+
+    #[actix_rt::test]
+    async fn flushes_on_interval() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let writer = BatchWriter::start(
+            "token".to_string(),
+            fake.config("organisation", "bucket"),
+            BatchWriterConfig { flush_interval_ms: 10, ..config() }
+        );
+        writer.write("cpu usage=1 1").unwrap();
+        for _ in 0..100 {
+            if fake.point_count("bucket") == 1 {
+                break;
+            }
+            actix_rt::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(1, fake.point_count("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn shutdown_flushes_pending_lines() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let writer = BatchWriter::start("token".to_string(), fake.config("organisation", "bucket"), config());
+        writer.write("cpu usage=1 1").unwrap();
+        writer.shutdown(Duration::from_secs(5)).await.unwrap();
+        assert_eq!(1, fake.point_count("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn shutdown_times_out() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        fake.inject_failure(FakeFailure::SlowResponse(Duration::from_millis(500)));
+        let writer = BatchWriter::start("token".to_string(), fake.config("organisation", "bucket"), config());
+        writer.write("cpu usage=1 1").unwrap();
+        let result = writer.shutdown(Duration::from_millis(50)).await;
+        assert_eq!("Rest call failed batch writer shutdown timed out", result.unwrap_err().to_string());
+    }
+
+    #[actix_rt::test]
+    async fn flushes_in_background_on_drop() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        fake.inject_failure(FakeFailure::SlowResponse(Duration::from_millis(200)));
+        let writer = BatchWriter::start("token".to_string(), fake.config("organisation", "bucket"), config());
+        writer.write("cpu usage=1 1").unwrap();
+        let started = std::time::Instant::now();
+        drop(writer);
+        assert!(started.elapsed() < Duration::from_millis(100));
+        for _ in 0..100 {
+            if fake.point_count("bucket") == 1 {
+                break;
+            }
+            actix_rt::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(1, fake.point_count("bucket"));
+    }
+
+    #[test]
+    fn flush_blocking_waits_for_pending_lines() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let writer = BatchWriter::start("token".to_string(), fake.config("organisation", "bucket"), config());
+        writer.write("cpu usage=1 1").unwrap();
+        writer.flush_blocking(Duration::from_secs(5)).unwrap();
+        assert_eq!(1, fake.point_count("bucket"));
+        fake.inject_failure(FakeFailure::SlowResponse(Duration::from_millis(500)));
+        writer.write("cpu usage=2 2").unwrap();
+        let result = writer.flush_blocking(Duration::from_millis(50));
+        assert_eq!("Rest call failed batch writer flush timed out", result.unwrap_err().to_string());
+    }
+
+    struct RotatingCredentials {
+        tokens: Mutex<Vec<Secret>>,
+        invalidated: Mutex<usize>,
+    }
+
+    impl CredentialProvider for RotatingCredentials {
+        fn credentials(&self) -> Result<Secret, CredentialError> {
+            self.tokens
+                .lock()
+                .unwrap()
+                .last()
+                .cloned()
+                .ok_or_else(|| CredentialError::Missing("test provider".to_string()))
+        }
+
+        fn invalidate(&self) {
+            *self.invalidated.lock().unwrap() += 1;
+            self.tokens.lock().unwrap().pop();
+        }
+    }
+
+    #[actix_rt::test]
+    async fn fetches_credentials_per_batch() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let credentials = Arc::new(RotatingCredentials {
+            tokens: Mutex::new(vec!["token".into(), "expired".into()]),
+            invalidated: Mutex::new(0),
+        });
+        let writer = BatchWriter::start_with_credentials(credentials.clone(), fake.config("organisation", "bucket"), config());
+        writer.write("cpu usage=1 1").unwrap();
+        writer.flush().await.unwrap();
+        assert_eq!(1, *credentials.invalidated.lock().unwrap());
+        assert_eq!(0, writer.stats().failed_batches);
+        writer.write("cpu usage=2 2").unwrap();
+        writer.flush().await.unwrap();
+        assert_eq!(vec!["cpu usage=1 1".to_string(), "cpu usage=2 2".to_string()], fake.lines("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn keeps_lines_without_credentials() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let credentials = Arc::new(RotatingCredentials {
+            tokens: Mutex::new(vec![]),
+            invalidated: Mutex::new(0),
+        });
+        let writer = BatchWriter::start_with_credentials(credentials.clone(), fake.config("organisation", "bucket"), config());
+        writer.write("cpu usage=1 1").unwrap();
+        assert_eq!(
+            "Rest call failed No credentials found in test provider",
+            writer.flush().await.unwrap_err().to_string()
+        );
+        assert_eq!(1, writer.stats().pending_lines);
+        credentials.tokens.lock().unwrap().push("token".into());
+        writer.flush().await.unwrap();
+        assert_eq!(1, fake.point_count("bucket"));
+    }
+}
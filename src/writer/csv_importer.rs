@@ -0,0 +1,192 @@
+use std::io::{self, Read};
+use log::warn;
+use tokio::sync::mpsc;
+use crate::error::csv_import_error::CsvImportError;
+use crate::mapper::csv_import_mapper::CsvRowMapper;
+use crate::mapper::point_mapper::to_line_protocol;
+use crate::model::csv_import_config::{CsvImportConfig, RowErrorPolicy};
+use crate::model::csv_import_stats::CsvImportStats;
+use crate::model::influxdb_config::InfluxdbConfig;
+use crate::model::precision::Precision;
+use crate::model::secret::Secret;
+use crate::repository::influxdb_repository::send_write;
+
+pub async fn import_csv(
+    influxdb_token: impl Into<Secret>,
+    influxdb_config: &InfluxdbConfig,
+    import_config: &CsvImportConfig,
+    input: impl Read + Send + 'static
+) -> Result<CsvImportStats, CsvImportError> {
+    let influxdb_token = influxdb_token.into();
+    let (sender, mut receiver) = mpsc::channel::<Vec<String>>(1);
+    let parse_config = import_config.clone();
+    let parser = tokio::task::spawn_blocking(move || {
+        parse_csv(&parse_config, input, |lines| sender.blocking_send(lines).is_ok())
+    });
+    let mut stats = CsvImportStats::default();
+    while let Some(mut lines) = receiver.recv().await {
+        write_batch(&influxdb_token, influxdb_config, &mut lines, &mut stats).await?;
+    }
+    let parsed = parser.await.map_err(|error| csv::Error::from(io::Error::other(error)))??;
+    stats.rows = parsed.rows;
+    stats.skipped_rows = parsed.skipped_rows;
+    Ok(stats)
+}
+
+fn parse_csv(
+    import_config: &CsvImportConfig,
+    input: impl Read,
+    mut send: impl FnMut(Vec<String>) -> bool
+) -> Result<CsvImportStats, CsvImportError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(import_config.delimiter)
+        .from_reader(input);
+    let mut mapper = CsvRowMapper::new(import_config);
+    let mut stats = CsvImportStats::default();
+    let mut lines = vec![];
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map(|position| position.line()).unwrap_or_default();
+        if record.get(0).map(|first| first.starts_with('#')).unwrap_or(false) {
+            mapper.annotate(&record).map_err(|reason| CsvImportError::Row(line, reason))?;
+            continue;
+        }
+        if !mapper.has_header() {
+            mapper.header(&record).map_err(CsvImportError::Config)?;
+            continue;
+        }
+        stats.rows += 1;
+        match mapper.to_point(&record).and_then(|point| to_line_protocol(&point).map_err(|error| error.to_string())) {
+            Ok(point) => lines.push(point),
+            Err(reason) => match import_config.row_error_policy {
+                RowErrorPolicy::Fail => return Err(CsvImportError::Row(line, reason)),
+                RowErrorPolicy::Log => {
+                    warn!("Skipping csv row at line {}: {}", line, reason);
+                    stats.skipped_rows += 1;
+                }
+                RowErrorPolicy::Skip => stats.skipped_rows += 1,
+            },
+        }
+        if lines.len() >= import_config.batch_size.max(1) && !send(std::mem::take(&mut lines)) {
+            return Ok(stats);
+        }
+    }
+    if !lines.is_empty() {
+        send(lines);
+    }
+    Ok(stats)
+}
+
+async fn write_batch(
+    influxdb_token: &Secret,
+    influxdb_config: &InfluxdbConfig,
+    lines: &mut Vec<String>,
+    stats: &mut CsvImportStats
+) -> Result<(), CsvImportError> {
+    if lines.is_empty() {
+        return Ok(());
+    }
+    let (result, _, _) = send_write(influxdb_token.clone(), influxdb_config, Precision::Nanosecond, lines.join("\n")).await;
+    result?;
+    stats.written_points += lines.len() as u64;
+    stats.batches += 1;
+    lines.clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use super::*;
+    use crate::model::csv_import_config::{ColumnRole, FieldType, TimeFormat};
+    use crate::model::point::Point;
+    use crate::repository::influxdb_repository::{read_from_influxdb, write_to_influxdb};
+    use crate::test_support::fake_influxdb::FakeInfluxDb;
+
+    fn plain_config(row_error_policy: RowErrorPolicy) -> CsvImportConfig {
+        CsvImportConfig {
+            columns: BTreeMap::from([
+                ("name".to_string(), ColumnRole::Measurement),
+                ("host".to_string(), ColumnRole::Tag),
+                ("used".to_string(), ColumnRole::Field(FieldType::Float)),
+                ("ts".to_string(), ColumnRole::Time(TimeFormat::EpochSeconds)),
+            ]),
+            batch_size: 2,
+            row_error_policy,
+            ..CsvImportConfig::default()
+        }
+    }
+
+    #[actix_rt::test]
+    async fn imports_plain_csv_in_batches() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let input = "name,host,used,ts\nmem,a,1.5,1\nmem,b,2.5,1\nmem,a,3.5,2\n";
+        let result = import_csv("token".to_string(), &fake.config("organisation", "bucket"), &plain_config(RowErrorPolicy::Fail), input.as_bytes()).await;
+        assert_eq!(
+            CsvImportStats {
+                rows: 3,
+                written_points: 3,
+                skipped_rows: 0,
+                batches: 2,
+            },
+            result.unwrap()
+        );
+        assert_eq!(Point::new("mem").tag("host", "a").field("used", 1.5).timestamp(1_000_000_000), fake.points("bucket")[0]);
+    }
+
+    #[actix_rt::test]
+    async fn imports_annotated_csv() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let input = "#constant measurement,cpu\n#datatype tag,long,dateTime:RFC3339\nhost,count,time\na,3,2024-05-01T00:10:00Z\n";
+        let result = import_csv("token".to_string(), &fake.config("organisation", "bucket"), &CsvImportConfig::default(), input.as_bytes()).await;
+        assert_eq!(1, result.unwrap().written_points);
+        assert_eq!(vec!["cpu,host=a count=3i 1714522200000000000".to_string()], fake.lines("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn imports_flux_query_results() {
+        let source = FakeInfluxDb::start(&["token"]);
+        let lines = "cpu,host=a usage=1.5 1\ncpu,host=b usage=2.5 1\ncpu,host=a count=3i 2\ncpu state=\"ok\" 2";
+        write_to_influxdb("token".to_string(), &source.config("organisation", "source"), lines.to_string()).await.unwrap();
+        let flux = read_from_influxdb(
+            "token".to_string(),
+            &source.config("organisation", "source"),
+            r#"from(bucket: "source") |> range(start: 0)"#.to_string()
+        ).await.unwrap();
+        let result = import_csv("token".to_string(), &source.config("organisation", "target"), &CsvImportConfig::default(), io::Cursor::new(flux)).await;
+        assert_eq!(4, result.unwrap().written_points);
+        let mut expected = source.points("source");
+        let mut result = source.points("target");
+        expected.sort_by_key(|point| format!("{:?}", point));
+        result.sort_by_key(|point| format!("{:?}", point));
+        assert_eq!(expected, result);
+    }
+
+    #[actix_rt::test]
+    async fn skips_invalid_rows() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let input = "name,host,used,ts\nmem,a,x,1\nmem,b,2.5,1\n";
+        let result = import_csv("token".to_string(), &fake.config("organisation", "bucket"), &plain_config(RowErrorPolicy::Log), input.as_bytes()).await.unwrap();
+        assert_eq!(1, result.skipped_rows);
+        assert_eq!(1, fake.point_count("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn fails_on_invalid_rows() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let input = "name,host,used,ts\nmem,a,1.5,1\nmem,b,x,1\n";
+        let result = import_csv("token".to_string(), &fake.config("organisation", "bucket"), &plain_config(RowErrorPolicy::Fail), input.as_bytes()).await;
+        assert_eq!("Invalid csv row at line 3: invalid float value 'x' in column used", result.unwrap_err().to_string());
+        assert_eq!(0, fake.request_count());
+    }
+
+    #[actix_rt::test]
+    async fn reports_write_failures() {
+        let fake = FakeInfluxDb::start(&["token"]);
+        let input = "name,host,used,ts\nmem,a,1.5,1\n";
+        let result = import_csv("wrong".to_string(), &fake.config("organisation", "bucket"), &plain_config(RowErrorPolicy::Fail), input.as_bytes()).await;
+        assert!(matches!(result, Err(CsvImportError::Write(_))));
+    }
+}
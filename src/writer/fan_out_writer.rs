@@ -0,0 +1,140 @@
+use reqwest::Error;
+use crate::error::influxdb_error::InfluxDbError;
+use crate::model::batch_writer_stats::BatchWriterStats;
+use crate::model::fan_out_target::FanOutTarget;
+use crate::writer::batch_writer::{await_flush, BatchWriter};
+
+#[derive(Debug)]
+pub struct TargetResult {
+    pub target: String,
+    pub result: Result<(), InfluxDbError<Option<Error>>>,
+}
+
+pub struct FanOutWriter {
+    writers: Vec<(String, BatchWriter)>,
+}
+
+impl FanOutWriter {
+    pub fn start(targets: Vec<FanOutTarget>) -> Self {
+        let writers = targets
+            .into_iter()
+            .map(|target| {
+                let writer = BatchWriter::start(target.influxdb_token, target.influxdb_config, target.batch_config);
+                (target.name, writer)
+            })
+            .collect();
+        FanOutWriter { writers }
+    }
+
+    pub fn write(&self, body: &str) -> Vec<TargetResult> {
+        self.writers
+            .iter()
+            .map(|(name, writer)| TargetResult {
+                target: name.clone(),
+                result: writer.write(body),
+            })
+            .collect()
+    }
+
+    pub async fn flush(&self) -> Vec<TargetResult> {
+        let mut pending = vec![];
+        for (name, writer) in &self.writers {
+            pending.push((name.clone(), writer.request_flush().await));
+        }
+        let mut results = vec![];
+        for (target, receiver) in pending {
+            let result = match receiver {
+                Ok(receiver) => await_flush(receiver).await,
+                Err(error) => Err(error),
+            };
+            results.push(TargetResult { target, result });
+        }
+        results
+    }
+
+    pub fn stats(&self) -> Vec<(String, BatchWriterStats)> {
+        self.writers
+            .iter()
+            .map(|(name, writer)| (name.clone(), writer.stats()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+    use super::*;
+    use crate::model::batch_writer_config::BatchWriterConfig;
+    use crate::test_support::fake_influxdb::{FakeFailure, FakeInfluxDb};
+
+    fn target(name: &str, fake: &FakeInfluxDb) -> FanOutTarget {
+        FanOutTarget {
+            name: name.to_string(),
+            influxdb_token: "token".into(),
+            influxdb_config: fake.config("organisation", "bucket"),
+            batch_config: BatchWriterConfig {
+                flush_interval_ms: 60_000,
+                max_retries: 0,
+                retry_interval_ms: 1,
+                ..BatchWriterConfig::default()
+            },
+        }
+    }
+
+    #[actix_rt::test]
+    async fn writes_to_every_target() {
+        let on_prem = FakeInfluxDb::start(&["token"]);
+        let cloud = FakeInfluxDb::start(&["token"]);
+        let writer = FanOutWriter::start(vec![target("on-prem", &on_prem), target("cloud", &cloud)]);
+        let results = writer.write("cpu usage=1 1");
+        assert!(results.iter().all(|result| result.result.is_ok()));
+        let results = writer.flush().await;
+        assert_eq!(vec!["on-prem", "cloud"], results.iter().map(|result| result.target.as_str()).collect::<Vec<&str>>());
+        assert!(results.iter().all(|result| result.result.is_ok()));
+        assert_eq!(vec!["cpu usage=1 1".to_string()], on_prem.lines("bucket"));
+        assert_eq!(vec!["cpu usage=1 1".to_string()], cloud.lines("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn reports_failures_per_target() {
+        let on_prem = FakeInfluxDb::start(&["token"]);
+        let cloud = FakeInfluxDb::start(&["token"]);
+        cloud.inject_failure(FakeFailure::ServiceUnavailable);
+        let writer = FanOutWriter::start(vec![target("on-prem", &on_prem), target("cloud", &cloud)]);
+        writer.write("cpu usage=1 1");
+        let results = writer.flush().await;
+        assert!(results[0].result.is_ok());
+        assert!(results[1].result.is_err());
+        assert_eq!(1, on_prem.point_count("bucket"));
+        assert_eq!(0, cloud.point_count("bucket"));
+        let stats = writer.stats();
+        assert_eq!(0, stats[0].1.pending_lines);
+        assert_eq!(1, stats[1].1.pending_lines);
+        let results = writer.flush().await;
+        assert!(results.iter().all(|result| result.result.is_ok()));
+        assert_eq!(1, cloud.point_count("bucket"));
+    }
+
+    #[actix_rt::test]
+    async fn slow_target_does_not_block_others() {
+        let on_prem = FakeInfluxDb::start(&["token"]);
+        let cloud = FakeInfluxDb::start(&["token"]);
+        cloud.inject_failure(FakeFailure::SlowResponse(Duration::from_millis(500)));
+        let mut fast = target("on-prem", &on_prem);
+        fast.batch_config.flush_interval_ms = 10;
+        let mut slow = target("cloud", &cloud);
+        slow.batch_config.flush_interval_ms = 10;
+        let writer = FanOutWriter::start(vec![fast, slow]);
+        let started = Instant::now();
+        writer.write("cpu usage=1 1");
+        while on_prem.point_count("bucket") == 0 && started.elapsed() < Duration::from_secs(5) {
+            actix_rt::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(1, on_prem.point_count("bucket"));
+        assert_eq!(0, cloud.point_count("bucket"));
+        assert!(started.elapsed() < Duration::from_millis(500));
+        let results = writer.flush().await;
+        assert!(results.iter().all(|result| result.result.is_ok()));
+        assert_eq!(1, cloud.point_count("bucket"));
+    }
+}
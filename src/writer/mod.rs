@@ -0,0 +1,3 @@
+pub mod batch_writer;
+pub mod csv_importer;
+pub mod fan_out_writer;
\ No newline at end of file